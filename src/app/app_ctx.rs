@@ -6,9 +6,9 @@ use std::{
 use rust_extensions::{date_time::DateTimeAsMicroseconds, AppStates, ApplicationStates};
 
 use crate::{
-    grpc_client::PersistenceGrpcService, namespaces::NamespacesList,
+    namespaces::NamespacesList, persistence::PersistenceBackend,
     queue_subscribers::SubscriberIdGenerator, sessions::SessionsList, settings::SettingsModel,
-    utils::MultiThreadedShortString,
+    utils::MultiThreadedShortString, wal::MessagesWal,
 };
 
 use super::prometheus_metrics::PrometheusMetrics;
@@ -24,7 +24,7 @@ pub struct AppContext {
     /// Every topic lives inside a namespace. A client which names none works in the
     /// default one, which is what everybody did before namespaces existed.
     pub namespaces: NamespacesList,
-    pub persistence_client: Arc<PersistenceGrpcService>,
+    pub persistence_client: Arc<dyn PersistenceBackend>,
     pub sessions: SessionsList,
     pub subscriber_id_generator: SubscriberIdGenerator,
 
@@ -46,6 +46,17 @@ pub struct AppContext {
 
     pub settings: Arc<SettingsModel>,
 
+    /// Requests made over HTTP which wait for their correlated reply.
+    pub reply_awaiters: super::ReplyAwaiters,
+
+    pub publish_rate_limiter: super::PublishRateLimiter,
+
+    /// Failed writes to each persistence route, and when to try again.
+    pub persistence_health: super::PersistenceHealthList,
+
+    /// Messages waiting to be persisted, kept on disk as well. `None` if disabled.
+    pub wal: Option<Arc<MessagesWal>>,
+
     /// Expiry (`unix_microseconds`) of the current MCP-writes enable window.
     /// `0` means the write tools are disabled. Runtime-only — never persisted,
     /// so a restart always leaves MCP writes off.
@@ -53,12 +64,20 @@ pub struct AppContext {
 }
 
 impl AppContext {
-    pub async fn new(messages_repo: PersistenceGrpcService, settings: Arc<SettingsModel>) -> Self {
+    pub async fn new(
+        persistence_client: Arc<dyn PersistenceBackend>,
+        settings: Arc<SettingsModel>,
+    ) -> Self {
+        let wal = settings
+            .wal_path
+            .as_ref()
+            .map(|path| Arc::new(MessagesWal::new(path.clone())));
+
         Self {
             states: Arc::new(AppStates::create_un_initialized()),
-            namespaces: NamespacesList::new(),
+            namespaces: NamespacesList::new(wal.clone()),
 
-            persistence_client: Arc::new(messages_repo),
+            persistence_client,
             sessions: SessionsList::new(),
 
             subscriber_id_generator: SubscriberIdGenerator::new(),
@@ -76,7 +95,13 @@ impl AppContext {
 
             restore_page_scheduler: Default::default(),
             debug_console: super::DebugConsole::new(),
+            publish_rate_limiter: super::PublishRateLimiter::new(
+                settings.publish_rate_limits.clone(),
+            ),
             settings,
+            reply_awaiters: super::ReplyAwaiters::new(),
+            persistence_health: super::PersistenceHealthList::new(),
+            wal,
             mcp_writes_enabled_until: AtomicI64::new(0),
         }
     }
@@ -140,6 +165,12 @@ impl AppContext {
     pub fn get_default_namespace(&self) -> Arc<crate::namespaces::Namespace> {
         self.namespaces.get_default()
     }
+
+    /// Health of the persistence the namespace is routed to.
+    pub fn get_persistence_health(&self, namespace: &str) -> Arc<super::PersistenceHealth> {
+        self.persistence_health
+            .get(self.persistence_client.get_route_name(namespace).as_str())
+    }
 }
 
 impl ApplicationStates for AppContext {
@@ -6,6 +6,12 @@ pub mod shutdown;
 pub use app_ctx::AppContext;
 pub use app_ctx::APP_VERSION;
 pub use debug_console::*;
+mod reply_awaiters;
+pub use reply_awaiters::*;
+mod publish_rate_limiter;
+pub use publish_rate_limiter::*;
+mod persistence_health;
+pub use persistence_health::*;
 #[cfg(not(test))]
 mod load_subpage_scheduler;
 #[cfg(not(test))]
@@ -0,0 +1,212 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+/// Back-off after the first failed write; it doubles with every next one.
+const MIN_BACK_OFF: Duration = Duration::from_millis(500);
+
+const MAX_BACK_OFF: Duration = Duration::from_secs(30);
+
+/// Failed writes in a row after which the circuit opens: the node stops trying
+/// for `MAX_BACK_OFF`, and then lets one write through to probe persistence.
+const FAILURES_TO_OPEN_CIRCUIT: u32 = 5;
+
+#[derive(Clone, Debug)]
+pub struct PersistenceHealthSnapshot {
+    /// Name of the persistence route. Empty if the node has a single persistence.
+    pub route: String,
+    pub degraded_since: DateTimeAsMicroseconds,
+    pub failures: u32,
+    pub circuit_is_open: bool,
+    pub next_attempt: DateTimeAsMicroseconds,
+    pub last_error: String,
+}
+
+struct PersistenceHealthInner {
+    failures: u32,
+    degraded_since: Option<DateTimeAsMicroseconds>,
+    next_attempt: DateTimeAsMicroseconds,
+    last_error: String,
+}
+
+/// Keeps track of failed writes to one persistence. Messages of a failed write
+/// stay queued to persist, and this decides when the next attempt is due.
+pub struct PersistenceHealth {
+    route: String,
+    inner: Mutex<PersistenceHealthInner>,
+}
+
+impl PersistenceHealth {
+    pub fn new(route: String) -> Self {
+        Self {
+            route,
+            inner: Mutex::new(PersistenceHealthInner {
+                failures: 0,
+                degraded_since: None,
+                next_attempt: DateTimeAsMicroseconds::new(0),
+                last_error: String::new(),
+            }),
+        }
+    }
+
+    /// `false` while the node backs off after a failed write.
+    pub fn is_time_to_write(&self, now: DateTimeAsMicroseconds) -> bool {
+        let inner = self.inner.lock();
+        inner.failures == 0 || now.unix_microseconds >= inner.next_attempt.unix_microseconds
+    }
+
+    /// `true` once the back-off after a failed write is over: nothing else may
+    /// trigger the next attempt if nobody publishes.
+    pub fn is_retry_due(&self, now: DateTimeAsMicroseconds) -> bool {
+        let inner = self.inner.lock();
+        inner.failures > 0 && now.unix_microseconds >= inner.next_attempt.unix_microseconds
+    }
+
+    pub fn is_circuit_open(&self) -> bool {
+        self.inner.lock().failures >= FAILURES_TO_OPEN_CIRCUIT
+    }
+
+    pub fn get_back_off(&self) -> Duration {
+        get_back_off(self.inner.lock().failures)
+    }
+
+    pub fn write_succeeded(&self) {
+        let mut inner = self.inner.lock();
+        inner.failures = 0;
+        inner.degraded_since = None;
+        inner.last_error.clear();
+    }
+
+    pub fn write_failed(&self, now: DateTimeAsMicroseconds, err: String) {
+        let mut inner = self.inner.lock();
+        inner.failures += 1;
+
+        if inner.degraded_since.is_none() {
+            inner.degraded_since = Some(now);
+        }
+
+        inner.next_attempt = now.add(get_back_off(inner.failures));
+        inner.last_error = err;
+    }
+
+    /// `None` while persistence is healthy.
+    pub fn get_snapshot(&self) -> Option<PersistenceHealthSnapshot> {
+        let inner = self.inner.lock();
+
+        let degraded_since = inner.degraded_since?;
+
+        Some(PersistenceHealthSnapshot {
+            route: self.route.clone(),
+            degraded_since,
+            failures: inner.failures,
+            circuit_is_open: inner.failures >= FAILURES_TO_OPEN_CIRCUIT,
+            next_attempt: inner.next_attempt,
+            last_error: inner.last_error.clone(),
+        })
+    }
+}
+
+/// Health of each persistence the namespaces are routed to, by route name. One
+/// route which is down backs off on its own and leaves the others be.
+pub struct PersistenceHealthList {
+    items: Mutex<HashMap<String, Arc<PersistenceHealth>>>,
+}
+
+impl PersistenceHealthList {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, route: &str) -> Arc<PersistenceHealth> {
+        let mut items = self.items.lock();
+
+        if let Some(result) = items.get(route) {
+            return result.clone();
+        }
+
+        let result = Arc::new(PersistenceHealth::new(route.to_string()));
+        items.insert(route.to_string(), result.clone());
+        result
+    }
+
+    pub fn is_retry_due(&self, now: DateTimeAsMicroseconds) -> bool {
+        self.items.lock().values().any(|itm| itm.is_retry_due(now))
+    }
+
+    /// The route which has been degraded the longest. `None` while every one of
+    /// them is healthy.
+    pub fn get_snapshot(&self) -> Option<PersistenceHealthSnapshot> {
+        self.items
+            .lock()
+            .values()
+            .filter_map(|itm| itm.get_snapshot())
+            .min_by_key(|itm| itm.degraded_since.unix_microseconds)
+    }
+}
+
+fn get_back_off(failures: u32) -> Duration {
+    if failures == 0 {
+        return Duration::ZERO;
+    }
+
+    if failures >= FAILURES_TO_OPEN_CIRCUIT {
+        return MAX_BACK_OFF;
+    }
+
+    MIN_BACK_OFF
+        .saturating_mul(1 << (failures - 1))
+        .min(MAX_BACK_OFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    #[test]
+    fn test_back_off_grows_until_circuit_opens_and_resets_on_success() {
+        let health = PersistenceHealth::new(String::new());
+        let now = DateTimeAsMicroseconds::new(1_000_000);
+
+        assert!(health.is_time_to_write(now));
+        assert!(health.get_snapshot().is_none());
+
+        health.write_failed(now, "unavailable".to_string());
+
+        assert!(!health.is_time_to_write(now));
+        assert!(!health.is_retry_due(now));
+        assert!(health.is_retry_due(now.add(MIN_BACK_OFF)));
+        assert_eq!(MIN_BACK_OFF, health.get_back_off());
+
+        health.write_failed(now, "unavailable".to_string());
+        assert_eq!(MIN_BACK_OFF * 2, health.get_back_off());
+        assert!(!health.is_circuit_open());
+
+        for _ in 2..FAILURES_TO_OPEN_CIRCUIT {
+            health.write_failed(now, "unavailable".to_string());
+        }
+
+        assert!(health.is_circuit_open());
+        assert_eq!(MAX_BACK_OFF, health.get_back_off());
+        assert!(!health.is_time_to_write(now.add(Duration::from_secs(29))));
+
+        let snapshot = health.get_snapshot().unwrap();
+        assert_eq!(
+            now.unix_microseconds,
+            snapshot.degraded_since.unix_microseconds
+        );
+        assert_eq!(FAILURES_TO_OPEN_CIRCUIT, snapshot.failures);
+
+        health.write_succeeded();
+
+        assert!(health.is_time_to_write(now));
+        assert!(!health.is_circuit_open());
+        assert!(health.get_snapshot().is_none());
+    }
+}
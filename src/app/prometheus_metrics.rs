@@ -1,7 +1,15 @@
 use my_tcp_sockets::ThreadsStatistics;
-use prometheus::{Encoder, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
 
 use crate::messages_page::SizeMetrics;
+use crate::persistence::{CompressionStats, PersistenceTargetSnapshot};
+
+use super::PersistenceHealthList;
 
 const TCP_METRIC: &str = "tcp_metric";
 
@@ -9,11 +17,28 @@ pub struct PrometheusMetrics {
     registry: Registry,
     pub persist_queue_size: IntGaugeVec,
     pub topic_queue_size: IntGaugeVec,
+    topic_queue_dead_lettered: IntGaugeVec,
+    topic_queue_expired: IntGaugeVec,
     permanent_queues_without_subscribers: IntGauge,
     topics_without_queues: IntGauge,
     topic_data_size: IntGaugeVec,
     topic_mean_message_size: IntGaugeVec,
     topic_messages_amount: IntGaugeVec,
+    topic_dedup_keys: IntGaugeVec,
+    topic_deduplicated: IntGaugeVec,
+    topic_quota_utilisation: IntGaugeVec,
+    namespace_quota_utilisation: IntGaugeVec,
+    quota_rejected_publishes: IntCounterVec,
+    persisted_ack_latency: HistogramVec,
+    persisted_ack_timeouts: IntCounterVec,
+    persistence_write_failures: IntCounter,
+    persistence_degraded: IntGauge,
+    persistence_circuit_open: IntGauge,
+    persistence_target_healthy: IntGaugeVec,
+    persistence_target_queued_writes: IntGaugeVec,
+    persistence_target_lag: IntGaugeVec,
+    persistence_compression_ratio: IntGaugeVec,
+    persistence_compression_bytes: IntGaugeVec,
     http_connections_amount: IntGauge,
     tcp_connections: IntGaugeVec,
 }
@@ -28,6 +53,10 @@ impl PrometheusMetrics {
 
         let topic_queue_size = create_topic_queue_size();
 
+        let topic_queue_dead_lettered = create_topic_queue_dead_lettered();
+
+        let topic_queue_expired = create_topic_queue_expired();
+
         let topics_without_queues = create_topics_without_queues();
 
         let topic_data_size = create_topic_data_size();
@@ -40,6 +69,36 @@ impl PrometheusMetrics {
 
         let tcp_connections = create_tcp_connections();
 
+        let topic_dedup_keys = create_topic_dedup_keys();
+
+        let topic_deduplicated = create_topic_deduplicated();
+
+        let topic_quota_utilisation = create_topic_quota_utilisation();
+
+        let namespace_quota_utilisation = create_namespace_quota_utilisation();
+
+        let quota_rejected_publishes = create_quota_rejected_publishes();
+
+        let persisted_ack_latency = create_persisted_ack_latency();
+
+        let persisted_ack_timeouts = create_persisted_ack_timeouts();
+
+        let persistence_write_failures = create_persistence_write_failures();
+
+        let persistence_degraded = create_persistence_degraded();
+
+        let persistence_circuit_open = create_persistence_circuit_open();
+
+        let persistence_target_healthy = create_persistence_target_healthy();
+
+        let persistence_target_queued_writes = create_persistence_target_queued_writes();
+
+        let persistence_target_lag = create_persistence_target_lag();
+
+        let persistence_compression_ratio = create_persistence_compression_ratio();
+
+        let persistence_compression_bytes = create_persistence_compression_bytes();
+
         registry
             .register(Box::new(tcp_connections.clone()))
             .unwrap();
@@ -52,6 +111,14 @@ impl PrometheusMetrics {
             .register(Box::new(topic_queue_size.clone()))
             .unwrap();
 
+        registry
+            .register(Box::new(topic_queue_dead_lettered.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(topic_queue_expired.clone()))
+            .unwrap();
+
         registry
             .register(Box::new(persist_queue_size.clone()))
             .unwrap();
@@ -76,16 +143,93 @@ impl PrometheusMetrics {
             .register(Box::new(topic_mean_message_size.clone()))
             .unwrap();
 
+        registry
+            .register(Box::new(topic_dedup_keys.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(topic_deduplicated.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(topic_quota_utilisation.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(namespace_quota_utilisation.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(quota_rejected_publishes.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persisted_ack_latency.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persisted_ack_timeouts.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_write_failures.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_degraded.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_circuit_open.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_target_healthy.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_target_queued_writes.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_target_lag.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_compression_ratio.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(persistence_compression_bytes.clone()))
+            .unwrap();
+
         return Self {
             registry,
             persist_queue_size,
             topic_queue_size,
+            topic_queue_dead_lettered,
+            topic_queue_expired,
             permanent_queues_without_subscribers,
             topics_without_queues,
             topic_data_size,
             topic_messages_amount,
             http_connections_amount,
             topic_mean_message_size,
+            topic_dedup_keys,
+            topic_deduplicated,
+            topic_quota_utilisation,
+            namespace_quota_utilisation,
+            quota_rejected_publishes,
+            persisted_ack_latency,
+            persisted_ack_timeouts,
+            persistence_write_failures,
+            persistence_degraded,
+            persistence_circuit_open,
+            persistence_target_healthy,
+            persistence_target_queued_writes,
+            persistence_target_lag,
+            persistence_compression_ratio,
+            persistence_compression_bytes,
             tcp_connections,
         };
     }
@@ -102,6 +246,30 @@ impl PrometheusMetrics {
             .set(value as i64);
     }
 
+    pub fn update_topic_queue_dead_lettered(
+        &self,
+        namespace: &str,
+        topic_id: &str,
+        queue_id: &str,
+        value: usize,
+    ) {
+        self.topic_queue_dead_lettered
+            .with_label_values(&[namespace, topic_id, queue_id])
+            .set(value as i64);
+    }
+
+    pub fn update_topic_queue_expired(
+        &self,
+        namespace: &str,
+        topic_id: &str,
+        queue_id: &str,
+        value: usize,
+    ) {
+        self.topic_queue_expired
+            .with_label_values(&[namespace, topic_id, queue_id])
+            .set(value as i64);
+    }
+
     pub fn update_permanent_queues_without_subscribers(&self, value: i64) {
         self.permanent_queues_without_subscribers.set(value);
     }
@@ -133,6 +301,124 @@ impl PrometheusMetrics {
             .set(metrics.avg_message_size as i64);
     }
 
+    pub fn update_topic_dedup(
+        &self,
+        namespace: &str,
+        topic_id: &str,
+        keys: usize,
+        deduplicated: usize,
+    ) {
+        self.topic_dedup_keys
+            .with_label_values(&[namespace, topic_id])
+            .set(keys as i64);
+
+        self.topic_deduplicated
+            .with_label_values(&[namespace, topic_id])
+            .set(deduplicated as i64);
+    }
+
+    /// `utilisation` is in percent of the limit.
+    pub fn update_topic_quota_utilisation(
+        &self,
+        namespace: &str,
+        topic_id: &str,
+        quota: &str,
+        utilisation: i64,
+    ) {
+        self.topic_quota_utilisation
+            .with_label_values(&[namespace, topic_id, quota])
+            .set(utilisation);
+    }
+
+    /// `utilisation` is in percent of the limit.
+    pub fn update_namespace_quota_utilisation(
+        &self,
+        namespace: &str,
+        quota: &str,
+        utilisation: i64,
+    ) {
+        self.namespace_quota_utilisation
+            .with_label_values(&[namespace, quota])
+            .set(utilisation);
+    }
+
+    pub fn quota_rejected_publish(&self, namespace: &str, topic_id: &str, quota: &str) {
+        self.quota_rejected_publishes
+            .with_label_values(&[namespace, topic_id, quota])
+            .inc();
+    }
+
+    /// Time a publish with `persist_immediately` waited for its messages to be saved.
+    pub fn observe_persisted_ack(&self, namespace: &str, topic_id: &str, latency: Duration) {
+        self.persisted_ack_latency
+            .with_label_values(&[namespace, topic_id])
+            .observe(latency.as_secs_f64());
+    }
+
+    pub fn persisted_ack_timed_out(&self, namespace: &str, topic_id: &str) {
+        self.persisted_ack_timeouts
+            .with_label_values(&[namespace, topic_id])
+            .inc();
+    }
+
+    pub fn persistence_write_failed(&self) {
+        self.persistence_write_failures.inc();
+    }
+
+    pub fn update_persistence_health(&self, health: &PersistenceHealthList) {
+        let snapshot = health.get_snapshot();
+
+        self.persistence_degraded
+            .set(if snapshot.is_some() { 1 } else { 0 });
+
+        self.persistence_circuit_open.set(
+            if snapshot.map(|itm| itm.circuit_is_open).unwrap_or(false) {
+                1
+            } else {
+                0
+            },
+        );
+    }
+
+    pub fn update_persistence_targets(&self, targets: &[PersistenceTargetSnapshot]) {
+        for target in targets {
+            let labels = &[target.name.as_str()];
+
+            self.persistence_target_healthy
+                .with_label_values(labels)
+                .set(if target.failures == 0 { 1 } else { 0 });
+
+            self.persistence_target_queued_writes
+                .with_label_values(labels)
+                .set(target.queued_writes as i64);
+
+            self.persistence_target_lag
+                .with_label_values(labels)
+                .set(target.lag.as_millis() as i64);
+        }
+    }
+
+    pub fn update_persistence_compression(&self, stats: &CompressionStats) {
+        for (direction, size, compressed_size) in [
+            ("upload", stats.uploaded, stats.uploaded_compressed),
+            ("download", stats.downloaded, stats.downloaded_compressed),
+        ] {
+            self.persistence_compression_bytes
+                .with_label_values(&[direction, "raw"])
+                .set(size as i64);
+
+            self.persistence_compression_bytes
+                .with_label_values(&[direction, "compressed"])
+                .set(compressed_size as i64);
+
+            if size > 0 {
+                self.persistence_compression_ratio
+                    .with_label_values(&[direction])
+                    .set((compressed_size * 100 / size) as i64);
+            }
+        }
+    }
+
     pub fn build(&self) -> Vec<u8> {
         let mut buffer = vec![];
         let encoder = TextEncoder::new();
@@ -151,6 +437,14 @@ impl PrometheusMetrics {
             "Error during removing topic_queue_size from metrics for Namespace:{}, Topic:{}, Queue:{}: {:?}",
             namespace, topic_id, queue_id, result
         );
+
+        let _ = self
+            .topic_queue_dead_lettered
+            .remove_label_values(&[namespace, topic_id, queue_id]);
+
+        let _ = self
+            .topic_queue_expired
+            .remove_label_values(&[namespace, topic_id, queue_id]);
     }
 
     pub fn update_http_connections_amount(&self, amount: i64) {
@@ -197,6 +491,28 @@ fn create_topic_queue_size() -> IntGaugeVec {
     IntGaugeVec::new(gauge_opts, labels).unwrap()
 }
 
+fn create_topic_queue_dead_lettered() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "topic_queue_dead_lettered",
+        "Messages moved to the dead-letter target of the queue",
+    );
+
+    let labels = &["namespace", "topic", "queue"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_topic_queue_expired() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "topic_queue_expired",
+        "Messages the queue dropped because of the topic TTL",
+    );
+
+    let labels = &["namespace", "topic", "queue"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
 fn create_permanent_queues_without_subscribers() -> IntGauge {
     IntGauge::new(
         "permanent_queues_without_subscribers",
@@ -242,3 +558,162 @@ fn create_tcp_connections() -> IntGaugeVec {
     let labels = &[TCP_METRIC];
     IntGaugeVec::new(gauge_opts, labels).unwrap()
 }
+
+fn create_topic_dedup_keys() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "topic_dedup_keys",
+        "Message keys remembered by the publish dedup window of the topic",
+    );
+
+    let labels = &["namespace", "topic"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_topic_deduplicated() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "topic_deduplicated",
+        "Published messages dropped as duplicates by the dedup window of the topic",
+    );
+
+    let labels = &["namespace", "topic"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_topic_quota_utilisation() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "topic_quota_utilisation",
+        "Percent of the quota of the topic which is used",
+    );
+
+    let labels = &["namespace", "topic", "quota"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_namespace_quota_utilisation() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "namespace_quota_utilisation",
+        "Percent of the quota of the namespace which is used",
+    );
+
+    let labels = &["namespace", "quota"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_quota_rejected_publishes() -> IntCounterVec {
+    let counter_opts = Opts::new(
+        "quota_rejected_publishes",
+        "Publishes refused because the topic or its namespace is over a quota",
+    );
+
+    let labels = &["namespace", "topic", "quota"];
+
+    IntCounterVec::new(counter_opts, labels).unwrap()
+}
+
+fn create_persisted_ack_latency() -> HistogramVec {
+    let histogram_opts = HistogramOpts::new(
+        "publish_persisted_ack_latency",
+        "Seconds a publish with persist_immediately waits for its messages to be saved",
+    )
+    .buckets(vec![
+        0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ]);
+
+    let labels = &["namespace", "topic"];
+
+    HistogramVec::new(histogram_opts, labels).unwrap()
+}
+
+fn create_persisted_ack_timeouts() -> IntCounterVec {
+    let counter_opts = Opts::new(
+        "publish_persisted_ack_timeouts",
+        "Publishes with persist_immediately whose messages were not saved in time",
+    );
+
+    let labels = &["namespace", "topic"];
+
+    IntCounterVec::new(counter_opts, labels).unwrap()
+}
+
+fn create_persistence_write_failures() -> IntCounter {
+    IntCounter::new(
+        "persistence_write_failures",
+        "Writes to persistence which failed and are to be retried",
+    )
+    .unwrap()
+}
+
+fn create_persistence_degraded() -> IntGauge {
+    IntGauge::new(
+        "persistence_degraded",
+        "1 while the last write to persistence failed",
+    )
+    .unwrap()
+}
+
+fn create_persistence_circuit_open() -> IntGauge {
+    IntGauge::new(
+        "persistence_circuit_open",
+        "1 while the node has stopped writing to persistence after too many failures",
+    )
+    .unwrap()
+}
+
+fn create_persistence_target_healthy() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "persistence_target_healthy",
+        "1 while the last write to the persistence target succeeded",
+    );
+
+    let labels = &["target"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_persistence_target_queued_writes() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "persistence_target_queued_writes",
+        "Writes the persistence target is yet to get",
+    );
+
+    let labels = &["target"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_persistence_target_lag() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "persistence_target_lag_ms",
+        "Age of the oldest write the persistence target is yet to get",
+    );
+
+    let labels = &["target"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_persistence_compression_ratio() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "persistence_compression_ratio",
+        "Percent of their size compressed messages take on their way to or from persistence",
+    );
+
+    let labels = &["direction"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_persistence_compression_bytes() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "persistence_compression_bytes",
+        "Bytes of compressed transfers to or from persistence, before and after compression",
+    );
+
+    let labels = &["direction", "form"];
+
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
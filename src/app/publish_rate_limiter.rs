@@ -0,0 +1,314 @@
+use std::{collections::HashMap, time::Duration};
+
+use parking_lot::Mutex;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::utils::TokenBucket;
+
+/// Matches every session or topic, each of them with buckets of its own.
+pub const RATE_LIMIT_ANY: &str = "*";
+
+/// A publisher which was not throttled for this long drops out of the status.
+const THROTTLED_SHOWN_FOR: Duration = Duration::from_secs(60);
+
+/// Buckets which were not used for this long are forgotten.
+const IDLE_BUCKETS_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Copy, Debug)]
+pub enum ThrottlePolicy {
+    /// The publish waits for its tokens, unless that takes longer than `max_delay`.
+    Delay {
+        max_delay: Duration,
+    },
+    Reject,
+}
+
+/// `session_name` and `topic_id` pick what the limit applies to. Absent - the
+/// publishes of every session (topic) share one bucket; `*` - every session
+/// (topic) has a bucket of its own; a name - only that session (topic).
+#[derive(Clone, Debug)]
+pub struct PublishRateLimit {
+    pub session_name: Option<String>,
+    pub topic_id: Option<String>,
+    pub messages_per_second: Option<usize>,
+    pub bytes_per_second: Option<usize>,
+    pub policy: ThrottlePolicy,
+}
+
+impl PublishRateLimit {
+    fn get_key(
+        &self,
+        rule_no: usize,
+        session_name: &str,
+        namespace: &str,
+        topic_id: &str,
+    ) -> Option<BucketKey> {
+        let session_name = match self.session_name.as_deref() {
+            None => "",
+            Some(RATE_LIMIT_ANY) => session_name,
+            Some(expected) if expected == session_name => session_name,
+            Some(_) => return None,
+        };
+
+        let (namespace, topic_id) = match self.topic_id.as_deref() {
+            None => ("", ""),
+            Some(RATE_LIMIT_ANY) => (namespace, topic_id),
+            Some(expected) if expected == topic_id => (namespace, topic_id),
+            Some(_) => return None,
+        };
+
+        Some(BucketKey {
+            rule_no,
+            session_name: session_name.to_string(),
+            namespace: namespace.to_string(),
+            topic_id: topic_id.to_string(),
+        })
+    }
+}
+
+pub enum RateLimitResult {
+    Pass,
+    Delay(Duration),
+    Reject(String),
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct BucketKey {
+    rule_no: usize,
+    session_name: String,
+    namespace: String,
+    topic_id: String,
+}
+
+struct PublisherBuckets {
+    messages: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+    delayed: usize,
+    rejected: usize,
+    last_throttled: Option<DateTimeAsMicroseconds>,
+    last_used: DateTimeAsMicroseconds,
+}
+
+impl PublisherBuckets {
+    fn new(rule: &PublishRateLimit, now: DateTimeAsMicroseconds) -> Self {
+        Self {
+            messages: rule
+                .messages_per_second
+                .map(|per_second| TokenBucket::new(per_second, now)),
+            bytes: rule
+                .bytes_per_second
+                .map(|per_second| TokenBucket::new(per_second, now)),
+            delayed: 0,
+            rejected: 0,
+            last_throttled: None,
+            last_used: now,
+        }
+    }
+
+    fn get_wait(&mut self, messages: usize, bytes: usize, now: DateTimeAsMicroseconds) -> Duration {
+        self.last_used = now;
+
+        let mut result = Duration::ZERO;
+
+        if let Some(bucket) = self.messages.as_mut() {
+            bucket.refill(now);
+            result = result.max(bucket.get_wait(messages));
+        }
+
+        if let Some(bucket) = self.bytes.as_mut() {
+            bucket.refill(now);
+            result = result.max(bucket.get_wait(bytes));
+        }
+
+        result
+    }
+
+    fn take(&mut self, messages: usize, bytes: usize) {
+        if let Some(bucket) = self.messages.as_mut() {
+            bucket.take(messages);
+        }
+
+        if let Some(bucket) = self.bytes.as_mut() {
+            bucket.take(bytes);
+        }
+    }
+}
+
+pub struct ThrottledPublisher {
+    /// Empty if the limit is not per session.
+    pub session_name: String,
+    pub namespace: String,
+    /// Empty if the limit is not per topic.
+    pub topic_id: String,
+    pub delayed: usize,
+    pub rejected: usize,
+    pub last_throttled: DateTimeAsMicroseconds,
+}
+
+/// Token buckets of the publish rate limits from the settings. Checked before
+/// the messages get to the topic, so a throttled publisher never holds its lock.
+pub struct PublishRateLimiter {
+    limits: Vec<PublishRateLimit>,
+    buckets: Mutex<HashMap<BucketKey, PublisherBuckets>>,
+}
+
+impl PublishRateLimiter {
+    pub fn new(limits: Vec<PublishRateLimit>) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tokens are taken only if the publish goes through: a rejected one costs
+    /// the publisher nothing.
+    pub fn check(
+        &self,
+        session_name: &str,
+        namespace: &str,
+        topic_id: &str,
+        messages: usize,
+        bytes: usize,
+        now: DateTimeAsMicroseconds,
+    ) -> RateLimitResult {
+        if self.limits.is_empty() {
+            return RateLimitResult::Pass;
+        }
+
+        let mut buckets = self.buckets.lock();
+
+        let mut matched = Vec::new();
+        let mut delay = Duration::ZERO;
+
+        for (rule_no, rule) in self.limits.iter().enumerate() {
+            let Some(key) = rule.get_key(rule_no, session_name, namespace, topic_id) else {
+                continue;
+            };
+
+            let publisher = buckets
+                .entry(key.clone())
+                .or_insert_with(|| PublisherBuckets::new(rule, now));
+
+            let wait = publisher.get_wait(messages, bytes, now);
+
+            if wait > Duration::ZERO {
+                let reject = match rule.policy {
+                    ThrottlePolicy::Delay { max_delay } => wait > max_delay,
+                    ThrottlePolicy::Reject => true,
+                };
+
+                publisher.last_throttled = Some(now);
+
+                if reject {
+                    publisher.rejected += 1;
+
+                    return RateLimitResult::Reject(format!(
+                        "Publish rate limit #{} is exceeded. Retry in {:?}",
+                        rule_no, wait
+                    ));
+                }
+
+                publisher.delayed += 1;
+                delay = delay.max(wait);
+            }
+
+            matched.push(key);
+        }
+
+        for key in matched {
+            if let Some(publisher) = buckets.get_mut(&key) {
+                publisher.take(messages, bytes);
+            }
+        }
+
+        if delay > Duration::ZERO {
+            RateLimitResult::Delay(delay)
+        } else {
+            RateLimitResult::Pass
+        }
+    }
+
+    pub fn get_throttled(&self, now: DateTimeAsMicroseconds) -> Vec<ThrottledPublisher> {
+        let buckets = self.buckets.lock();
+
+        let mut result: Vec<ThrottledPublisher> = buckets
+            .iter()
+            .filter_map(|(key, publisher)| {
+                let last_throttled = publisher.last_throttled?;
+
+                if now.duration_since(last_throttled).as_positive_or_zero() > THROTTLED_SHOWN_FOR {
+                    return None;
+                }
+
+                Some(ThrottledPublisher {
+                    session_name: key.session_name.clone(),
+                    namespace: key.namespace.clone(),
+                    topic_id: key.topic_id.clone(),
+                    delayed: publisher.delayed,
+                    rejected: publisher.rejected,
+                    last_throttled,
+                })
+            })
+            .collect();
+
+        result.sort_by(|a, b| {
+            (a.session_name.as_str(), a.topic_id.as_str())
+                .cmp(&(b.session_name.as_str(), b.topic_id.as_str()))
+        });
+
+        result
+    }
+
+    pub fn gc(&self, now: DateTimeAsMicroseconds) {
+        self.buckets.lock().retain(|_, publisher| {
+            now.duration_since(publisher.last_used)
+                .as_positive_or_zero()
+                < IDLE_BUCKETS_TIMEOUT
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    #[test]
+    fn test_every_session_has_its_own_bucket() {
+        let limiter = PublishRateLimiter::new(vec![PublishRateLimit {
+            session_name: Some(RATE_LIMIT_ANY.to_string()),
+            topic_id: Some("orders".to_string()),
+            messages_per_second: Some(10),
+            bytes_per_second: None,
+            policy: ThrottlePolicy::Delay {
+                max_delay: Duration::from_millis(500),
+            },
+        }]);
+
+        let now = DateTimeAsMicroseconds::new(0);
+
+        let result = limiter.check("svc-a", "default", "orders", 10, 100, now);
+        assert!(matches!(result, RateLimitResult::Pass));
+
+        let result = limiter.check("svc-a", "default", "orders", 2, 100, now);
+        assert!(matches!(result, RateLimitResult::Delay(_)));
+
+        let result = limiter.check("svc-a", "default", "orders", 10, 100, now);
+        assert!(matches!(result, RateLimitResult::Reject(_)));
+
+        let result = limiter.check("svc-b", "default", "orders", 10, 100, now);
+        assert!(matches!(result, RateLimitResult::Pass));
+
+        let result = limiter.check("svc-a", "default", "payments", 100, 100, now);
+        assert!(matches!(result, RateLimitResult::Pass));
+
+        let throttled = limiter.get_throttled(now);
+        assert_eq!(1, throttled.len());
+        assert_eq!("svc-a", throttled[0].session_name);
+        assert_eq!(1, throttled[0].delayed);
+        assert_eq!(1, throttled[0].rejected);
+    }
+}
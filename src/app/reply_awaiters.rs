@@ -0,0 +1,171 @@
+use std::{collections::HashMap, time::Duration};
+
+use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+use parking_lot::Mutex;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, TaskCompletion, TaskCompletionAwaiter};
+
+use crate::operations::request_reply::{CORRELATION_ID_HEADER, REPLY_TO_HEADER};
+use crate::utils::get_header;
+
+pub struct ReplyMessage {
+    pub headers: SbMessageHeaders,
+    pub content: Vec<u8>,
+}
+
+/// A reply is the message with the correlation id of the request, published to
+/// the reply topic of the request in its namespace. The same correlation id in
+/// any other topic is somebody else's business.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReplyKey {
+    pub namespace: String,
+    pub reply_topic_id: String,
+    pub correlation_id: String,
+}
+
+/// Requests which wait for their reply, by [`ReplyKey`]. Works the same way
+/// the long-poll of an HTTP session does: a completion per request, resolved
+/// with `None` by the one second tick once its time is out.
+pub struct ReplyAwaiters {
+    pending: Mutex<
+        HashMap<
+            ReplyKey,
+            (
+                TaskCompletion<Option<ReplyMessage>, String>,
+                DateTimeAsMicroseconds,
+            ),
+        >,
+    >,
+}
+
+impl ReplyAwaiters {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn engage(
+        &self,
+        key: ReplyKey,
+        timeout: Duration,
+    ) -> Result<TaskCompletionAwaiter<Option<ReplyMessage>, String>, String> {
+        let mut write_access = self.pending.lock();
+
+        if write_access.contains_key(&key) {
+            return Err(format!(
+                "Request with correlation id '{}' is already awaiting its reply",
+                key.correlation_id
+            ));
+        }
+
+        let mut task_completion = TaskCompletion::new();
+        let awaiter = task_completion.get_awaiter();
+
+        let expires_at = DateTimeAsMicroseconds::now().add(timeout);
+        write_access.insert(key, (task_completion, expires_at));
+
+        Ok(awaiter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.lock().is_empty()
+    }
+
+    /// Gives up waiting without a reply.
+    pub fn cancel(&self, key: &ReplyKey) {
+        if let Some((mut task_completion, _)) = self.pending.lock().remove(key) {
+            task_completion.set_ok(None);
+        }
+    }
+
+    /// Hands every message published to the topic, carrying a correlation id
+    /// somebody waits for there, to its request. The message is published as
+    /// usual all the same.
+    ///
+    /// A message with a `reply-to` header is a request itself, never a reply.
+    pub fn complete_replies(&self, namespace: &str, topic_id: &str, messages: &[MessageToPublish]) {
+        let mut write_access = self.pending.lock();
+
+        if write_access.is_empty() {
+            return;
+        }
+
+        for msg in messages {
+            if get_header(&msg.headers, REPLY_TO_HEADER).is_some() {
+                continue;
+            }
+
+            let Some(correlation_id) = get_header(&msg.headers, CORRELATION_ID_HEADER) else {
+                continue;
+            };
+
+            let key = ReplyKey {
+                namespace: namespace.to_string(),
+                reply_topic_id: topic_id.to_string(),
+                correlation_id: correlation_id.to_string(),
+            };
+
+            if let Some((mut task_completion, _)) = write_access.remove(&key) {
+                task_completion.set_ok(Some(ReplyMessage {
+                    headers: msg.headers.clone(),
+                    content: msg.content.clone(),
+                }));
+            }
+        }
+    }
+
+    pub fn one_second_tick(&self, now: DateTimeAsMicroseconds) {
+        let mut write_access = self.pending.lock();
+
+        let expired: Vec<ReplyKey> = write_access
+            .iter()
+            .filter(|(_, (_, expires_at))| expires_at.unix_microseconds <= now.unix_microseconds)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in expired {
+            if let Some((mut task_completion, _)) = write_access.remove(&key) {
+                task_completion.set_ok(None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    use super::{ReplyAwaiters, ReplyKey};
+    use crate::operations::request_reply::CORRELATION_ID_HEADER;
+
+    #[test]
+    fn test_reply_is_matched_in_its_namespace_and_reply_topic_only() {
+        let awaiters = ReplyAwaiters::new();
+
+        let _awaiter = awaiters
+            .engage(
+                ReplyKey {
+                    namespace: "ns-a".to_string(),
+                    reply_topic_id: "replies".to_string(),
+                    correlation_id: "req-1".to_string(),
+                },
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        let reply = vec![MessageToPublish {
+            headers: SbMessageHeaders::new()
+                .add(CORRELATION_ID_HEADER.to_string(), "req-1".to_string()),
+            content: vec![1u8],
+        }];
+
+        awaiters.complete_replies("ns-b", "replies", &reply);
+        awaiters.complete_replies("ns-a", "other-topic", &reply);
+        assert!(!awaiters.is_empty());
+
+        awaiters.complete_replies("ns-a", "replies", &reply);
+        assert!(awaiters.is_empty());
+    }
+}
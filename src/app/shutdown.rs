@@ -1,35 +1,85 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use super::AppContext;
 
+/// How long the shutdown keeps trying a persistence which does not answer.
+const FORCE_PERSIST_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub async fn execute(app: Arc<AppContext>) {
     empty_persistence_queues(app.clone()).await;
     make_last_topics_and_queues_persist(app.clone()).await;
 }
 
+/// Messages which are still not persisted once the time is out are left to the
+/// write-ahead log, if there is one; without it they are lost.
 async fn empty_persistence_queues(app: Arc<AppContext>) {
+    let deadline = DateTimeAsMicroseconds::now().add(FORCE_PERSIST_TIMEOUT);
+
     for namespace in app.namespaces.get_all().iter() {
         let topics = namespace.topic_list.get_all();
 
         for topic in topics.iter() {
-            let metrics = topic.get_topic_size_metrics();
+            loop {
+                let persist_size = topic.get_topic_size_metrics().persist_size;
+
+                if persist_size == 0 {
+                    break;
+                }
+
+                let now = DateTimeAsMicroseconds::now();
+
+                if now.unix_microseconds >= deadline.unix_microseconds {
+                    let kept_by = if app.settings.wal_path.is_some() {
+                        "They are kept in the write-ahead log"
+                    } else {
+                        "There is no write-ahead log: they are lost"
+                    };
+
+                    println!(
+                        "Topic {}/{} still has {} messages to persist, but the time for Force Persist is out. {}",
+                        namespace.name.as_str(),
+                        topic.topic_id.as_str(),
+                        persist_size,
+                        kept_by
+                    );
+                    break;
+                }
 
-            while metrics.persist_size > 0 {
                 println!(
                     "Topic {}/{} has {} messages to persist. Doing Force Persist",
                     namespace.name.as_str(),
                     topic.topic_id.as_str(),
-                    metrics.persist_size
+                    persist_size
                 );
 
-                crate::operations::persist_topic_messages(&app, &topic).await;
+                if let Err(err) = crate::operations::persist_topic_messages(&app, &topic).await {
+                    let time_left = deadline.duration_since(now).as_positive_or_zero();
+                    let back_off = app
+                        .get_persistence_health(namespace.name.as_str())
+                        .get_back_off()
+                        .min(time_left);
+
+                    println!(
+                        "Force Persist of topic {}/{} failed: {:?}. Retrying in {:?}",
+                        namespace.name.as_str(),
+                        topic.topic_id.as_str(),
+                        err,
+                        back_off
+                    );
+
+                    tokio::time::sleep(back_off).await;
+                }
             }
 
-            println!(
-                "Topic {}/{} has no messages to persist.",
-                namespace.name.as_str(),
-                topic.topic_id.as_str()
-            );
+            if topic.get_topic_size_metrics().persist_size == 0 {
+                println!(
+                    "Topic {}/{} has no messages to persist.",
+                    namespace.name.as_str(),
+                    topic.topic_id.as_str()
+                );
+            }
         }
     }
 }
@@ -4,7 +4,7 @@ use my_http_server::HttpConnectionsCounter;
 use my_tcp_sockets::ThreadsStatistics;
 use rust_extensions::{MyTimerTick, RepeatTimerIteration};
 
-use crate::app::AppContext;
+use crate::{app::AppContext, quotas::QuotaUsage};
 
 pub struct MetricsTimer {
     app: Arc<AppContext>,
@@ -31,15 +31,34 @@ impl MyTimerTick for MetricsTimer {
     async fn tick(&self) -> RepeatTimerIteration {
         self.app.sessions.one_second_tick().await;
 
+        let now = rust_extensions::date_time::DateTimeAsMicroseconds::now();
+
+        self.app.reply_awaiters.one_second_tick(now);
+
+        self.app.publish_rate_limiter.gc(now);
+
+        if self.app.persistence_health.is_retry_due(now) {
+            self.app.persist_executor.trigger();
+        }
+
         self.app
             .prometheus
             .update_tcp_threads(&self.threads_statistics);
 
+        self.app
+            .prometheus
+            .update_persistence_targets(&self.app.persistence_client.get_targets());
+
+        self.app
+            .prometheus
+            .update_persistence_compression(&self.app.persistence_client.get_compression_stats());
+
         let mut permanent_queues_without_subscribers = 0;
         let mut topics_without_queues = 0;
 
         for namespace in self.app.namespaces.get_all().iter() {
             let namespace_name = namespace.name.as_str();
+            let mut namespace_usage = QuotaUsage::default();
 
             for topic in namespace.topic_list.get_all().iter() {
                 let metrics = {
@@ -48,11 +67,13 @@ impl MyTimerTick for MetricsTimer {
                     topic_data.one_second_tick();
 
                     let mut queues_count = 0;
+                    let mut queues_size = 0;
 
                     for queue in topic_data.queues.get_all_mut() {
                         queue.one_second_tick();
                         let queue_size = queue.get_queue_size();
                         queues_count += 1;
+                        queues_size += queue_size;
                         self.app.prometheus.update_topic_queue_size(
                             namespace_name,
                             topic.topic_id.as_str(),
@@ -60,6 +81,24 @@ impl MyTimerTick for MetricsTimer {
                             queue_size,
                         );
 
+                        if queue.dead_letter.is_some() || queue.dead_lettered > 0 {
+                            self.app.prometheus.update_topic_queue_dead_lettered(
+                                namespace_name,
+                                topic.topic_id.as_str(),
+                                queue.queue_id.as_str(),
+                                queue.dead_lettered,
+                            );
+                        }
+
+                        if queue.expired > 0 {
+                            self.app.prometheus.update_topic_queue_expired(
+                                namespace_name,
+                                topic.topic_id.as_str(),
+                                queue.queue_id.as_str(),
+                                queue.expired,
+                            );
+                        }
+
                         if queue.is_permanent() && queue.subscribers.get_amount() == 0 {
                             permanent_queues_without_subscribers += 1;
                         }
@@ -69,10 +108,36 @@ impl MyTimerTick for MetricsTimer {
                         topics_without_queues += 1;
                     }
 
+                    if let Some(dedup) = topic_data.dedup.as_ref() {
+                        self.app.prometheus.update_topic_dedup(
+                            namespace_name,
+                            topic.topic_id.as_str(),
+                            dedup.len(),
+                            dedup.deduplicated,
+                        );
+                    }
+
                     let metrics = topic_data.get_topic_size_metrics();
 
                     topic_data.statistics.one_second_tick(&metrics);
 
+                    let usage = QuotaUsage {
+                        memory_size: metrics.data_size,
+                        persist_queue_size: metrics.persist_size,
+                        queue_size: queues_size,
+                    };
+
+                    for (quota, utilisation) in topic_data.quotas.get_utilisation(&usage) {
+                        self.app.prometheus.update_topic_quota_utilisation(
+                            namespace_name,
+                            topic.topic_id.as_str(),
+                            quota.as_str(),
+                            utilisation,
+                        );
+                    }
+
+                    namespace_usage.append(&usage);
+
                     metrics
                 };
 
@@ -89,6 +154,18 @@ impl MyTimerTick for MetricsTimer {
                     .prometheus
                     .update_http_connections_amount(http_connections_amount);
             }
+
+            namespace.quota_usage.update(&namespace_usage);
+
+            if let Some(quotas) = self.app.settings.get_namespace_quotas(namespace_name) {
+                for (quota, utilisation) in quotas.get_utilisation(&namespace_usage) {
+                    self.app.prometheus.update_namespace_quota_utilisation(
+                        namespace_name,
+                        quota.as_str(),
+                        utilisation,
+                    );
+                }
+            }
         }
 
         self.app
@@ -3,11 +3,13 @@ mod gc_deleted_topics;
 mod gc_timer;
 mod metrics_timer;
 mod persist_job;
+mod scheduled_messages_timer;
 pub use dead_subscribers_kicker::DeadSubscribersKickerTimer;
 pub use gc_deleted_topics::GcDeletedTopicsTimer;
 pub use gc_timer::GcTimer;
 pub use metrics_timer::MetricsTimer;
 pub use persist_job::PersistJob;
+pub use scheduled_messages_timer::ScheduledMessagesTimer;
 #[cfg(not(test))]
 mod restore_sub_pages;
 #[cfg(not(test))]
@@ -84,6 +84,9 @@ impl EventsLoopTick<RestorePageTask> for RestoreSubPagesEventLoop {
         crate::operations::page_loader::load_page_to_cache(
             &model.topic,
             &self.app.persistence_client,
+            model
+                .topic
+                .get_persist_compression(self.app.settings.persist_compression),
             model.sub_page_id,
         )
         .await;
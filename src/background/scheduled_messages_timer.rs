@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick, RepeatTimerIteration};
+
+use crate::app::AppContext;
+
+/// Hands to the queues the messages which were held back until a moment: scheduled
+/// publishes and failed messages waiting for their redelivery delay.
+pub struct ScheduledMessagesTimer {
+    app: Arc<AppContext>,
+}
+
+impl ScheduledMessagesTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ScheduledMessagesTimer {
+    async fn tick(&self) -> RepeatTimerIteration {
+        let now = DateTimeAsMicroseconds::now();
+
+        for namespace in self.app.namespaces.get_all().iter() {
+            for topic in namespace.topic_list.get_all().iter() {
+                let mut topic_data = topic.get_access();
+
+                if topic_data.deleted != 0 {
+                    continue;
+                }
+
+                let scheduled_are_due = topic_data.enqueue_due_scheduled_messages(now);
+                let redeliveries_are_due = topic_data.enqueue_due_redeliveries(now);
+
+                if scheduled_are_due || redeliveries_are_due {
+                    crate::operations::delivery::try_to_deliver_to_subscribers(
+                        self.app.as_ref(),
+                        topic,
+                        &mut topic_data,
+                    );
+                }
+            }
+        }
+
+        RepeatTimerIteration::WithInterval
+    }
+}
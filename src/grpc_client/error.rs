@@ -12,6 +12,17 @@ pub enum PersistenceError {
     CompressedPageReaderError(CompressedPageReaderError),
     Timeout(Option<tokio::time::error::Elapsed>),
     GrpcReadError(GrpcReadError),
+    IoError(std::io::Error),
+    QuorumIsNotReached { written: usize, needed: usize },
+    NoTargetInSync,
+    RouteIsNotRestored(String),
+    RoutesFailed(Vec<(String, String)>),
+}
+
+impl From<std::io::Error> for PersistenceError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
 }
 
 impl From<GrpcReadError> for PersistenceError {
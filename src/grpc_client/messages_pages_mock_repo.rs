@@ -1,30 +1,73 @@
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use my_service_bus::abstractions::MessageId;
-use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use my_service_bus::shared::{page_id::PageId, protobuf_models::MessageProtobufModel};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use tokio::sync::Mutex;
 
 use crate::messages_page::MySbMessageContent;
+use crate::persistence::{PersistenceBackend, PersistenceCompression};
+use crate::persistence_grpc::{MessageContentGrpcModel, TopicAndQueuesSnapshotGrpcModel};
+use crate::topics::TopicSnapshot;
 
 use super::PersistenceError;
 
 pub struct MessagesPagesMockRepo {
     messages: Mutex<BTreeMap<String, BTreeMap<i64, MySbMessageContent>>>,
+    fail_writes: AtomicBool,
+    fail_reads: AtomicBool,
+    last_save_compression: parking_lot::Mutex<Option<PersistenceCompression>>,
 }
 
 impl MessagesPagesMockRepo {
     pub fn new() -> Self {
         Self {
             messages: Mutex::new(BTreeMap::new()),
+            fail_writes: AtomicBool::new(false),
+            fail_reads: AtomicBool::new(false),
+            last_save_compression: parking_lot::Mutex::new(None),
         }
     }
 
-    pub async fn load_page(
+    /// Makes every next save fail, the way an unavailable persistence does.
+    pub fn set_fail_writes(&self, value: bool) {
+        self.fail_writes.store(value, Ordering::SeqCst);
+    }
+
+    /// Makes every next page and snapshot read fail.
+    pub fn set_fail_reads(&self, value: bool) {
+        self.fail_reads.store(value, Ordering::SeqCst);
+    }
+
+    fn check_reads(&self) -> Result<(), PersistenceError> {
+        if self.fail_reads.load(Ordering::SeqCst) {
+            return Err(PersistenceError::TonicError(tonic::Status::unavailable(
+                "Persistence is down",
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn get_last_save_compression(&self) -> Option<PersistenceCompression> {
+        *self.last_save_compression.lock()
+    }
+}
+
+#[async_trait::async_trait]
+impl PersistenceBackend for MessagesPagesMockRepo {
+    async fn load_page(
         &self,
+        _namespace: Option<String>,
         topic_id: &str,
+        _page_id: PageId,
         from_message_id: MessageId,
         to_message_id: MessageId,
+        _compression: PersistenceCompression,
     ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
+        self.check_reads()?;
+
         let mut result = BTreeMap::new();
 
         let mut write_access = self.messages.lock().await;
@@ -48,11 +91,39 @@ impl MessagesPagesMockRepo {
         }
     }
 
-    pub async fn save_messages(
+    async fn get_message(
+        &self,
+        _namespace: Option<String>,
+        _topic_id: &str,
+        _message_id: MessageId,
+    ) -> Result<Option<MessageContentGrpcModel>, PersistenceError> {
+        Ok(None)
+    }
+
+    async fn get_first_message_id_by_date(
+        &self,
+        _namespace: Option<String>,
+        _topic_id: &str,
+        _from: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageId>, PersistenceError> {
+        Ok(None)
+    }
+
+    async fn save_messages(
         &self,
+        _namespace: Option<String>,
         topic_id: &str,
         messages: Vec<MessageProtobufModel>,
+        compression: PersistenceCompression,
     ) -> Result<(), PersistenceError> {
+        *self.last_save_compression.lock() = Some(compression);
+
+        if self.fail_writes.load(Ordering::SeqCst) {
+            return Err(PersistenceError::TonicError(tonic::Status::unavailable(
+                "Persistence is down",
+            )));
+        }
+
         let mut write_access = self.messages.lock().await;
         if !write_access.contains_key(topic_id) {
             write_access.insert(topic_id.to_string(), BTreeMap::new());
@@ -67,4 +138,29 @@ impl MessagesPagesMockRepo {
 
         Ok(())
     }
+
+    async fn get_persistence_version(&self) -> Option<String> {
+        Some("Mock".to_string())
+    }
+
+    async fn hard_delete_topic(
+        &self,
+        _namespace: Option<String>,
+        topic_id: &str,
+    ) -> Result<(), PersistenceError> {
+        println!("Hard delete topic {} is invoked", topic_id);
+        Ok(())
+    }
+
+    async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+        self.check_reads()?;
+        Ok(vec![])
+    }
+
+    async fn save_topic_and_queues(
+        &self,
+        _data: Vec<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<(), PersistenceError> {
+        Ok(())
+    }
 }
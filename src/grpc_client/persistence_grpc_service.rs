@@ -1,175 +1,369 @@
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use my_grpc_extensions::GrpcReadError;
+use my_grpc_extensions::{GrpcClientSettings, GrpcUrl};
+use my_logger::LogEventCtx;
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::{page_id::PageId, protobuf_models::MessageProtobufModel};
+use prost::Message;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::grpc_client::PersistenceGrpcClient;
+use crate::messages_page::MySbMessageContent;
+use crate::persistence::{
+    CompressionCounters, CompressionStats, PersistenceBackend, PersistenceCompression,
+};
 use crate::persistence_grpc::*;
 use crate::topics::TopicSnapshot;
-use crate::{messages_page::MySbMessageContent, settings::SettingsModel};
 
-#[cfg(test)]
-use super::MessagesPagesMockRepo;
 use super::PersistenceError;
 
-pub enum PersistenceGrpcService {
-    Grpc(PersistenceGrpcClient),
-    #[cfg(test)]
-    Mock(MessagesPagesMockRepo),
+/// A compressed upload bigger than this goes in several chunks of the stream.
+const COMPRESSED_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// How long a compressed call the service failed is not tried again.
+const COMPRESSION_RECHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+struct PersistenceGrpcUrl(String);
+
+#[async_trait::async_trait]
+impl GrpcClientSettings for PersistenceGrpcUrl {
+    async fn get_grpc_url(&self, _name: &'static str) -> GrpcUrl {
+        self.0.clone().into()
+    }
+}
+
+/// A compressed call which failed while the plain one right after it went through
+/// means a persistence which does not know the call yet. It is switched off for a
+/// while rather than for good, since the failure could have been a glitch.
+struct CompressedCallSupport {
+    disabled_until: AtomicI64,
+}
+
+impl CompressedCallSupport {
+    fn new() -> Self {
+        Self {
+            disabled_until: AtomicI64::new(0),
+        }
+    }
+
+    fn is_enabled(&self, now: DateTimeAsMicroseconds) -> bool {
+        self.disabled_until.load(Ordering::Relaxed) <= now.unix_microseconds
+    }
+
+    fn disable(&self, now: DateTimeAsMicroseconds) {
+        self.disabled_until.store(
+            now.unix_microseconds + COMPRESSION_RECHECK_INTERVAL.as_micros() as i64,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+/// The separate my-service-bus-persistence process.
+pub struct PersistenceGrpcService {
+    repo: PersistenceGrpcClient,
+    grpc_url: String,
+    compressed_uploads: CompressedCallSupport,
+    compressed_pages: CompressedCallSupport,
+    compression_counters: CompressionCounters,
 }
 
 impl PersistenceGrpcService {
-    pub fn create_production_instance(settings: Arc<SettingsModel>) -> Self {
-        Self::Grpc(PersistenceGrpcClient::new(settings.clone()))
+    pub fn new(grpc_url: String) -> Self {
+        Self {
+            repo: PersistenceGrpcClient::new(Arc::new(PersistenceGrpcUrl(grpc_url.clone()))),
+            grpc_url,
+            compressed_uploads: CompressedCallSupport::new(),
+            compressed_pages: CompressedCallSupport::new(),
+            compression_counters: CompressionCounters::default(),
+        }
+    }
+
+    async fn load_page_uncompressed(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        page_id: PageId,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+    ) -> Result<BTreeMap<i64, MySbMessageContent>, PersistenceError> {
+        let result = self
+            .repo
+            .get_page(GetPageGrpcRequest {
+                topic_id: topic_id.to_string(),
+                page_no: page_id.get_value(),
+                from_message_id: from_message_id.get_value(),
+                to_message_id: to_message_id.get_value(),
+                version: 1,
+                namespace,
+            })
+            .await?;
+
+        let result = result
+            .into_b_tree_map(|itm| (itm.message_id, itm.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn load_page_compressed(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        page_id: PageId,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+        compression: PersistenceCompression,
+    ) -> Result<BTreeMap<i64, MySbMessageContent>, PersistenceError> {
+        let chunks: Vec<CompressedMessageChunkModel> = self
+            .repo
+            .get_page_compressed(GetPageCompressedGrpcRequest {
+                topic_id: topic_id.to_string(),
+                page_no: page_id.get_value(),
+                from_message_id: from_message_id.get_value(),
+                to_message_id: to_message_id.get_value(),
+                version: 1,
+                namespace,
+                compression: Some(compression.as_grpc()),
+            })
+            .await?
+            .into_vec()
+            .await?;
+
+        let compressed: Vec<u8> = chunks.into_iter().flat_map(|itm| itm.chunk).collect();
+
+        // A persistence which ignores `Compression` sends a zip page, which fails here.
+        let payload = compression.decompress(&compressed)?;
+        let page = MessagesPageGrpcModel::decode(payload.as_slice())?;
+
+        self.compression_counters
+            .downloaded(payload.len(), compressed.len());
+
+        Ok(page
+            .messages
+            .into_iter()
+            .map(|itm| (itm.message_id, itm.into()))
+            .collect())
+    }
+
+    async fn save_messages_compressed(
+        &self,
+        request: &SaveMessagesGrpcRequest,
+        compression: PersistenceCompression,
+    ) -> Result<(), PersistenceError> {
+        let payload = request.encode_to_vec();
+        let compressed = compression.compress(&payload)?;
+
+        let chunks = compressed
+            .chunks(COMPRESSED_CHUNK_SIZE)
+            .map(|chunk| SaveMessagesCompressedGrpcRequest {
+                compression: compression.as_grpc(),
+                chunk: chunk.to_vec(),
+            })
+            .collect();
+
+        self.repo.save_messages_compressed(chunks).await?;
+
+        self.compression_counters
+            .uploaded(payload.len(), compressed.len());
+
+        Ok(())
     }
 
-    #[cfg(test)]
-    pub fn create_mock_instance() -> Self {
-        Self::Mock(MessagesPagesMockRepo::new())
+    fn compressed_call_failed(&self, call: &str, err: &PersistenceError) {
+        my_logger::LOGGER.write_error(
+            "PersistenceGrpcService",
+            format!(
+                "{} failed while the uncompressed call went through: {:?}. Not compressing for {:?}",
+                call, err, COMPRESSION_RECHECK_INTERVAL
+            ),
+            LogEventCtx::new().add("grpcUrl", self.grpc_url.as_str()),
+        );
     }
+}
 
-    pub async fn load_page(
+#[async_trait::async_trait]
+impl PersistenceBackend for PersistenceGrpcService {
+    async fn load_page(
         &self,
         namespace: Option<String>,
         topic_id: &str,
         page_id: PageId,
         from_message_id: MessageId,
         to_message_id: MessageId,
+        compression: PersistenceCompression,
     ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => {
-                let result = repo
-                    .get_page(GetPageGrpcRequest {
-                        topic_id: topic_id.to_string(),
-                        page_no: page_id.get_value(),
-                        from_message_id: from_message_id.get_value(),
-                        to_message_id: to_message_id.get_value(),
-                        version: 1,
-                        namespace,
-                    })
-                    .await
-                    .unwrap();
-
-                let result: BTreeMap<i64, MySbMessageContent> = result
-                    .into_b_tree_map(|itm| (itm.message_id, itm.into()))
-                    .await?;
-
-                Ok(Some(result))
-                //  repo.load_page(topic_id, page_id, from_message_id, to_message_id)
-                //    .await
-            }
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(repo) => {
-                repo.load_page(topic_id, from_message_id, to_message_id)
-                    .await
-            }
+        let now = DateTimeAsMicroseconds::now();
+
+        if compression == PersistenceCompression::None || !self.compressed_pages.is_enabled(now) {
+            let result = self
+                .load_page_uncompressed(
+                    namespace,
+                    topic_id,
+                    page_id,
+                    from_message_id,
+                    to_message_id,
+                )
+                .await?;
+
+            return Ok(Some(result));
         }
+
+        let page_no = page_id.get_value();
+
+        let compressed_err = match self
+            .load_page_compressed(
+                namespace.clone(),
+                topic_id,
+                page_id,
+                from_message_id,
+                to_message_id,
+                compression,
+            )
+            .await
+        {
+            Ok(result) => return Ok(Some(result)),
+            Err(err) => err,
+        };
+
+        let result = self
+            .load_page_uncompressed(
+                namespace,
+                topic_id,
+                PageId::new(page_no),
+                from_message_id,
+                to_message_id,
+            )
+            .await?;
+
+        self.compressed_pages.disable(now);
+        self.compressed_call_failed("GetPageCompressed", &compressed_err);
+
+        Ok(Some(result))
     }
 
-    pub async fn get_message(
+    async fn get_message(
         &self,
         namespace: Option<String>,
         topic_id: &str,
         message_id: MessageId,
     ) -> Result<Option<MessageContentGrpcModel>, PersistenceError> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => {
-                let result = repo
-                    .get_message(GetMessageGrpcRequest {
-                        topic_id: topic_id.to_string(),
-                        message_id: message_id.get_value(),
-                        namespace,
-                    })
-                    .await?;
-
-                if result.message_id != message_id.get_value() {
-                    return Ok(None);
-                }
-
-                Ok(Some(result))
+        let result = self
+            .repo
+            .get_message(GetMessageGrpcRequest {
+                topic_id: topic_id.to_string(),
+                message_id: message_id.get_value(),
+                namespace,
+            })
+            .await?;
+
+        if result.message_id != message_id.get_value() {
+            return Ok(None);
+        }
+
+        Ok(Some(result))
+    }
+
+    async fn get_first_message_id_by_date(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        from: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageId>, PersistenceError> {
+        let mut stream = self
+            .repo
+            .get_history_by_date(GetHistoryByDateGrpcRequest {
+                topic_id: topic_id.to_string(),
+                from_date_time: from.unix_microseconds,
+                namespace,
+            })
+            .await?;
+
+        // The history goes in message id order, so the first message of the moment
+        // is the answer; the rest of the stream is not read at all.
+        while let Some(message) = stream.get_next_item().await {
+            let message: MessageContentGrpcModel = message?;
+
+            if message.created >= from.unix_microseconds {
+                return Ok(Some(message.message_id.into()));
             }
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(_) => Ok(None),
         }
+
+        Ok(None)
     }
 
-    pub async fn save_messages(
+    async fn save_messages(
         &self,
         namespace: Option<String>,
         topic_id: &str,
         messages: Vec<MessageProtobufModel>,
+        compression: PersistenceCompression,
     ) -> Result<(), PersistenceError> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => {
-                let input_data = vec![SaveMessagesGrpcRequest {
-                    topic_id: topic_id.to_string(),
-                    messages: messages.into_iter().map(|itm| itm.into()).collect(),
-                    namespace,
-                }];
-                repo.save_messages(input_data).await?;
+        let request = SaveMessagesGrpcRequest {
+            topic_id: topic_id.to_string(),
+            messages: messages.into_iter().map(|itm| itm.into()).collect(),
+            namespace,
+        };
 
-                Ok(())
-            }
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(repo) => repo.save_messages(topic_id, messages).await,
+        let now = DateTimeAsMicroseconds::now();
+
+        if compression == PersistenceCompression::None || !self.compressed_uploads.is_enabled(now) {
+            self.repo.save_messages(vec![request]).await?;
+            return Ok(());
         }
+
+        let compressed_err = match self.save_messages_compressed(&request, compression).await {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+
+        self.repo.save_messages(vec![request]).await?;
+
+        self.compressed_uploads.disable(now);
+        self.compressed_call_failed("SaveMessagesCompressed", &compressed_err);
+
+        Ok(())
     }
 
-    pub async fn get_persistence_version(&self) -> Option<String> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => match repo.get_version(()).await {
-                Ok(result) => Some(result.version),
-                Err(_) => None,
-            },
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(_) => Some("Mock".to_string()),
+    async fn get_persistence_version(&self) -> Option<String> {
+        match self.repo.get_version(()).await {
+            Ok(result) => Some(result.version),
+            Err(_) => None,
         }
     }
 
-    pub async fn hard_delete_topic(
+    async fn hard_delete_topic(
         &self,
         namespace: Option<String>,
         topic_id: &str,
-    ) -> Result<(), GrpcReadError> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => {
-                repo.hard_delete_topic(HardDeleteTopicGrpcRequest {
-                    topic_id: topic_id.to_string(),
-                    namespace,
-                })
-                .await?;
-                Ok(())
-            }
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(_) => {
-                println!("Hard delete topic {} is invoked", topic_id);
-                Ok(())
-            }
-        }
+    ) -> Result<(), PersistenceError> {
+        self.repo
+            .hard_delete_topic(HardDeleteTopicGrpcRequest {
+                topic_id: topic_id.to_string(),
+                namespace,
+            })
+            .await?;
+
+        Ok(())
     }
 
-    pub async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, GrpcReadError> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => {
-                let result = repo.get_queue_snapshot(()).await?;
-                let result = result.into_vec().await?;
-                Ok(result)
-            }
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(_) => Ok(vec![]),
-        }
+    async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+        let result = self.repo.get_queue_snapshot(()).await?;
+        let result = result.into_vec().await?;
+        Ok(result)
     }
 
-    pub async fn save_topic_and_queues(
+    async fn save_topic_and_queues(
         &self,
         data: Vec<TopicAndQueuesSnapshotGrpcModel>,
-    ) -> Result<(), GrpcReadError> {
-        match self {
-            PersistenceGrpcService::Grpc(repo) => repo.save_queue_snapshot(data).await,
-            #[cfg(test)]
-            PersistenceGrpcService::Mock(_) => Ok(()),
-        }
+    ) -> Result<(), PersistenceError> {
+        self.repo.save_queue_snapshot(data).await?;
+        Ok(())
+    }
+
+    fn get_compression_stats(&self) -> CompressionStats {
+        self.compression_counters.get_stats()
     }
 }
@@ -27,6 +27,38 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         super::topics_controller::UpdatePersistAction::new(app.clone()),
     ));
 
+    controllers.register_post_action(Arc::new(super::topics_controller::UpdateTtlAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_post_action(Arc::new(
+        super::topics_controller::UpdateDedupAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(
+        super::topics_controller::UpdateQuotasAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(
+        super::topics_controller::UpdatePersistCompressionAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(
+        super::topics_controller::SetForwardRuleAction::new(app.clone()),
+    ));
+
+    controllers.register_delete_action(Arc::new(
+        super::topics_controller::DeleteForwardRuleAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(
+        super::topics_controller::SetContentRouterAction::new(app.clone()),
+    ));
+
+    controllers.register_delete_action(Arc::new(
+        super::topics_controller::DeleteContentRouterAction::new(app.clone()),
+    ));
+
     controllers.register_delete_action(Arc::new(
         super::sessions_controller::DeleteSessionAction::new(app.clone()),
     ));
@@ -61,10 +93,46 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         app.clone(),
     )));
 
+    controllers.register_post_action(Arc::new(super::queues::SeekByTimeAction::new(
+        app.clone(),
+    )));
+
     controllers.register_post_action(Arc::new(super::queues::SetMaxMessagePerPayloadAction::new(
         app.clone(),
     )));
 
+    controllers.register_post_action(Arc::new(super::queues::SetDeadLetterAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_post_action(Arc::new(
+        super::queues::SetRedeliveryPolicyAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(super::queues::SetOrderingKeyAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_post_action(Arc::new(super::queues::SetHeaderFilterAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_post_action(Arc::new(super::queues::SetPausedAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_post_action(Arc::new(
+        super::queues::SetDeliveryTimeoutAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(super::queues::PurgeQueueAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_post_action(Arc::new(super::queues::CloneQueueAction::new(
+        app.clone(),
+    )));
+
     controllers
         .register_delete_action(Arc::new(super::queues::DeleteQueueAction::new(app.clone())));
 
@@ -113,6 +181,10 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
 
     controllers.register_post_action(Arc::new(super::publisher::PublishAction::new(app.clone())));
 
+    controllers.register_post_action(Arc::new(
+        super::publisher::PublishTransactionAction::new(app.clone()),
+    ));
+
     controllers.register_get_action(Arc::new(super::prometheus_controller::MetricsAction::new(
         app.clone(),
     )));
@@ -131,5 +203,13 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         super::subscribers_controller::AwaitDeliveryAction::new(app.clone()),
     ));
 
+    controllers.register_post_action(Arc::new(
+        super::request_reply_controller::CreateReplyQueueAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(
+        super::request_reply_controller::RequestAction::new(app.clone()),
+    ));
+
     controllers
 }
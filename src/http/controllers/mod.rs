@@ -9,6 +9,7 @@ pub mod namespaces_controller;
 pub mod prometheus_controller;
 pub mod publisher;
 pub mod queues;
+pub mod request_reply_controller;
 pub mod sessions_controller;
 pub mod status_controller;
 pub mod subscribers_controller;
@@ -12,10 +12,26 @@ pub struct PublishMessageHttpInput {
     #[http_query(name="topicId"; description = "Id of topic")]
     pub topic_id: String,
 
+    #[http_query(name = "persistImmediately"; description = "Respond only once the messages are saved to persistence")]
+    pub persist_immediately: Option<bool>,
+
     #[http_body(description = "Base64 encoded messages")]
     pub messages: Vec<MessageToPublishJsonModel>,
 }
 
+#[derive(MyHttpInput)]
+pub struct PublishTransactionHttpInput {
+    #[http_body(description = "Messages grouped by topic: all of them are published or none")]
+    pub topics: Vec<TopicMessagesToPublishJsonModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct TopicMessagesToPublishJsonModel {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    pub messages: Vec<MessageToPublishJsonModel>,
+}
+
 #[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
 pub struct MessageToPublishJsonModel {
     pub headers: Option<Vec<MessageKeyValueJsonModel>>,
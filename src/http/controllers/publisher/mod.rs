@@ -1,3 +1,5 @@
-mod contracts;
+pub mod contracts;
 mod publish_action;
 pub use publish_action::PublishAction;
+mod publish_transaction_action;
+pub use publish_transaction_action::PublishTransactionAction;
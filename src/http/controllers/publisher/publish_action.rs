@@ -21,6 +21,8 @@ use super::contracts::PublishMessageHttpInput;
     authorized: "Yes",
     result: [
         {status_code: 202, description: "Message is published"},
+        {status_code: 429, description: "Topic or its namespace is over a quota, or the publisher is throttled"},
+        {status_code: 504, description: "Messages are published, but were not saved to persistence in time"},
     ]
 )]
 pub struct PublishAction {
@@ -55,12 +57,23 @@ async fn handle_request(
         messages_to_publish.push(msg);
     }
 
+    let namespace = http_session.get_namespace();
+
+    crate::operations::publisher::throttle(
+        action.app.as_ref(),
+        http_session.get_name_and_version().name.as_str(),
+        &namespace,
+        http_input.topic_id.as_str(),
+        &messages_to_publish,
+    )
+    .await?;
+
     crate::operations::publisher::publish(
         &action.app,
-        &http_session.get_namespace(),
+        &namespace,
         http_input.topic_id.as_str(),
         messages_to_publish,
-        false,
+        http_input.persist_immediately.unwrap_or(false),
         http_session.session_id,
     )
     .await?;
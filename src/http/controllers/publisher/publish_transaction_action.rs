@@ -0,0 +1,88 @@
+use crate::http::auth::GetSessionToken;
+
+use my_http_server::macros::http_route;
+use my_service_bus::abstractions::publisher::MessageToPublish;
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::contracts::PublishTransactionHttpInput;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Publish/Transaction",
+    controller: "Publish",
+    description: "Publish messages to several topics: all of them or none",
+    summary: "Publishes messages to several topics atomically",
+    input_data: "PublishTransactionHttpInput",
+    authorized: "Yes",
+    result: [
+        {status_code: 202, description: "Messages are published to every topic"},
+        {status_code: 429, description: "One of the topics or the namespace is over a quota, or the publisher is throttled"},
+    ]
+)]
+pub struct PublishTransactionAction {
+    app: Arc<AppContext>,
+}
+
+impl PublishTransactionAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &PublishTransactionAction,
+    http_input: PublishTransactionHttpInput,
+    ctx: &HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let http_session = ctx.get_http_session(&action.app).await?;
+
+    let mut items = Vec::with_capacity(http_input.topics.len());
+
+    let mut content_size = 0;
+
+    for topic in http_input.topics {
+        let mut messages_to_publish = Vec::with_capacity(topic.messages.len());
+
+        for mut msg_in_json in topic.messages {
+            let msg = MessageToPublish {
+                headers: msg_in_json.get_headers(),
+                content: msg_in_json.get_content()?,
+            };
+
+            content_size += msg.content.len();
+
+            messages_to_publish.push(msg);
+        }
+
+        items.push((topic.topic_id, messages_to_publish));
+    }
+
+    let namespace = http_session.get_namespace();
+
+    for (topic_id, messages) in items.iter() {
+        crate::operations::publisher::throttle(
+            action.app.as_ref(),
+            http_session.get_name_and_version().name.as_str(),
+            &namespace,
+            topic_id.as_str(),
+            messages,
+        )
+        .await?;
+    }
+
+    crate::operations::publisher::publish_transaction(
+        &action.app,
+        &namespace,
+        items,
+        http_session.session_id,
+    )
+    .await?;
+
+    http_session.update_written_amount(content_size);
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
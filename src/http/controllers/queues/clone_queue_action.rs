@@ -0,0 +1,52 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::app::AppContext;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/Clone",
+    controller: "Queues",
+    description: "Clone queue position",
+    summary: "Creates permanent queue which starts exactly where the given queue is",
+    input_data: "CloneQueueInputContract",
+    result: [
+        {status_code: 200, description: "Size of the new queue", model: "CloneQueueResponseContract"},
+
+    ]
+)]
+pub struct CloneQueueAction {
+    app: Arc<AppContext>,
+}
+
+impl CloneQueueAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &CloneQueueAction,
+    input_data: CloneQueueInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let size = crate::operations::queues::clone_queue(
+        action.app.as_ref(),
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        input_data.new_queue_id.as_str(),
+    )
+    .await?;
+
+    HttpOutput::as_json(CloneQueueResponseContract { size })
+        .into_ok_result(true)
+        .into()
+}
@@ -1,4 +1,6 @@
-use my_http_server::macros::MyHttpInput;
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
 
 #[derive(MyHttpInput)]
 pub struct GetListOfQueuesInputContract {
@@ -23,3 +25,115 @@ pub struct SetQueueMessageIdInputContract {
     #[http_query(name="messageId"; description = "Message id")]
     pub message_id: i64,
 }
+
+#[derive(MyHttpInput)]
+pub struct SetQueueDeadLetterInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="maxDeliveryAttempts"; description = "Failed deliveries before the message is dead-lettered. 0 - disabled")]
+    pub max_delivery_attempts: i32,
+    #[http_query(name="deadLetterQueueId"; description = "Queue of the same topic to move messages to. Empty - publish to <topicId>.dlq topic")]
+    pub dead_letter_queue_id: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct SetQueueRedeliveryPolicyInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="backOff"; description = "fixed or exponential")]
+    pub back_off: String,
+    #[http_query(name="delayMs"; description = "Delay before the first redelivery. 0 - redeliver right away")]
+    pub delay_ms: i64,
+    #[http_query(name="maxDelayMs"; description = "Cap of the exponential delay")]
+    pub max_delay_ms: Option<i64>,
+}
+
+#[derive(MyHttpInput)]
+pub struct SetQueueOrderingKeyInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="header"; description = "Message header with the ordering key. Empty - ordering is off")]
+    pub header: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct SetQueueHeaderFilterInputContract {
+    #[http_body(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_body(name = "queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_body(name = "filter"; description = "Header filter as JSON. Empty - every message is delivered")]
+    pub filter: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct SeekQueueByTimeInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="fromDate"; description = "Queue starts from the first message created at this moment or later")]
+    pub from_date: DateTimeAsMicroseconds,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct SeekQueueByTimeResponseContract {
+    #[serde(rename = "messageId")]
+    pub message_id: i64,
+}
+
+#[derive(MyHttpInput)]
+pub struct SetQueuePausedInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="paused"; description = "true - stop delivery, false - resume it")]
+    pub paused: bool,
+}
+
+#[derive(MyHttpInput)]
+pub struct PurgeQueueInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="keepLast"; description = "How many newest messages to leave in the queue. Default - 0")]
+    pub keep_last: Option<usize>,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct PurgeQueueResponseContract {
+    pub dropped: usize,
+}
+
+#[derive(MyHttpInput)]
+pub struct CloneQueueInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue to copy the position from")]
+    pub queue_id: String,
+    #[http_query(name="newQueueId"; description = "Id of permanent queue to create")]
+    pub new_queue_id: String,
+}
+
+#[derive(Debug, MyHttpObjectStructure, Serialize)]
+pub struct CloneQueueResponseContract {
+    pub size: usize,
+}
+
+#[derive(MyHttpInput)]
+pub struct SetQueueDeliveryTimeoutInputContract {
+    #[http_query(name="topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name="queueId"; description = "Id of queue")]
+    pub queue_id: String,
+    #[http_query(name="timeoutMs"; description = "Subscriber which holds a delivery longer is disconnected. Empty or 0 - the global timeout")]
+    pub timeout_ms: Option<u64>,
+}
@@ -8,3 +8,21 @@ pub use get_list_of_queues_action::GetQueuesAction;
 pub use set_message_id_action::SetMessageIdAction;
 mod set_max_message_per_payload_action;
 pub use set_max_message_per_payload_action::*;
+mod set_dead_letter_action;
+pub use set_dead_letter_action::SetDeadLetterAction;
+mod set_redelivery_policy_action;
+pub use set_redelivery_policy_action::SetRedeliveryPolicyAction;
+mod set_ordering_key_action;
+pub use set_ordering_key_action::SetOrderingKeyAction;
+mod set_header_filter_action;
+pub use set_header_filter_action::SetHeaderFilterAction;
+mod seek_by_time_action;
+pub use seek_by_time_action::SeekByTimeAction;
+mod set_paused_action;
+pub use set_paused_action::SetPausedAction;
+mod purge_queue_action;
+pub use purge_queue_action::PurgeQueueAction;
+mod clone_queue_action;
+pub use clone_queue_action::CloneQueueAction;
+mod set_delivery_timeout_action;
+pub use set_delivery_timeout_action::SetDeliveryTimeoutAction;
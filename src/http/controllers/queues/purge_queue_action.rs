@@ -0,0 +1,51 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::app::AppContext;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/Purge",
+    controller: "Queues",
+    description: "Purge queue",
+    summary: "Drops pending messages of the queue, optionally leaving the newest ones",
+    input_data: "PurgeQueueInputContract",
+    result: [
+        {status_code: 200, description: "How many messages were dropped", model: "PurgeQueueResponseContract"},
+
+    ]
+)]
+pub struct PurgeQueueAction {
+    app: Arc<AppContext>,
+}
+
+impl PurgeQueueAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &PurgeQueueAction,
+    input_data: PurgeQueueInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let dropped = crate::operations::queues::purge_queue(
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        input_data.keep_last.unwrap_or(0),
+    )
+    .await?;
+
+    HttpOutput::as_json(PurgeQueueResponseContract { dropped })
+        .into_ok_result(true)
+        .into()
+}
@@ -0,0 +1,54 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::app::AppContext;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SeekByTime",
+    controller: "Queues",
+    description: "Set current queue messageId by time",
+    summary: "Moves queue to the first message created at the given moment or later",
+    input_data: "SeekQueueByTimeInputContract",
+    result: [
+        {status_code: 200, description: "Message id the queue starts from", model: "SeekQueueByTimeResponseContract"},
+
+    ]
+)]
+pub struct SeekByTimeAction {
+    app: Arc<AppContext>,
+}
+
+impl SeekByTimeAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SeekByTimeAction,
+    input_data: SeekQueueByTimeInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let message_id = crate::operations::queues::seek_by_time(
+        action.app.as_ref(),
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        input_data.from_date,
+    )
+    .await?;
+
+    let response = SeekQueueByTimeResponseContract {
+        message_id: message_id.get_value(),
+    };
+
+    HttpOutput::as_json(response).into_ok_result(true).into()
+}
@@ -0,0 +1,54 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::{app::AppContext, queues::DeadLetterSettings};
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SetDeadLetter",
+    controller: "Queues",
+    description: "Set dead-letter policy of the queue",
+    summary: "Sets how many failed deliveries a message gets before it is moved to the dead-letter target",
+    input_data: "SetQueueDeadLetterInputContract",
+    result: [
+        {status_code: 202, description: "Operation is succesfull"},
+
+    ]
+)]
+pub struct SetDeadLetterAction {
+    app: Arc<AppContext>,
+}
+
+impl SetDeadLetterAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetDeadLetterAction,
+    input_data: SetQueueDeadLetterInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let dead_letter = DeadLetterSettings::restore(
+        Some(input_data.max_delivery_attempts),
+        input_data.dead_letter_queue_id,
+    );
+
+    crate::operations::queues::set_dead_letter(
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        dead_letter,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
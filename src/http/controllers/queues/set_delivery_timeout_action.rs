@@ -0,0 +1,54 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::{sync::Arc, time::Duration};
+
+use super::*;
+
+use crate::app::AppContext;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SetDeliveryTimeout",
+    controller: "Queues",
+    description: "Set delivery timeout of the queue",
+    summary: "Overrides the global delivery timeout for one queue",
+    input_data: "SetQueueDeliveryTimeoutInputContract",
+    result: [
+        {status_code: 202, description: "Operation is succesfull"},
+
+    ]
+)]
+pub struct SetDeliveryTimeoutAction {
+    app: Arc<AppContext>,
+}
+
+impl SetDeliveryTimeoutAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetDeliveryTimeoutAction,
+    input_data: SetQueueDeliveryTimeoutInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let delivery_timeout = match input_data.timeout_ms {
+        Some(timeout_ms) if timeout_ms > 0 => Some(Duration::from_millis(timeout_ms)),
+        _ => None,
+    };
+
+    crate::operations::queues::set_delivery_timeout(
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        delivery_timeout,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,57 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::{app::AppContext, queues::HeaderFilter};
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SetHeaderFilter",
+    controller: "Queues",
+    description: "Set header filter of the queue",
+    summary: "Messages which do not match the filter are confirmed without being delivered",
+    input_data: "SetQueueHeaderFilterInputContract",
+    result: [
+        {status_code: 202, description: "Operation is succesfull"},
+
+    ]
+)]
+pub struct SetHeaderFilterAction {
+    app: Arc<AppContext>,
+}
+
+impl SetHeaderFilterAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetHeaderFilterAction,
+    input_data: SetQueueHeaderFilterInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let header_filter = match input_data.filter {
+        Some(filter) if !filter.trim().is_empty() => Some(
+            HeaderFilter::from_json(filter.as_str())
+                .map_err(HttpFailResult::as_validation_error)?,
+        ),
+        _ => None,
+    };
+
+    crate::operations::queues::set_header_filter(
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        header_filter,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
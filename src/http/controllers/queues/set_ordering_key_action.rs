@@ -0,0 +1,54 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::app::AppContext;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SetOrderingKey",
+    controller: "Queues",
+    description: "Set ordering key header of the queue",
+    summary: "Messages with the same ordering key are never on delivery to two subscribers at once",
+    input_data: "SetQueueOrderingKeyInputContract",
+    result: [
+        {status_code: 202, description: "Operation is succesfull"},
+
+    ]
+)]
+pub struct SetOrderingKeyAction {
+    app: Arc<AppContext>,
+}
+
+impl SetOrderingKeyAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetOrderingKeyAction,
+    input_data: SetQueueOrderingKeyInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let header = match input_data.header {
+        Some(header) if !header.is_empty() => Some(header),
+        _ => None,
+    };
+
+    crate::operations::queues::set_ordering_key(
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        header,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
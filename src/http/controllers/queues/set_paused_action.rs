@@ -0,0 +1,50 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::app::AppContext;
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SetPaused",
+    controller: "Queues",
+    description: "Pause or resume delivery of the queue",
+    summary: "Paused queue keeps its subscribers and keeps getting messages, but delivers nothing",
+    input_data: "SetQueuePausedInputContract",
+    result: [
+        {status_code: 202, description: "Operation is succesfull"},
+
+    ]
+)]
+pub struct SetPausedAction {
+    app: Arc<AppContext>,
+}
+
+impl SetPausedAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetPausedAction,
+    input_data: SetQueuePausedInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    crate::operations::queues::set_paused(
+        action.app.as_ref(),
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        input_data.paused,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,65 @@
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use my_http_server::macros::http_route;
+
+use std::sync::Arc;
+
+use super::*;
+
+use crate::{
+    app::AppContext,
+    queues::{RedeliveryBackOff, RedeliveryPolicy},
+};
+
+#[http_route(
+    method: "POST",
+    route: "/api/Queues/SetRedeliveryPolicy",
+    controller: "Queues",
+    description: "Set redelivery back-off of the queue",
+    summary: "Sets how long a negatively confirmed message waits before it is delivered again",
+    input_data: "SetQueueRedeliveryPolicyInputContract",
+    result: [
+        {status_code: 202, description: "Operation is succesfull"},
+
+    ]
+)]
+pub struct SetRedeliveryPolicyAction {
+    app: Arc<AppContext>,
+}
+
+impl SetRedeliveryPolicyAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetRedeliveryPolicyAction,
+    input_data: SetQueueRedeliveryPolicyInputContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let Some(back_off) = RedeliveryBackOff::from_str(input_data.back_off.as_str()) else {
+        return Err(HttpFailResult::as_validation_error(format!(
+            "Unknown backOff '{}'. Use fixed or exponential",
+            input_data.back_off
+        )));
+    };
+
+    let redelivery = RedeliveryPolicy::restore(
+        Some(back_off.into_u8() as i32),
+        Some(input_data.delay_ms),
+        input_data.max_delay_ms,
+    );
+
+    crate::operations::queues::set_redelivery_policy(
+        &namespace,
+        input_data.topic_id.as_str(),
+        input_data.queue_id.as_str(),
+        redelivery,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,44 @@
+use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use serde::Serialize;
+
+use crate::http::controllers::{
+    publisher::contracts::MessageToPublishJsonModel, MessageKeyValueJsonModel,
+};
+
+#[derive(MyHttpInput)]
+pub struct CreateReplyQueueHttpInput {
+    #[http_body(name = "replyTopicId"; description = "Topic the replies are published to")]
+    pub reply_topic_id: String,
+}
+
+#[derive(MyHttpObjectStructure, Serialize)]
+pub struct CreateReplyQueueHttpResponse {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "queueId")]
+    pub queue_id: String,
+    #[serde(rename = "correlationIdPrefix")]
+    pub correlation_id_prefix: String,
+}
+
+#[derive(MyHttpInput)]
+pub struct RequestHttpInput {
+    #[http_body(name = "topicId"; description = "Topic the request is published to")]
+    pub topic_id: String,
+
+    #[http_body(name = "replyTopicId"; description = "Topic the replier publishes its reply to")]
+    pub reply_topic_id: String,
+
+    #[http_body(name = "timeoutMs"; description = "How long to wait for the reply. Default is 30 seconds")]
+    pub timeout_ms: Option<u64>,
+
+    #[http_body(description = "Request message. A missing correlation-id is generated")]
+    pub message: MessageToPublishJsonModel,
+}
+
+#[derive(MyHttpObjectStructure, Serialize)]
+pub struct RequestHttpResponse {
+    pub headers: Vec<MessageKeyValueJsonModel>,
+    #[serde(rename = "base64Message")]
+    pub base64_message: String,
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, http::auth::GetSessionToken};
+
+use super::contracts::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/RequestReply/ReplyQueue",
+    input_data: "CreateReplyQueueHttpInput",
+    description: "Create a private reply queue which lives as long as the session",
+    summary: "Create a private reply queue",
+    controller: "RequestReply",
+    result:[
+        {status_code: 200, description: "Reply queue is created", model: "CreateReplyQueueHttpResponse"},
+    ]
+)]
+pub struct CreateReplyQueueAction {
+    app: Arc<AppContext>,
+}
+
+impl CreateReplyQueueAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &CreateReplyQueueAction,
+    input_data: CreateReplyQueueHttpInput,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let http_session = ctx.get_http_session(&action.app).await?;
+
+    http_session.ping();
+
+    let namespace = http_session.get_namespace();
+
+    let reply_queue = crate::operations::request_reply::create_reply_queue(
+        &action.app,
+        &namespace,
+        input_data.reply_topic_id,
+        http_session.into(),
+    )
+    .await?;
+
+    let response = CreateReplyQueueHttpResponse {
+        topic_id: reply_queue.topic_id,
+        queue_id: reply_queue.queue_id,
+        correlation_id_prefix: reply_queue.correlation_id_prefix,
+    };
+
+    HttpOutput::as_json(response).into_ok_result(true).into()
+}
@@ -0,0 +1,5 @@
+mod contracts;
+mod create_reply_queue_action;
+pub use create_reply_queue_action::*;
+mod request_action;
+pub use request_action::*;
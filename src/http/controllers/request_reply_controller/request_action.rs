@@ -0,0 +1,87 @@
+use std::{sync::Arc, time::Duration};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use my_service_bus::abstractions::publisher::MessageToPublish;
+use rust_extensions::base64::IntoBase64;
+
+use crate::{
+    app::AppContext,
+    http::{auth::GetSessionToken, controllers::MessageKeyValueJsonModel},
+};
+
+use super::contracts::*;
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/RequestReply/Request",
+    input_data: "RequestHttpInput",
+    description: "Publish a request and wait for the reply with the same correlation-id",
+    summary: "Publish a request and wait for its reply",
+    controller: "RequestReply",
+    result:[
+        {status_code: 200, description: "Reply. Empty if there was no reply in time", model: "RequestHttpResponse"},
+    ]
+)]
+pub struct RequestAction {
+    app: Arc<AppContext>,
+}
+
+impl RequestAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &RequestAction,
+    mut input_data: RequestHttpInput,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let http_session = ctx.get_http_session(&action.app).await?;
+
+    http_session.ping();
+
+    let message = MessageToPublish {
+        headers: input_data.message.get_headers(),
+        content: input_data.message.get_content()?,
+    };
+
+    http_session.update_written_amount(message.content.len());
+
+    let timeout = match input_data.timeout_ms {
+        Some(timeout_ms) if timeout_ms > 0 => Duration::from_millis(timeout_ms),
+        _ => DEFAULT_REQUEST_TIMEOUT,
+    };
+
+    let reply = crate::operations::request_reply::request(
+        &action.app,
+        &http_session.get_namespace(),
+        input_data.topic_id.as_str(),
+        input_data.reply_topic_id.as_str(),
+        message,
+        timeout,
+        http_session.session_id,
+    )
+    .await?;
+
+    match reply {
+        None => HttpOutput::Empty.into_ok_result(false).into(),
+        Some(reply) => {
+            let response = RequestHttpResponse {
+                headers: reply
+                    .headers
+                    .iter()
+                    .map(|(key, value)| MessageKeyValueJsonModel {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+                base64_message: reply.content.into_base64(),
+            };
+
+            HttpOutput::as_json(response).into_ok_result(false).into()
+        }
+    }
+}
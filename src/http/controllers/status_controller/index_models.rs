@@ -2,11 +2,16 @@ use std::collections::BTreeMap;
 
 use crate::app::AppContext;
 
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
 use serde::{Deserialize, Serialize};
 
 use super::models::{
+    persistence_health_model::PersistenceHealthJsonModel,
+    persistence_target_model::PersistenceTargetJsonModel,
     queue_model::QueuesJsonResult,
     session_model::SessionsJsonResult,
+    throttled_publisher_model::ThrottledPublisherJsonModel,
     topic_model::{TopicJsonContract, TopicsJsonResult},
 };
 
@@ -33,6 +38,17 @@ pub struct StatusJsonResult {
     #[serde(rename = "mcpWritesRemainingSecs")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mcp_writes_remaining_secs: Option<u64>,
+    /// Publishers the rate limits held back during the last minute.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throttled: Vec<ThrottledPublisherJsonModel>,
+    /// Absent while writes to persistence succeed.
+    #[serde(rename = "persistenceDegraded")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistence_degraded: Option<PersistenceHealthJsonModel>,
+    /// Each persistence service the node writes to. Absent if there is one only.
+    #[serde(rename = "persistenceTargets")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub persistence_targets: Vec<PersistenceTargetJsonModel>,
 }
 
 impl StatusJsonResult {
@@ -73,6 +89,23 @@ impl StatusJsonResult {
             persistence_version: app.persistence_version.get(),
             version: crate::app::APP_VERSION.to_string(),
             mcp_writes_remaining_secs: app.mcp_writes_remaining_secs(),
+            throttled: app
+                .publish_rate_limiter
+                .get_throttled(DateTimeAsMicroseconds::now())
+                .into_iter()
+                .filter(|itm| itm.namespace.is_empty() || itm.namespace == namespace.name)
+                .map(ThrottledPublisherJsonModel::new)
+                .collect(),
+            persistence_degraded: app
+                .persistence_health
+                .get_snapshot()
+                .map(PersistenceHealthJsonModel::new),
+            persistence_targets: app
+                .persistence_client
+                .get_targets()
+                .into_iter()
+                .map(PersistenceTargetJsonModel::new)
+                .collect(),
             debug: app.debug_console.get_target().map(|t| match &t.queue_id {
                 Some(queue_id) => format!("{} / {}", t.topic_id, queue_id),
                 None => format!("{} / *", t.topic_id),
@@ -3,3 +3,6 @@ pub mod session_model;
 pub mod topic_model;
 pub mod topic_publisher;
 pub mod topic_queue_subscriber;
+pub mod throttled_publisher_model;
+pub mod persistence_health_model;
+pub mod persistence_target_model;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::PersistenceHealthSnapshot;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PersistenceHealthJsonModel {
+    pub route: String,
+    #[serde(rename = "degradedSince")]
+    pub degraded_since: String,
+    pub failures: u32,
+    #[serde(rename = "circuitOpen")]
+    pub circuit_open: bool,
+    #[serde(rename = "nextAttempt")]
+    pub next_attempt: String,
+    #[serde(rename = "lastError")]
+    pub last_error: String,
+}
+
+impl PersistenceHealthJsonModel {
+    pub fn new(src: PersistenceHealthSnapshot) -> Self {
+        Self {
+            route: src.route,
+            degraded_since: src.degraded_since.to_rfc3339(),
+            failures: src.failures,
+            circuit_open: src.circuit_is_open,
+            next_attempt: src.next_attempt.to_rfc3339(),
+            last_error: src.last_error,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::PersistenceTargetSnapshot;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PersistenceTargetJsonModel {
+    pub name: String,
+    pub primary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespaces: Option<String>,
+    pub healthy: bool,
+    pub failures: u32,
+    #[serde(rename = "lastError")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(rename = "lastSuccess")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<String>,
+    #[serde(rename = "queuedWrites")]
+    pub queued_writes: usize,
+    #[serde(rename = "lagMs")]
+    pub lag_ms: u128,
+    #[serde(rename = "droppedWrites")]
+    pub dropped_writes: usize,
+}
+
+impl PersistenceTargetJsonModel {
+    pub fn new(src: PersistenceTargetSnapshot) -> Self {
+        Self {
+            name: src.name,
+            primary: src.is_primary,
+            namespaces: src.namespaces,
+            healthy: src.failures == 0,
+            failures: src.failures,
+            last_error: src.last_error,
+            last_success: src.last_success.map(|itm| itm.to_rfc3339()),
+            queued_writes: src.queued_writes,
+            lag_ms: src.lag.as_millis(),
+            dropped_writes: src.dropped_writes,
+        }
+    }
+}
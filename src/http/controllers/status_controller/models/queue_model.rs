@@ -36,6 +36,33 @@ pub struct QueueJsonContract {
     #[serde(rename = "onDelivery")]
     on_delivery: usize,
     data: Vec<QueueIndex>,
+    #[serde(rename = "maxDeliveryAttempts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_delivery_attempts: Option<i32>,
+    #[serde(rename = "deadLetter")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dead_letter: Option<String>,
+    #[serde(rename = "deadLettered")]
+    dead_lettered: usize,
+    expired: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redelivery: Option<String>,
+    /// Failed messages waiting for their redelivery delay.
+    delayed: usize,
+    #[serde(rename = "orderingKey")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ordering_key: Option<String>,
+    #[serde(rename = "pinnedKeys")]
+    pinned_keys: usize,
+    #[serde(rename = "headerFilter")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    header_filter: Option<String>,
+    #[serde(rename = "filteredOut")]
+    filtered_out: usize,
+    paused: bool,
+    #[serde(rename = "deliveryTimeoutMs")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivery_timeout_ms: Option<u64>,
 }
 
 impl QueueJsonContract {
@@ -46,6 +73,40 @@ impl QueueJsonContract {
             size: topic_queue.get_queue_size(),
             on_delivery: topic_queue.get_on_delivery(),
             data: QueueIndex::get_queue_snapshot(topic_queue),
+            max_delivery_attempts: topic_queue
+                .dead_letter
+                .as_ref()
+                .map(|itm| itm.max_delivery_attempts),
+            dead_letter: topic_queue
+                .dead_letter
+                .as_ref()
+                .map(|itm| itm.get_target_as_string(topic_queue.topic_id.as_str())),
+            dead_lettered: topic_queue.dead_lettered,
+            expired: topic_queue.expired,
+            redelivery: topic_queue.redelivery.as_ref().map(|itm| {
+                format!(
+                    "{}:{}ms..{}ms",
+                    itm.back_off.as_str(),
+                    itm.delay.as_millis(),
+                    itm.max_delay.as_millis()
+                )
+            }),
+            delayed: topic_queue.delayed.len(),
+            ordering_key: topic_queue
+                .ordering_keys
+                .as_ref()
+                .map(|itm| itm.header.clone()),
+            pinned_keys: topic_queue
+                .ordering_keys
+                .as_ref()
+                .map(|itm| itm.get_pinned_amount())
+                .unwrap_or(0),
+            header_filter: topic_queue.header_filter.as_ref().map(|itm| itm.to_json()),
+            filtered_out: topic_queue.filtered_out,
+            paused: topic_queue.paused,
+            delivery_timeout_ms: topic_queue
+                .delivery_timeout
+                .map(|itm| itm.as_millis() as u64),
         }
     }
 }
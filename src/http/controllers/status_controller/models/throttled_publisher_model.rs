@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::ThrottledPublisher;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ThrottledPublisherJsonModel {
+    /// Absent if the limit is shared by every session.
+    #[serde(rename = "sessionName")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_name: Option<String>,
+    /// Absent if the limit is shared by every topic.
+    #[serde(rename = "topicId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic_id: Option<String>,
+    pub delayed: usize,
+    pub rejected: usize,
+    #[serde(rename = "lastThrottled")]
+    pub last_throttled: String,
+}
+
+impl ThrottledPublisherJsonModel {
+    pub fn new(src: ThrottledPublisher) -> Self {
+        Self {
+            session_name: get_optional(src.session_name),
+            topic_id: get_optional(src.topic_id),
+            delayed: src.delayed,
+            rejected: src.rejected,
+            last_throttled: src.last_throttled.to_rfc3339(),
+        }
+    }
+}
+
+fn get_optional(src: String) -> Option<String> {
+    if src.is_empty() {
+        None
+    } else {
+        Some(src)
+    }
+}
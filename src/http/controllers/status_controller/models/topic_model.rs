@@ -35,6 +35,85 @@ pub struct TopicJsonContract {
     pub subscribers: Vec<TopicQueueSubscriberJsonModel>,
     pub persist: bool,
     pub deleted: i64,
+    /// Messages published with a due time which has not come yet.
+    pub scheduled: usize,
+    #[serde(rename = "ttlMs")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_ms: Option<u64>,
+    #[serde(rename = "dedupWindowMs")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup_window_ms: Option<u64>,
+    /// Message keys the dedup window currently remembers.
+    #[serde(rename = "dedupKeys")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup_keys: Option<usize>,
+    #[serde(rename = "deduplicated")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deduplicated: Option<usize>,
+    #[serde(rename = "forwardRules")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forward_rules: Vec<ForwardRuleJsonModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub router: Option<ContentRouterJsonModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quotas: Option<TopicQuotasJsonModel>,
+    /// Compression of the topic itself. Absent while it uses the one of the settings.
+    #[serde(rename = "persistCompression")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persist_compression: Option<String>,
+}
+
+/// Limits of the topic next to what it uses now. An unset limit is absent.
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct TopicQuotasJsonModel {
+    #[serde(rename = "maxMemorySize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memory_size: Option<usize>,
+    #[serde(rename = "memorySize")]
+    pub memory_size: usize,
+    #[serde(rename = "maxPersistQueueSize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_persist_queue_size: Option<usize>,
+    #[serde(rename = "persistQueueSize")]
+    pub persist_queue_size: usize,
+    #[serde(rename = "maxQueueSize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_queue_size: Option<usize>,
+    #[serde(rename = "queueSize")]
+    pub queue_size: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct ContentRouterJsonModel {
+    pub header: String,
+    pub routes: Vec<ContentRouteJsonModel>,
+    #[serde(rename = "fallbackTopicId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_topic_id: Option<String>,
+    /// Messages sent to the fallback topic.
+    #[serde(rename = "fallenBack")]
+    pub fallen_back: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct ContentRouteJsonModel {
+    pub value: String,
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    pub routed: usize,
+    pub failed: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct ForwardRuleJsonModel {
+    #[serde(rename = "targetNamespace")]
+    pub target_namespace: String,
+    #[serde(rename = "targetTopicId")]
+    pub target_topic_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    pub forwarded: usize,
+    pub failed: usize,
 }
 
 impl TopicJsonContract {
@@ -70,6 +149,61 @@ impl TopicJsonContract {
             subscribers,
             persist: topic_data.persist,
             deleted: topic_data.deleted,
+            scheduled: topic_data.scheduled.len(),
+            ttl_ms: topic_data.ttl.map(|itm| itm.as_millis() as u64),
+            dedup_window_ms: topic_data
+                .dedup
+                .as_ref()
+                .and_then(|itm| itm.get_window())
+                .map(|itm| itm.as_millis() as u64),
+            dedup_keys: topic_data.dedup.as_ref().map(|itm| itm.len()),
+            deduplicated: topic_data.dedup.as_ref().map(|itm| itm.deduplicated),
+            forward_rules: topic_data
+                .forward_rules
+                .iter()
+                .map(|itm| ForwardRuleJsonModel {
+                    target_namespace: itm.namespace.clone(),
+                    target_topic_id: itm.topic_id.clone(),
+                    filter: itm.filter.as_ref().map(|filter| filter.to_json()),
+                    forwarded: itm.statistics.get_forwarded(),
+                    failed: itm.statistics.get_failed(),
+                })
+                .collect(),
+            router: topic_data
+                .router
+                .as_ref()
+                .map(|itm| ContentRouterJsonModel {
+                    header: itm.header.clone(),
+                    routes: itm
+                        .get_routes()
+                        .iter()
+                        .map(|route| ContentRouteJsonModel {
+                            value: route.value.clone(),
+                            topic_id: route.topic_id.clone(),
+                            routed: route.statistics.get_forwarded(),
+                            failed: route.statistics.get_failed(),
+                        })
+                        .collect(),
+                    fallback_topic_id: itm.fallback_topic_id.clone(),
+                    fallen_back: itm.fallback_statistics.get_forwarded(),
+                }),
+            quotas: if topic_data.quotas.is_empty() {
+                None
+            } else {
+                let usage = topic_data.get_quota_usage();
+
+                Some(TopicQuotasJsonModel {
+                    max_memory_size: topic_data.quotas.max_memory_size,
+                    memory_size: usage.memory_size,
+                    max_persist_queue_size: topic_data.quotas.max_persist_queue_size,
+                    persist_queue_size: usage.persist_queue_size,
+                    max_queue_size: topic_data.quotas.max_queue_size,
+                    queue_size: usage.queue_size,
+                })
+            },
+            persist_compression: topic_data
+                .persist_compression
+                .map(|itm| itm.as_str().to_string()),
             mean_message_size: topic_data.statistics.size_metrics.avg_message_size,
         }
     }
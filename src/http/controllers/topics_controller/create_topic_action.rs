@@ -34,6 +34,8 @@ async fn handle_request(
 ) -> Result<HttpOkResult, HttpFailResult> {
     let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
 
+    crate::operations::check_persistence_route(action.app.as_ref(), namespace.as_ref())?;
+
     crate::operations::create_topic_if_not_exists(&namespace, None, input_data.topic_id.as_ref())
         .await?;
 
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "DELETE",
+    route: "/api/Topics/Router",
+    input_data: DeleteContentRouterRequestContract,
+    description: "Stop routing messages of the topic. They stay in the topic again",
+    summary: "Delete content router of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Content router is deleted"},
+    ]
+)]
+pub struct DeleteContentRouterAction {
+    app: Arc<AppContext>,
+}
+
+impl DeleteContentRouterAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &DeleteContentRouterAction,
+    input_data: DeleteContentRouterRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    crate::operations::remove_content_router(&action.app, &namespace, input_data.topic_id).await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
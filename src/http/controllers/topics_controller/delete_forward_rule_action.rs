@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "DELETE",
+    route: "/api/Topics/Forward",
+    input_data: DeleteForwardRuleRequestContract,
+    description: "Stop forwarding messages of the topic to the target topic",
+    summary: "Delete forward rule of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Forward rule is deleted"},
+    ]
+)]
+pub struct DeleteForwardRuleAction {
+    app: Arc<AppContext>,
+}
+
+impl DeleteForwardRuleAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &DeleteForwardRuleAction,
+    input_data: DeleteForwardRuleRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    crate::operations::remove_forward_rule(
+        &action.app,
+        &namespace,
+        input_data.topic_id,
+        input_data.target_namespace,
+        input_data.target_topic_id,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -10,3 +10,19 @@ mod restore_topic_action;
 pub use restore_topic_action::*;
 mod update_persist_action;
 pub use update_persist_action::*;
+mod update_ttl_action;
+pub use update_ttl_action::*;
+mod update_dedup_action;
+pub use update_dedup_action::*;
+mod update_quotas_action;
+pub use update_quotas_action::*;
+mod update_persist_compression_action;
+pub use update_persist_compression_action::*;
+mod set_forward_rule_action;
+pub use set_forward_rule_action::*;
+mod delete_forward_rule_action;
+pub use delete_forward_rule_action::*;
+mod set_content_router_action;
+pub use set_content_router_action::*;
+mod delete_content_router_action;
+pub use delete_content_router_action::*;
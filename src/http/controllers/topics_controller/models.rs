@@ -1,6 +1,6 @@
 use crate::topics::Topic;
 
-use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
+use my_http_server::macros::{MyHttpInput, MyHttpInputObjectStructure, MyHttpObjectStructure};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 use serde::{Deserialize, Serialize};
 
@@ -52,3 +52,88 @@ pub struct UpdatePersistRequestContract {
     #[http_body(description = "Persist or not persist")]
     pub persist: bool,
 }
+
+#[derive(Debug, MyHttpInput)]
+pub struct UpdateTtlRequestContract {
+    #[http_body(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_body(name = "ttlMs"; description = "Messages older than this are not delivered. 0 - no TTL")]
+    pub ttl_ms: u64,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct UpdateDedupRequestContract {
+    #[http_body(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_body(name = "windowMs"; description = "Message keys are remembered this long. 0 - no time limit")]
+    pub window_ms: u64,
+    #[http_body(name = "maxKeys"; description = "Amount of message keys to remember. 0 - default amount, or no dedup if windowMs is 0 as well")]
+    pub max_keys: usize,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct UpdateQuotasRequestContract {
+    #[http_body(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_body(name = "maxMemorySize"; description = "Bytes of messages the topic keeps in memory. 0 - no limit")]
+    pub max_memory_size: usize,
+    #[http_body(name = "maxPersistQueueSize"; description = "Messages waiting to be persisted. 0 - no limit")]
+    pub max_persist_queue_size: usize,
+    #[http_body(name = "maxQueueSize"; description = "Messages waiting for delivery in all the queues together. 0 - no limit")]
+    pub max_queue_size: usize,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct UpdatePersistCompressionRequestContract {
+    #[http_body(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_body(name = "compression"; description = "none, deflate or zstd. default - the one of the settings")]
+    pub compression: String,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct SetForwardRuleRequestContract {
+    #[http_body(name = "topicId"; description = "Id of topic the messages are published to")]
+    pub topic_id: String,
+    #[http_body(name = "targetNamespace"; description = "Namespace of the target topic. Empty - the default one")]
+    pub target_namespace: Option<String>,
+    #[http_body(name = "targetTopicId"; description = "Id of topic the messages are forwarded to")]
+    pub target_topic_id: String,
+    #[http_body(name = "filter"; description = "Header filter as JSON. Empty - every message is forwarded")]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct DeleteForwardRuleRequestContract {
+    #[http_query(name = "topicId"; description = "Id of topic the messages are published to")]
+    pub topic_id: String,
+    #[http_query(name = "targetNamespace"; description = "Namespace of the target topic. Empty - the default one")]
+    pub target_namespace: Option<String>,
+    #[http_query(name = "targetTopicId"; description = "Id of topic the messages are forwarded to")]
+    pub target_topic_id: String,
+}
+
+#[derive(MyHttpInput)]
+pub struct SetContentRouterRequestContract {
+    #[http_body(name = "topicId"; description = "Id of topic the messages are published to")]
+    pub topic_id: String,
+    #[http_body(name = "header"; description = "Header the messages are routed by")]
+    pub header: String,
+    #[http_body(name = "routes"; description = "Topic to route the messages to, by the value of the header")]
+    pub routes: Vec<ContentRouteHttpModel>,
+    #[http_body(name = "fallbackTopicId"; description = "Topic for the messages with no route. Empty - they stay in the topic")]
+    pub fallback_topic_id: Option<String>,
+}
+
+#[derive(MyHttpInputObjectStructure, Serialize, Deserialize)]
+pub struct ContentRouteHttpModel {
+    pub value: String,
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+}
+
+#[derive(Debug, MyHttpInput)]
+pub struct DeleteContentRouterRequestContract {
+    #[http_query(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topics/Router",
+    input_data: SetContentRouterRequestContract,
+    description: "Route messages published to the topic to other topics by the value of a header",
+    summary: "Set content router of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Content router is set"},
+    ]
+)]
+pub struct SetContentRouterAction {
+    app: Arc<AppContext>,
+}
+
+impl SetContentRouterAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetContentRouterAction,
+    input_data: SetContentRouterRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let fallback_topic_id = match input_data.fallback_topic_id {
+        Some(fallback_topic_id) if !fallback_topic_id.trim().is_empty() => Some(fallback_topic_id),
+        _ => None,
+    };
+
+    crate::operations::set_content_router(
+        &action.app,
+        &namespace,
+        input_data.topic_id,
+        input_data.header,
+        input_data
+            .routes
+            .into_iter()
+            .map(|itm| (itm.value, itm.topic_id))
+            .collect(),
+        fallback_topic_id,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, queues::HeaderFilter};
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topics/Forward",
+    input_data: SetForwardRuleRequestContract,
+    description: "Forward messages published to the topic to another topic",
+    summary: "Set forward rule of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Forward rule is set"},
+    ]
+)]
+pub struct SetForwardRuleAction {
+    app: Arc<AppContext>,
+}
+
+impl SetForwardRuleAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &SetForwardRuleAction,
+    input_data: SetForwardRuleRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let filter = match input_data.filter {
+        Some(filter) if !filter.trim().is_empty() => Some(
+            HeaderFilter::from_json(filter.as_str())
+                .map_err(HttpFailResult::as_validation_error)?,
+        ),
+        _ => None,
+    };
+
+    crate::operations::add_forward_rule(
+        &action.app,
+        &namespace,
+        input_data.topic_id,
+        input_data.target_namespace,
+        input_data.target_topic_id,
+        filter,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
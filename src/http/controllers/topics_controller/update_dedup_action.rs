@@ -0,0 +1,53 @@
+use std::{sync::Arc, time::Duration};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topics/Dedup",
+    input_data: UpdateDedupRequestContract,
+    description: "Update publish deduplication window of the topic",
+    summary: "Update publish deduplication window of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Topic deduplication window is updated"},
+    ]
+)]
+pub struct UpdateDedupAction {
+    app: Arc<AppContext>,
+}
+
+impl UpdateDedupAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &UpdateDedupAction,
+    input_data: UpdateDedupRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let window = if input_data.window_ms > 0 {
+        Some(Duration::from_millis(input_data.window_ms))
+    } else {
+        None
+    };
+
+    let max_keys = if input_data.max_keys > 0 {
+        Some(input_data.max_keys)
+    } else {
+        None
+    };
+
+    crate::operations::update_topic_dedup(&namespace, input_data.topic_id, window, max_keys)
+        .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, persistence::PersistenceCompression};
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topics/PersistCompression",
+    input_data: UpdatePersistCompressionRequestContract,
+    description: "Update how messages of the topic travel to and from persistence",
+    summary: "Update persist compression of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Topic persist compression is updated"},
+    ]
+)]
+pub struct UpdatePersistCompressionAction {
+    app: Arc<AppContext>,
+}
+
+impl UpdatePersistCompressionAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &UpdatePersistCompressionAction,
+    input_data: UpdatePersistCompressionRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let persist_compression = match input_data.compression.as_str() {
+        "default" => None,
+        src => match PersistenceCompression::parse(src) {
+            Some(compression) => Some(compression),
+            None => {
+                return Err(HttpFailResult::as_validation_error(format!(
+                    "Invalid compression '{}'. Please use default, none, deflate or zstd",
+                    src
+                )))
+            }
+        },
+    };
+
+    crate::operations::update_topic_persist_compression(
+        &action.app,
+        &namespace,
+        input_data.topic_id,
+        persist_compression,
+    )
+    .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
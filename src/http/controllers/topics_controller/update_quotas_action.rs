@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::{app::AppContext, quotas::Quotas};
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topics/Quotas",
+    input_data: UpdateQuotasRequestContract,
+    description: "Update quotas of the topic. Publishes are refused with 429 while the topic is over any of them",
+    summary: "Update quotas of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Topic quotas are updated"},
+    ]
+)]
+pub struct UpdateQuotasAction {
+    app: Arc<AppContext>,
+}
+
+impl UpdateQuotasAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &UpdateQuotasAction,
+    input_data: UpdateQuotasRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let quotas = Quotas {
+        max_memory_size: get_limit(input_data.max_memory_size),
+        max_persist_queue_size: get_limit(input_data.max_persist_queue_size),
+        max_queue_size: get_limit(input_data.max_queue_size),
+    };
+
+    crate::operations::update_topic_quotas(&action.app, &namespace, input_data.topic_id, quotas)
+        .await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
+
+fn get_limit(src: usize) -> Option<usize> {
+    if src > 0 {
+        Some(src)
+    } else {
+        None
+    }
+}
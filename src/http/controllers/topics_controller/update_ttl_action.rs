@@ -0,0 +1,46 @@
+use std::{sync::Arc, time::Duration};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+
+use crate::app::AppContext;
+
+use super::models::*;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Topics/Ttl",
+    input_data: UpdateTtlRequestContract,
+    description: "Update message TTL of the topic",
+    summary: "Update message TTL of the topic",
+    controller: "Topics",
+    result:[
+        {status_code: 202, description: "Topic TTL is updated"},
+    ]
+)]
+pub struct UpdateTtlAction {
+    app: Arc<AppContext>,
+}
+
+impl UpdateTtlAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &UpdateTtlAction,
+    input_data: UpdateTtlRequestContract,
+    ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let namespace = crate::http::get_request_namespace(&action.app, ctx)?;
+
+    let ttl = if input_data.ttl_ms > 0 {
+        Some(Duration::from_millis(input_data.ttl_ms))
+    } else {
+        None
+    };
+
+    crate::operations::update_topic_ttl(&namespace, input_data.topic_id, ttl).await?;
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
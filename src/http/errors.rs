@@ -1,9 +1,33 @@
-use my_http_server::HttpFailResult;
+use my_http_server::{HttpFailResult, HttpOutput, HttpResponseHeaders, WebContentType};
 
 use crate::operations::OperationFailResult;
 
 impl From<OperationFailResult> for HttpFailResult {
     fn from(src: OperationFailResult) -> Self {
-        Self::as_forbidden(Some(format!("{:?}", src)))
+        match src {
+            OperationFailResult::QuotaExceeded(_) | OperationFailResult::PublishIsThrottled(_) => {
+                as_status_code(429, format!("{:?}", src))
+            }
+            OperationFailResult::PersistenceTimeout(_) => as_status_code(504, format!("{:?}", src)),
+            OperationFailResult::PersistenceRouteIsNotRestored { .. } => {
+                as_status_code(503, format!("{:?}", src))
+            }
+            _ => Self::as_forbidden(Some(format!("{:?}", src))),
+        }
     }
 }
+
+/// 429 - the publisher is expected to back off and retry, the same way it does
+/// with any other HTTP service. 504 - the messages are published, but the node
+/// could not confirm they are saved. 503 - the persistence of the namespace is
+/// down; it takes a restart of the node once it is back.
+fn as_status_code(status_code: u16, message: String) -> HttpFailResult {
+    let result: Result<(), HttpFailResult> = HttpOutput::Content {
+        status_code,
+        content: message.into_bytes(),
+        headers: HttpResponseHeaders::new(WebContentType::Text.into()),
+    }
+    .into_err(false, false);
+
+    result.unwrap_err()
+}
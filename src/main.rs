@@ -2,6 +2,7 @@ use app::AppContext;
 
 use background::{
     DeadSubscribersKickerTimer, GcDeletedTopicsTimer, GcTimer, MetricsTimer, PersistJob,
+    ScheduledMessagesTimer,
 };
 use my_tcp_sockets::{unix_socket_server::UnixSocketServer, TcpServer};
 use rust_extensions::MyTimer;
@@ -20,8 +21,10 @@ mod mcp;
 mod messages_page;
 mod metric_data;
 mod operations;
+mod persistence;
 mod queue_subscribers;
 mod queues;
+mod quotas;
 mod sessions;
 mod settings;
 mod sub_page;
@@ -29,6 +32,7 @@ mod tcp;
 #[cfg(test)]
 mod test_tools;
 mod utils;
+mod wal;
 
 mod background;
 mod namespaces;
@@ -45,10 +49,9 @@ async fn main() {
     let settings = settings::SettingsModel::read().await;
     let settings = Arc::new(settings);
 
-    let messages_repo =
-        crate::grpc_client::PersistenceGrpcService::create_production_instance(settings.clone());
+    let persistence_client = crate::persistence::create_persistence_backend(settings.clone());
 
-    let app = Arc::new(AppContext::new(messages_repo, settings).await);
+    let app = Arc::new(AppContext::new(persistence_client, settings).await);
 
     app.persist_executor
         .register(Arc::new(PersistJob::new(app.clone())));
@@ -97,6 +100,10 @@ async fn main() {
             tcp_server.threads_statistics,
         )),
     );
+    metrics_timer.register_timer(
+        "ScheduledMessages",
+        Arc::new(ScheduledMessagesTimer::new(app.clone())),
+    );
 
     let mut gc_timer = MyTimer::new(Duration::from_secs(3));
     gc_timer.register_timer("GC", Arc::new(GcTimer::new(app.clone())));
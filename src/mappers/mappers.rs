@@ -1,12 +1,19 @@
+use my_logger::LogEventCtx;
 use my_service_bus::abstractions::queue_with_intervals::QueueIndexRange;
 use my_service_bus::abstractions::subscriber::TopicQueueType;
 use my_service_bus::abstractions::SbMessageHeaders;
 use my_service_bus::shared::protobuf_models::MessageProtobufModel;
 use my_service_bus::shared::validators::DEFAULT_NAMESPACE;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
+use std::time::Duration;
 
 use crate::messages_page::MySbMessageContent;
-use crate::topics::{TopicQueueSnapshot, TopicSnapshot};
+use crate::persistence::PersistenceCompression;
+use crate::queues::{DeadLetterSettings, HeaderFilter, RedeliveryPolicy};
+use crate::quotas::Quotas;
+use crate::topics::{
+    ContentRouterSnapshot, ForwardRuleSnapshot, TopicQueueSnapshot, TopicSnapshot,
+};
 
 use crate::persistence_grpc::*;
 
@@ -44,6 +51,23 @@ impl From<&TopicSnapshot> for TopicAndQueuesSnapshotGrpcModel {
             } else {
                 Some(src.namespace.clone())
             },
+            scheduled_messages: src
+                .scheduled
+                .iter()
+                .map(|(message_id, deliver_at)| ScheduledMessageGrpcModel {
+                    message_id: *message_id,
+                    deliver_at: *deliver_at,
+                })
+                .collect(),
+            ttl_ms: src.ttl.map(|itm| itm.as_millis() as i64),
+            dedup_window_ms: src.dedup_window.map(|itm| itm.as_millis() as i64),
+            dedup_max_keys: src.dedup_max_keys.map(|itm| itm as i64),
+            forward_rules: src.forward_rules.iter().map(|itm| itm.into()).collect(),
+            router: src.router.as_ref().map(|itm| itm.into()),
+            max_memory_size: src.quotas.max_memory_size.map(|itm| itm as i64),
+            max_persist_queue_size: src.quotas.max_persist_queue_size.map(|itm| itm as i64),
+            max_queue_size: src.quotas.max_queue_size.map(|itm| itm as i64),
+            persist_compression: src.persist_compression.map(|itm| itm.as_grpc()),
         }
     }
 }
@@ -70,6 +94,101 @@ impl From<TopicAndQueuesSnapshotGrpcModel> for TopicSnapshot {
                 .into_iter()
                 .map(|itm| itm.into())
                 .collect(),
+            scheduled: src
+                .scheduled_messages
+                .into_iter()
+                .map(|itm| (itm.message_id, itm.deliver_at))
+                .collect(),
+            ttl: match src.ttl_ms {
+                Some(ttl_ms) if ttl_ms > 0 => Some(Duration::from_millis(ttl_ms as u64)),
+                _ => None,
+            },
+            dedup_window: match src.dedup_window_ms {
+                Some(window_ms) if window_ms > 0 => Some(Duration::from_millis(window_ms as u64)),
+                _ => None,
+            },
+            dedup_max_keys: match src.dedup_max_keys {
+                Some(max_keys) if max_keys > 0 => Some(max_keys as usize),
+                _ => None,
+            },
+            forward_rules: src
+                .forward_rules
+                .into_iter()
+                .map(|itm| itm.into())
+                .collect(),
+            router: src.router.map(|itm| itm.into()),
+            quotas: Quotas {
+                max_memory_size: get_quota(src.max_memory_size),
+                max_persist_queue_size: get_quota(src.max_persist_queue_size),
+                max_queue_size: get_quota(src.max_queue_size),
+            },
+            persist_compression: src
+                .persist_compression
+                .and_then(PersistenceCompression::from_grpc),
+        }
+    }
+}
+
+impl From<&ForwardRuleSnapshot> for ForwardRuleGrpcModel {
+    fn from(src: &ForwardRuleSnapshot) -> Self {
+        Self {
+            namespace: if src.namespace == DEFAULT_NAMESPACE {
+                None
+            } else {
+                Some(src.namespace.clone())
+            },
+            topic_id: src.topic_id.clone(),
+            header_filter: src.filter.as_ref().map(|itm| itm.to_json()),
+        }
+    }
+}
+
+impl From<ForwardRuleGrpcModel> for ForwardRuleSnapshot {
+    fn from(src: ForwardRuleGrpcModel) -> Self {
+        Self {
+            namespace: match src.namespace {
+                Some(namespace) if !namespace.is_empty() => namespace,
+                _ => DEFAULT_NAMESPACE.to_string(),
+            },
+            topic_id: src.topic_id,
+            filter: src
+                .header_filter
+                .as_ref()
+                .and_then(|itm| HeaderFilter::from_json(itm).ok()),
+        }
+    }
+}
+
+impl From<&ContentRouterSnapshot> for ContentRouterGrpcModel {
+    fn from(src: &ContentRouterSnapshot) -> Self {
+        Self {
+            header: src.header.clone(),
+            routes: src
+                .routes
+                .iter()
+                .map(|(value, topic_id)| ContentRouteGrpcModel {
+                    value: value.clone(),
+                    topic_id: topic_id.clone(),
+                })
+                .collect(),
+            fallback_topic_id: src.fallback_topic_id.clone(),
+        }
+    }
+}
+
+impl From<ContentRouterGrpcModel> for ContentRouterSnapshot {
+    fn from(src: ContentRouterGrpcModel) -> Self {
+        Self {
+            header: src.header,
+            routes: src
+                .routes
+                .into_iter()
+                .map(|itm| (itm.value, itm.topic_id))
+                .collect(),
+            fallback_topic_id: match src.fallback_topic_id {
+                Some(fallback_topic_id) if !fallback_topic_id.is_empty() => Some(fallback_topic_id),
+                _ => None,
+            },
         }
     }
 }
@@ -80,16 +199,68 @@ impl From<&TopicQueueSnapshot> for QueueSnapshotGrpcModel {
             queue_id: src.queue_id.to_string(),
             queue_type: src.queue_type.into_u8() as i32,
             ranges: src.ranges.iter().map(|itm| itm.into()).collect(),
+            max_delivery_attempts: src
+                .dead_letter
+                .as_ref()
+                .map(|itm| itm.max_delivery_attempts),
+            dead_letter_queue_id: src
+                .dead_letter
+                .as_ref()
+                .and_then(|itm| itm.get_dead_letter_queue_id())
+                .map(|itm| itm.to_string()),
+            redelivery_back_off: src
+                .redelivery
+                .as_ref()
+                .map(|itm| itm.back_off.into_u8() as i32),
+            redelivery_delay_ms: src
+                .redelivery
+                .as_ref()
+                .map(|itm| itm.delay.as_millis() as i64),
+            redelivery_max_delay_ms: src
+                .redelivery
+                .as_ref()
+                .map(|itm| itm.max_delay.as_millis() as i64),
+            ordering_key_header: src.ordering_key_header.clone(),
+            header_filter: src.header_filter.as_ref().map(|itm| itm.to_json()),
+            paused: Some(src.paused),
+            delivery_timeout_ms: src.delivery_timeout.map(|itm| itm.as_millis() as i64),
         }
     }
 }
 
 impl From<QueueSnapshotGrpcModel> for TopicQueueSnapshot {
     fn from(src: QueueSnapshotGrpcModel) -> Self {
+        let (header_filter, paused) = restore_header_filter(
+            src.queue_id.as_str(),
+            src.header_filter.as_deref(),
+            src.paused.unwrap_or(false),
+        );
+
         Self {
             queue_id: src.queue_id.to_string(),
             queue_type: TopicQueueType::from_u8(src.queue_type as u8),
             ranges: src.ranges.into_iter().map(|itm| itm.into()).collect(),
+            dead_letter: DeadLetterSettings::restore(
+                src.max_delivery_attempts,
+                src.dead_letter_queue_id,
+            ),
+            redelivery: RedeliveryPolicy::restore(
+                src.redelivery_back_off,
+                src.redelivery_delay_ms,
+                src.redelivery_max_delay_ms,
+            ),
+            ordering_key_header: match src.ordering_key_header {
+                Some(header) if !header.is_empty() => Some(header),
+                _ => None,
+            },
+            header_filter,
+            paused,
+            delivery_timeout: match src.delivery_timeout_ms {
+                Some(delivery_timeout_ms) if delivery_timeout_ms > 0 => {
+                    Some(Duration::from_millis(delivery_timeout_ms as u64))
+                }
+                _ => None,
+            },
         }
     }
 }
@@ -160,3 +331,74 @@ impl Into<TopicSnapshot> for TopicAndQueuesSnapshotGrpcModel {
     }
 }
  */
+
+fn get_quota(src: Option<i64>) -> Option<usize> {
+    match src {
+        Some(limit) if limit > 0 => Some(limit as usize),
+        _ => None,
+    }
+}
+
+/// A filter which can not be read back is not dropped silently: the queue would get
+/// every message of the topic. It is restored paused instead, until the filter is set
+/// again.
+fn restore_header_filter(
+    queue_id: &str,
+    src: Option<&str>,
+    paused: bool,
+) -> (Option<HeaderFilter>, bool) {
+    let Some(src) = src else {
+        return (None, paused);
+    };
+
+    match HeaderFilter::from_json(src) {
+        Ok(header_filter) => (Some(header_filter), paused),
+        Err(err) => {
+            my_logger::LOGGER.write_error(
+                "restore_header_filter",
+                format!("Queue is restored paused. {}", err),
+                LogEventCtx::new()
+                    .add("queueId", queue_id)
+                    .add("headerFilter", src),
+            );
+
+            (None, true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persistence_grpc::QueueSnapshotGrpcModel;
+    use crate::topics::TopicQueueSnapshot;
+
+    #[test]
+    fn test_queue_with_broken_header_filter_is_restored_paused() {
+        let src = QueueSnapshotGrpcModel {
+            queue_id: "test-queue".to_string(),
+            header_filter: Some("{not a filter".to_string()),
+            paused: Some(false),
+            ..Default::default()
+        };
+
+        let snapshot: TopicQueueSnapshot = src.into();
+
+        assert!(snapshot.header_filter.is_none());
+        assert!(snapshot.paused);
+    }
+
+    #[test]
+    fn test_queue_header_filter_is_restored() {
+        let src = QueueSnapshotGrpcModel {
+            queue_id: "test-queue".to_string(),
+            header_filter: Some(r#"{"op":"prefix","header":"type","value":"order."}"#.to_string()),
+            paused: Some(false),
+            ..Default::default()
+        };
+
+        let snapshot: TopicQueueSnapshot = src.into();
+
+        assert!(snapshot.header_filter.is_some());
+        assert!(!snapshot.paused);
+    }
+}
@@ -26,6 +26,8 @@ pub struct GetOverviewResponse {
     pub total_memory: u64,
     #[property(description = "Persistence service version reported by the persistence grpc client")]
     pub persistence_version: String,
+    #[property(description = "Set while writes to persistence fail: when they started failing (rfc3339)")]
+    pub persistence_degraded_since: Option<String>,
     #[property(description = "MyServiceBus main-node application version")]
     pub app_version: String,
 }
@@ -91,6 +93,11 @@ impl McpToolCall<GetOverviewInput, GetOverviewResponse> for GetOverviewHandler {
             used_memory: sys_info.used_memory(),
             total_memory: sys_info.total_memory(),
             persistence_version: self.app.persistence_version.get(),
+            persistence_degraded_since: self
+                .app
+                .persistence_health
+                .get_snapshot()
+                .map(|itm| itm.degraded_since.to_rfc3339()),
             app_version: APP_VERSION.to_string(),
         })
     }
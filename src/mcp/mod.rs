@@ -17,7 +17,11 @@ mod list_sessions_tool_call;
 mod list_topics_tool_call;
 mod persistence_get_message_tool_call;
 mod persistence_load_page_tool_call;
+mod purge_queue_tool_call;
+mod seek_queue_by_time_tool_call;
+mod set_queue_paused_tool_call;
 mod set_topic_persist_tool_call;
+mod set_topic_router_tool_call;
 
 mod write_gate;
 
@@ -34,14 +38,18 @@ pub use list_sessions_tool_call::*;
 pub use list_topics_tool_call::*;
 pub use persistence_get_message_tool_call::*;
 pub use persistence_load_page_tool_call::*;
+pub use purge_queue_tool_call::*;
+pub use seek_queue_by_time_tool_call::*;
+pub use set_queue_paused_tool_call::*;
 pub use set_topic_persist_tool_call::*;
+pub use set_topic_router_tool_call::*;
 
 pub fn build_middleware(app: Arc<AppContext>) -> McpMiddleware {
     let mut mcp = McpMiddleware::new(
         "/mcp",
         "my-service-bus",
         APP_VERSION,
-        "MyServiceBus stats: topics, queues, subscribers, sessions, in-memory pages and messages. Write tools (set topic persist, delete queue, delete topic) are refused unless a human has enabled MCP writes in the UI.",
+        "MyServiceBus stats: topics, queues, subscribers, sessions, in-memory pages and messages. Write tools (set topic persist, set topic router, seek queue by time, pause/resume queue, purge queue, delete queue, delete topic) are refused unless a human has enabled MCP writes in the UI.",
     );
 
     mcp.register_tool_call(Arc::new(GetOverviewHandler::new(app.clone())));
@@ -56,6 +64,10 @@ pub fn build_middleware(app: Arc<AppContext>) -> McpMiddleware {
     mcp.register_tool_call(Arc::new(PersistenceGetMessageHandler::new(app.clone())));
     mcp.register_tool_call(Arc::new(GetDebugConsoleHandler::new(app.clone())));
     mcp.register_tool_call(Arc::new(SetTopicPersistHandler::new(app.clone())));
+    mcp.register_tool_call(Arc::new(SetTopicRouterHandler::new(app.clone())));
+    mcp.register_tool_call(Arc::new(SeekQueueByTimeHandler::new(app.clone())));
+    mcp.register_tool_call(Arc::new(SetQueuePausedHandler::new(app.clone())));
+    mcp.register_tool_call(Arc::new(PurgeQueueHandler::new(app.clone())));
     mcp.register_tool_call(Arc::new(DeleteQueueHandler::new(app.clone())));
     mcp.register_tool_call(Arc::new(DeleteTopicHandler::new(app.clone())));
 
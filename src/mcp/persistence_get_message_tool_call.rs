@@ -88,6 +88,11 @@ impl McpToolCall<PersistenceGetMessageInput, PersistenceGetMessageResponse> for
         let sub_page_id_value = sub_page_id.get_value();
         let page_no = page_id.get_value();
 
+        let compression = match namespace.topic_list.get(model.topic_id.as_str()) {
+            Some(topic) => topic.get_persist_compression(self.app.settings.persist_compression),
+            None => self.app.settings.persist_compression,
+        };
+
         let result = self
             .app
             .persistence_client
@@ -97,6 +102,7 @@ impl McpToolCall<PersistenceGetMessageInput, PersistenceGetMessageResponse> for
                 page_id,
                 sub_page_id.get_first_message_id(),
                 sub_page_id.get_last_message_id(),
+                compression,
             )
             .await
             .map_err(|err| {
@@ -89,6 +89,11 @@ impl McpToolCall<PersistenceLoadPageInput, PersistenceLoadPageResponse> for Pers
         let to_value = to_message_id.get_value();
         let range_capacity = (to_value - from_value + 1).max(0) as usize;
 
+        let compression = match namespace.topic_list.get(model.topic_id.as_str()) {
+            Some(topic) => topic.get_persist_compression(self.app.settings.persist_compression),
+            None => self.app.settings.persist_compression,
+        };
+
         let result = self
             .app
             .persistence_client
@@ -98,6 +103,7 @@ impl McpToolCall<PersistenceLoadPageInput, PersistenceLoadPageResponse> for Pers
                 page_id,
                 from_message_id,
                 to_message_id,
+                compression,
             )
             .await
             .map_err(|err| {
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use mcp_server_middleware::*;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct PurgeQueueInput {
+    #[property(description = "Topic id the queue belongs to")]
+    pub topic_id: String,
+    #[property(description = "Queue id to purge")]
+    pub queue_id: String,
+    #[property(
+        description = "How many newest messages to leave in the queue. Optional, defaults to 0"
+    )]
+    pub keep_last: Option<usize>,
+    #[property(description = "Namespace to work in. Optional, absent means the default namespace")]
+    pub namespace: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct PurgeQueueResponse {
+    #[property(description = "Topic id")]
+    pub topic_id: String,
+    #[property(description = "Queue id")]
+    pub queue_id: String,
+    #[property(description = "How many pending messages were dropped")]
+    pub dropped: usize,
+    #[property(description = "How many messages the queue holds after the purge")]
+    pub size: i64,
+}
+
+pub struct PurgeQueueHandler {
+    app: Arc<AppContext>,
+}
+
+impl PurgeQueueHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for PurgeQueueHandler {
+    const FUNC_NAME: &'static str = "mysb_purge_queue";
+    const DESCRIPTION: &'static str =
+        "Drops the backlog of a queue: every pending message except the newest keep_last (0 by default) is removed from the queue and will never be delivered to it, and failed-delivery counters are reset. Messages which are on delivery right now are not touched. This is a DESTRUCTIVE WRITE operation and it is IRREVERSIBLE; every purge is written to the log with the dropped count. Requires MCP writes to be enabled by a human in the UI.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<PurgeQueueInput, PurgeQueueResponse> for PurgeQueueHandler {
+    async fn execute_tool_call(
+        &self,
+        model: PurgeQueueInput,
+    ) -> Result<PurgeQueueResponse, String> {
+        super::write_gate::ensure_mcp_writes_enabled(self.app.as_ref())?;
+
+        let namespace = self
+            .app
+            .namespaces
+            .get_or_create_optional(model.namespace.as_deref())
+            .map_err(|err| format!("Invalid namespace. {}", err))?;
+
+        let dropped = crate::operations::queues::purge_queue(
+            &namespace,
+            model.topic_id.as_str(),
+            model.queue_id.as_str(),
+            model.keep_last.unwrap_or(0),
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to purge queue '{}' of topic '{}': {:?}",
+                model.queue_id, model.topic_id, err
+            )
+        })?;
+
+        let size = namespace
+            .topic_list
+            .get(&model.topic_id)
+            .and_then(|topic| {
+                topic.get_topic_info(|inner| {
+                    inner
+                        .queues
+                        .get(model.queue_id.as_str())
+                        .map(|queue| queue.get_queue_size() as i64)
+                })
+            })
+            .unwrap_or(0);
+
+        Ok(PurgeQueueResponse {
+            topic_id: model.topic_id,
+            queue_id: model.queue_id,
+            dropped,
+            size,
+        })
+    }
+}
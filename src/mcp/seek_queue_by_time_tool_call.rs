@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use mcp_server_middleware::*;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SeekQueueByTimeInput {
+    #[property(description = "Topic id the queue belongs to")]
+    pub topic_id: String,
+    #[property(description = "Queue id to move")]
+    pub queue_id: String,
+    #[property(
+        description = "Moment to seek to: RFC3339 date (e.g. 2024-05-01T14:05:00Z) or unix milliseconds"
+    )]
+    pub from_date: String,
+    #[property(description = "Namespace to work in. Optional, absent means the default namespace")]
+    pub namespace: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SeekQueueByTimeResponse {
+    #[property(description = "Topic id")]
+    pub topic_id: String,
+    #[property(description = "Queue id")]
+    pub queue_id: String,
+    #[property(description = "Message id the queue starts from now")]
+    pub message_id: i64,
+    #[property(description = "How many messages the queue held before the seek")]
+    pub previous_size: i64,
+    #[property(description = "How many messages the queue holds after the seek")]
+    pub size: i64,
+}
+
+pub struct SeekQueueByTimeHandler {
+    app: Arc<AppContext>,
+}
+
+impl SeekQueueByTimeHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for SeekQueueByTimeHandler {
+    const FUNC_NAME: &'static str = "mysb_seek_queue_by_time";
+    const DESCRIPTION: &'static str =
+        "Moves a queue to the first message created at the given moment or later: everything before it is dropped from the queue, everything after it (including already consumed messages) is delivered again. In-memory pages are checked first, then the persistence service. If no message is that new the queue is moved to the end of the topic. This is a WRITE operation and requires MCP writes to be enabled by a human in the UI.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<SeekQueueByTimeInput, SeekQueueByTimeResponse> for SeekQueueByTimeHandler {
+    async fn execute_tool_call(
+        &self,
+        model: SeekQueueByTimeInput,
+    ) -> Result<SeekQueueByTimeResponse, String> {
+        super::write_gate::ensure_mcp_writes_enabled(self.app.as_ref())?;
+
+        let from = crate::utils::parse_date_time(model.from_date.as_str()).ok_or_else(|| {
+            format!(
+                "Invalid from_date '{}'. Expected RFC3339 date or unix milliseconds",
+                model.from_date
+            )
+        })?;
+
+        let namespace = self
+            .app
+            .namespaces
+            .get_or_create_optional(model.namespace.as_deref())
+            .map_err(|err| format!("Invalid namespace. {}", err))?;
+
+        let topic = namespace
+            .topic_list
+            .get(&model.topic_id)
+            .ok_or_else(|| format!("Topic '{}' not found", model.topic_id))?;
+
+        let get_size = || {
+            topic.get_topic_info(|inner| {
+                inner
+                    .queues
+                    .get(model.queue_id.as_str())
+                    .map(|queue| queue.get_queue_size() as i64)
+                    .unwrap_or(0)
+            })
+        };
+
+        let previous_size = get_size();
+
+        let message_id = crate::operations::queues::seek_by_time(
+            self.app.as_ref(),
+            &namespace,
+            model.topic_id.as_str(),
+            model.queue_id.as_str(),
+            from,
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to seek queue '{}' of topic '{}': {:?}",
+                model.queue_id, model.topic_id, err
+            )
+        })?;
+
+        let size = get_size();
+
+        Ok(SeekQueueByTimeResponse {
+            topic_id: model.topic_id,
+            queue_id: model.queue_id,
+            message_id: message_id.get_value(),
+            previous_size,
+            size,
+        })
+    }
+}
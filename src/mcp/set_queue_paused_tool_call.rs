@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use mcp_server_middleware::*;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SetQueuePausedInput {
+    #[property(description = "Topic id the queue belongs to")]
+    pub topic_id: String,
+    #[property(description = "Queue id to pause or resume")]
+    pub queue_id: String,
+    #[property(description = "true pauses delivery, false resumes it. Optional, defaults to true")]
+    pub paused: Option<bool>,
+    #[property(description = "Namespace to work in. Optional, absent means the default namespace")]
+    pub namespace: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SetQueuePausedResponse {
+    #[property(description = "Topic id")]
+    pub topic_id: String,
+    #[property(description = "Queue id")]
+    pub queue_id: String,
+    #[property(description = "Paused flag the queue had before this call")]
+    pub previous_paused: bool,
+    #[property(description = "Paused flag the queue has after this call")]
+    pub paused: bool,
+    #[property(
+        description = "true when the call actually changed the flag (false = already configured)"
+    )]
+    pub changed: bool,
+}
+
+pub struct SetQueuePausedHandler {
+    app: Arc<AppContext>,
+}
+
+impl SetQueuePausedHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for SetQueuePausedHandler {
+    const FUNC_NAME: &'static str = "mysb_set_queue_paused";
+    const DESCRIPTION: &'static str =
+        "Pauses or resumes delivery for one queue. A paused queue keeps its subscribers connected and keeps accumulating messages, it just delivers nothing until it is resumed; resuming starts delivery of everything that piled up. Pass paused=true to pause (the default) or paused=false to resume. The flag survives a restart. This is a WRITE operation and requires MCP writes to be enabled by a human in the UI.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<SetQueuePausedInput, SetQueuePausedResponse> for SetQueuePausedHandler {
+    async fn execute_tool_call(
+        &self,
+        model: SetQueuePausedInput,
+    ) -> Result<SetQueuePausedResponse, String> {
+        super::write_gate::ensure_mcp_writes_enabled(self.app.as_ref())?;
+
+        let paused = model.paused.unwrap_or(true);
+
+        let namespace = self
+            .app
+            .namespaces
+            .get_or_create_optional(model.namespace.as_deref())
+            .map_err(|err| format!("Invalid namespace. {}", err))?;
+
+        let previous_paused = crate::operations::queues::set_paused(
+            self.app.as_ref(),
+            &namespace,
+            model.topic_id.as_str(),
+            model.queue_id.as_str(),
+            paused,
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to set paused for queue '{}' of topic '{}': {:?}",
+                model.queue_id, model.topic_id, err
+            )
+        })?;
+
+        Ok(SetQueuePausedResponse {
+            topic_id: model.topic_id,
+            queue_id: model.queue_id,
+            previous_paused,
+            paused,
+            changed: previous_paused != paused,
+        })
+    }
+}
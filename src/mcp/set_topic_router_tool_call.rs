@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use mcp_server_middleware::*;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct TopicRouteInput {
+    #[property(description = "Header value the route is taken for")]
+    pub value: String,
+    #[property(description = "Topic the messages with this header value are routed to")]
+    pub topic_id: String,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SetTopicRouterInput {
+    #[property(description = "Topic id to configure")]
+    pub topic_id: String,
+    #[property(
+        description = "Header the messages are routed by. Optional, absent or empty removes the router of the topic"
+    )]
+    pub header: Option<String>,
+    #[property(
+        description = "Route table: header value -> destination topic. Replaces the current one"
+    )]
+    pub routes: Option<Vec<TopicRouteInput>>,
+    #[property(
+        description = "Topic for the messages with no route. Optional, absent means such messages stay in the topic"
+    )]
+    pub fallback_topic_id: Option<String>,
+    #[property(description = "Namespace to work in. Optional, absent means the default namespace")]
+    pub namespace: Option<String>,
+}
+
+#[derive(ApplyJsonSchema, Debug, Serialize, Deserialize)]
+pub struct SetTopicRouterResponse {
+    #[property(description = "Topic id")]
+    pub topic_id: String,
+    #[property(description = "true when the topic routes its messages after this call")]
+    pub routing: bool,
+    #[property(description = "Amount of routes in the route table")]
+    pub routes: usize,
+}
+
+pub struct SetTopicRouterHandler {
+    app: Arc<AppContext>,
+}
+
+impl SetTopicRouterHandler {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl ToolDefinition for SetTopicRouterHandler {
+    const FUNC_NAME: &'static str = "mysb_set_topic_router";
+    const DESCRIPTION: &'static str =
+        "Makes a topic a content router: each published message goes to the topic its header value is routed to, messages with no route go to the fallback topic (or stay in the topic when there is none). Destination topics are created on the way. The whole route table is replaced; pass no header to remove the router. This is a WRITE operation.";
+}
+
+#[async_trait::async_trait]
+impl McpToolCall<SetTopicRouterInput, SetTopicRouterResponse> for SetTopicRouterHandler {
+    async fn execute_tool_call(
+        &self,
+        model: SetTopicRouterInput,
+    ) -> Result<SetTopicRouterResponse, String> {
+        super::write_gate::ensure_mcp_writes_enabled(self.app.as_ref())?;
+
+        let namespace = self
+            .app
+            .namespaces
+            .get_or_create_optional(model.namespace.as_deref())
+            .map_err(|err| format!("Invalid namespace. {}", err))?;
+
+        let header = match model.header {
+            Some(header) if !header.trim().is_empty() => header,
+            _ => {
+                crate::operations::remove_content_router(
+                    &self.app,
+                    &namespace,
+                    model.topic_id.clone(),
+                )
+                .await
+                .map_err(|err| {
+                    format!(
+                        "Failed to remove router of topic '{}': {:?}",
+                        model.topic_id, err
+                    )
+                })?;
+
+                return Ok(SetTopicRouterResponse {
+                    topic_id: model.topic_id,
+                    routing: false,
+                    routes: 0,
+                });
+            }
+        };
+
+        let routes: Vec<(String, String)> = model
+            .routes
+            .unwrap_or_default()
+            .into_iter()
+            .map(|itm| (itm.value, itm.topic_id))
+            .collect();
+
+        let routes_amount = routes.len();
+
+        let fallback_topic_id = match model.fallback_topic_id {
+            Some(fallback_topic_id) if !fallback_topic_id.trim().is_empty() => {
+                Some(fallback_topic_id)
+            }
+            _ => None,
+        };
+
+        crate::operations::set_content_router(
+            &self.app,
+            &namespace,
+            model.topic_id.clone(),
+            header,
+            routes,
+            fallback_topic_id,
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to set router of topic '{}': {:?}",
+                model.topic_id, err
+            )
+        })?;
+
+        Ok(SetTopicRouterResponse {
+            topic_id: model.topic_id,
+            routing: true,
+            routes: routes_amount,
+        })
+    }
+}
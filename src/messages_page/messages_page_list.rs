@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 
 use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
 use my_service_bus::shared::{page_id::PageId, sub_page::SubPageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_extensions::sorted_vec::{GetMutOrCreateEntry, SortedVec};
 
 use super::*;
@@ -75,6 +76,53 @@ impl MessagesPageList {
         None
     }
 
+    /// First cached message created at `from` or later. `None` unless the message right
+    /// before it is cached too and is older than `from`: otherwise the answer may be a
+    /// message which lives in the persistence only.
+    pub fn find_first_message_id_by_time(&self, from: DateTimeAsMicroseconds) -> Option<i64> {
+        // Id of the last loaded message which is older than `from`.
+        let mut older_id = None;
+
+        for sub_page in self.sub_pages.iter() {
+            for meta in sub_page.get_messages_meta() {
+                if !meta.loaded {
+                    older_id = None;
+                    continue;
+                }
+
+                if meta.created_unix_microseconds < from.unix_microseconds {
+                    older_id = Some(meta.message_id);
+                    continue;
+                }
+
+                if older_id == Some(meta.message_id - 1) {
+                    return Some(meta.message_id);
+                }
+
+                return None;
+            }
+        }
+
+        None
+    }
+
+    /// First cached message created at `from` or later, with no regard to what may be
+    /// in the persistence. For topics which do not persist: the cache is all there is.
+    pub fn find_first_cached_message_id_by_time(
+        &self,
+        from: DateTimeAsMicroseconds,
+    ) -> Option<i64> {
+        for sub_page in self.sub_pages.iter() {
+            for meta in sub_page.get_messages_meta() {
+                if meta.loaded && meta.created_unix_microseconds >= from.unix_microseconds {
+                    return Some(meta.message_id);
+                }
+            }
+        }
+
+        None
+    }
+
     pub fn gc_all_except(&mut self, keep: SubPageId) {
         let mut to_remove = Vec::new();
 
@@ -189,6 +237,8 @@ impl MessagesPageList {
 
 #[cfg(test)]
 mod tests {
+    use my_service_bus::abstractions::{MessageId, SbMessageHeaders};
+
     use super::*;
 
     #[test]
@@ -236,4 +286,42 @@ mod tests {
             None
         );
     }
+
+    fn add_message(list: &mut MessagesPageList, id: i64, time: i64) {
+        let message_id: MessageId = id.into();
+        list.get_or_create_mut(message_id.into()).add_message(
+            MySbMessageContent::new(
+                message_id,
+                Vec::new(),
+                SbMessageHeaders::new(),
+                DateTimeAsMicroseconds::new(time),
+            ),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_find_first_message_id_by_time() {
+        let mut list = MessagesPageList::new();
+
+        add_message(&mut list, 10, 100);
+        add_message(&mut list, 11, 200);
+        add_message(&mut list, 12, 300);
+
+        assert_eq!(
+            list.find_first_message_id_by_time(DateTimeAsMicroseconds::new(150)),
+            Some(11)
+        );
+
+        // Message 9 and older are not cached, so the answer may be in the persistence.
+        assert_eq!(
+            list.find_first_message_id_by_time(DateTimeAsMicroseconds::new(50)),
+            None
+        );
+
+        assert_eq!(
+            list.find_first_message_id_by_time(DateTimeAsMicroseconds::new(400)),
+            None
+        );
+    }
 }
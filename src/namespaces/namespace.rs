@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use my_service_bus::shared::validators::DEFAULT_NAMESPACE;
 
-use crate::topics::TopicsList;
+use crate::{quotas::SharedQuotaUsage, topics::TopicsList, wal::MessagesWal};
 
 /// A namespace owns its topics and shares nothing with the others: a topic name is
 /// unique only inside one namespace, so `orders` in `default` and `orders` in
@@ -8,13 +10,16 @@ use crate::topics::TopicsList;
 pub struct Namespace {
     pub name: String,
     pub topic_list: TopicsList,
+    /// What the quotas of the namespace are checked against.
+    pub quota_usage: SharedQuotaUsage,
 }
 
 impl Namespace {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, wal: Option<Arc<MessagesWal>>) -> Self {
         Self {
-            topic_list: TopicsList::new(name.clone()),
+            topic_list: TopicsList::new(name.clone(), wal),
             name,
+            quota_usage: SharedQuotaUsage::new(),
         }
     }
 
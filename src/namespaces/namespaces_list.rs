@@ -5,6 +5,8 @@ use my_service_bus::shared::validators::{
     validate_namespace_name, InvalidNamespaceName, DEFAULT_NAMESPACE,
 };
 
+use crate::wal::MessagesWal;
+
 use super::Namespace;
 
 /// A node carries single digits of namespaces, so a lookup is a linear scan over a
@@ -14,17 +16,19 @@ use super::Namespace;
 pub struct NamespacesList {
     inner: ArcSwap<Vec<Arc<Namespace>>>,
     write_lock: Mutex<()>,
+    wal: Option<Arc<MessagesWal>>,
 }
 
 impl NamespacesList {
-    pub fn new() -> Self {
+    pub fn new(wal: Option<Arc<MessagesWal>>) -> Self {
         // The default namespace exists from the start: it is where every
         // pre-namespace client works, and resolving it must never fail.
-        let default = Arc::new(Namespace::new(DEFAULT_NAMESPACE.to_string()));
+        let default = Arc::new(Namespace::new(DEFAULT_NAMESPACE.to_string(), wal.clone()));
 
         Self {
             inner: ArcSwap::from_pointee(vec![default]),
             write_lock: Mutex::new(()),
+            wal,
         }
     }
 
@@ -62,7 +66,7 @@ impl NamespacesList {
             return Ok(result);
         }
 
-        let namespace = Arc::new(Namespace::new(name.to_string()));
+        let namespace = Arc::new(Namespace::new(name.to_string(), self.wal.clone()));
 
         let mut new_list = self.inner.load().as_ref().clone();
         new_list.push(namespace.clone());
@@ -91,7 +95,7 @@ mod tests {
 
     #[test]
     fn test_default_namespace_exists_from_the_start() {
-        let namespaces = NamespacesList::new();
+        let namespaces = NamespacesList::new(None);
 
         assert_eq!(DEFAULT_NAMESPACE, namespaces.get_default().name.as_str());
         assert_eq!(1, namespaces.get_all().len());
@@ -99,7 +103,7 @@ mod tests {
 
     #[test]
     fn test_namespace_is_created_on_first_mention_and_reused_afterwards() {
-        let namespaces = NamespacesList::new();
+        let namespaces = NamespacesList::new(None);
 
         let first = namespaces.get_or_create("alpha").unwrap();
         let second = namespaces.get_or_create("alpha").unwrap();
@@ -110,7 +114,7 @@ mod tests {
 
     #[test]
     fn test_invalid_name_is_an_error_and_creates_nothing() {
-        let namespaces = NamespacesList::new();
+        let namespaces = NamespacesList::new(None);
 
         assert_eq!(true, namespaces.get_or_create("Alpha").is_err());
         assert_eq!(1, namespaces.get_all().len());
@@ -118,7 +122,7 @@ mod tests {
 
     #[test]
     fn test_no_name_resolves_to_the_default_namespace() {
-        let namespaces = NamespacesList::new();
+        let namespaces = NamespacesList::new(None);
 
         assert_eq!(
             DEFAULT_NAMESPACE,
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+
+use crate::{app::AppContext, namespaces::Namespace, topics::ContentRouter};
+
+use super::OperationFailResult;
+
+/// Replaces the whole route table of the topic. `routes` are
+/// `(header value, destination topic id)` pairs.
+pub async fn set_content_router(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    header: String,
+    routes: Vec<(String, String)>,
+    fallback_topic_id: Option<String>,
+) -> Result<(), OperationFailResult> {
+    let topic = match namespace.topic_list.get(topic_id.as_str()) {
+        Some(topic) => topic,
+        None => return Err(OperationFailResult::TopicNotFound { topic_id }),
+    };
+
+    if header.trim().is_empty() {
+        return Err(OperationFailResult::Other(
+            "Header to route by is not set".to_string(),
+        ));
+    }
+
+    let destinations = routes
+        .iter()
+        .map(|(_, destination)| destination)
+        .chain(fallback_topic_id.iter());
+
+    for destination in destinations {
+        my_service_bus::shared::validators::validate_topic_name(destination)?;
+
+        if super::leads_to(
+            app,
+            namespace.name.as_str(),
+            destination.as_str(),
+            namespace.name.as_str(),
+            topic_id.as_str(),
+        ) {
+            return Err(OperationFailResult::Other(format!(
+                "Routing {}/{} to {} makes a cycle",
+                namespace.name, topic_id, destination
+            )));
+        }
+    }
+
+    my_logger::LOGGER.write_info(
+        "set_content_router",
+        "Content router is set",
+        LogEventCtx::new()
+            .add("namespace", namespace.name.as_str())
+            .add("topicId", topic_id.as_str())
+            .add("header", header.as_str())
+            .add("routes", routes.len().to_string()),
+    );
+
+    topic.get_access().router = Some(ContentRouter::new(
+        topic.namespace.clone(),
+        header,
+        routes,
+        fallback_topic_id,
+    ));
+
+    app.persist_executor.trigger();
+
+    Ok(())
+}
+
+/// Returns false if the topic was not a router.
+pub async fn remove_content_router(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+) -> Result<bool, OperationFailResult> {
+    let topic = match namespace.topic_list.get(topic_id.as_str()) {
+        Some(topic) => topic,
+        None => return Err(OperationFailResult::TopicNotFound { topic_id }),
+    };
+
+    let removed = topic.get_access().router.take().is_some();
+
+    if removed {
+        app.persist_executor.trigger();
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    #[tokio::test]
+    async fn test_routed_messages_land_in_auto_created_topics() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let orders = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "orders",
+        )
+        .await
+        .unwrap();
+
+        super::set_content_router(
+            &app,
+            &namespace,
+            "orders".to_string(),
+            "region".to_string(),
+            vec![("eu".to_string(), "orders-eu".to_string())],
+            Some("orders-other".to_string()),
+        )
+        .await
+        .unwrap();
+
+        let messages = vec![
+            MessageToPublish {
+                headers: SbMessageHeaders::new().add("region".to_string(), "eu".to_string()),
+                content: vec![1u8],
+            },
+            MessageToPublish {
+                headers: SbMessageHeaders::new().add("region".to_string(), "ap".to_string()),
+                content: vec![2u8],
+            },
+        ];
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            "orders",
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(0, orders.get_message_id().get_value());
+
+        let orders_eu = namespace.topic_list.get("orders-eu").unwrap();
+        assert_eq!(1, orders_eu.get_message_id().get_value());
+
+        let orders_other = namespace.topic_list.get("orders-other").unwrap();
+        assert_eq!(1, orders_other.get_message_id().get_value());
+    }
+
+    #[tokio::test]
+    async fn test_route_which_comes_back_is_refused() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        for topic_id in ["orders", "orders-eu"] {
+            crate::operations::create_topic_if_not_exists(
+                &namespace,
+                Some(session.session_id),
+                topic_id,
+            )
+            .await
+            .unwrap();
+        }
+
+        crate::operations::add_forward_rule(
+            &app,
+            &namespace,
+            "orders-eu".to_string(),
+            None,
+            "orders".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        for (routes, fallback_topic_id) in [
+            (vec![("eu".to_string(), "orders-eu".to_string())], None),
+            (Vec::new(), Some("orders-eu".to_string())),
+            (Vec::new(), Some("orders".to_string())),
+        ] {
+            let result = super::set_content_router(
+                &app,
+                &namespace,
+                "orders".to_string(),
+                "region".to_string(),
+                routes,
+                fallback_topic_id,
+            )
+            .await;
+
+            assert!(result.is_err());
+        }
+    }
+}
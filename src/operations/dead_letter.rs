@@ -0,0 +1,245 @@
+use std::{sync::Arc, time::Duration};
+
+use my_logger::LogEventCtx;
+use my_service_bus::abstractions::{publisher::MessageToPublish, subscriber::TopicQueueType};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::AppContext,
+    messages_page::MySbMessageContent,
+    queues::{
+        get_dead_letter_topic_id, DeadLetterTarget, QueueId, DEAD_LETTER_ATTEMPTS_HEADER,
+        DEAD_LETTER_QUEUE_HEADER,
+    },
+    topics::{Topic, TopicInner},
+};
+
+use super::OperationFailResult;
+
+/// Shortest wait before a message which the dead-letter topic did not take is tried
+/// again. A longer redelivery delay of its queue wins.
+pub const DEAD_LETTER_TOPIC_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Message which has run out of delivery attempts. It is already dequeued from its
+/// queue by the time it gets here.
+pub struct DeadLetterMessage {
+    pub queue_id: QueueId,
+    pub attempts: i32,
+    pub message: MySbMessageContent,
+}
+
+/// Moves messages to the dead-letter target of their queue. Returns true if some
+/// sibling queue of the topic got messages and delivery has to run again.
+pub fn move_to_dead_letter(
+    app: &AppContext,
+    topic: &Arc<Topic>,
+    topic_data: &mut TopicInner,
+    dead_letters: Vec<DeadLetterMessage>,
+) -> bool {
+    let mut moved_to_queue = false;
+
+    let mut to_topic = Vec::new();
+
+    for dead_letter in dead_letters {
+        let target = topic_data
+            .queues
+            .get(dead_letter.queue_id.as_str())
+            .and_then(|queue| queue.dead_letter.as_ref().map(|itm| itm.target.clone()));
+
+        match target {
+            Some(DeadLetterTarget::Queue(target_queue_id)) => {
+                let ids = topic_data.store_messages(vec![to_dead_letter_copy(&dead_letter)]);
+                topic_data.statistics.update_messages_count(1);
+
+                match topic_data.queues.get_mut(target_queue_id.as_str()) {
+                    Some(target_queue) => {
+                        target_queue.enqueue_messages(&ids);
+                    }
+                    None => {
+                        let target_queue = topic_data.queues.add_queue_if_not_exists(
+                            topic.topic_id.clone(),
+                            target_queue_id,
+                            TopicQueueType::Permanent,
+                        );
+                        target_queue.enqueue_messages(&ids);
+                    }
+                }
+
+                mark_as_dead_lettered(topic, topic_data, &dead_letter);
+                moved_to_queue = true;
+            }
+            Some(DeadLetterTarget::Topic) => {
+                to_topic.push(dead_letter);
+            }
+            None => {
+                // Dead-lettering was switched off while the message was in flight:
+                // give it back to the queue.
+                if let Some(queue) = topic_data.queues.get_mut(dead_letter.queue_id.as_str()) {
+                    queue.queue.enqueue(dead_letter.message.id.get_value());
+                }
+            }
+        }
+    }
+
+    if moved_to_queue {
+        app.persist_executor.trigger();
+    }
+
+    if to_topic.len() == 0 {
+        return moved_to_queue;
+    }
+
+    match publish_to_dead_letter_topic(app, topic, &to_topic) {
+        Ok(_) => {
+            for dead_letter in &to_topic {
+                mark_as_dead_lettered(topic, topic_data, dead_letter);
+            }
+        }
+        Err(err) => {
+            my_logger::LOGGER.write_error(
+                "move_to_dead_letter",
+                format!("Can not publish to dead-letter topic. Err: {:?}", err),
+                LogEventCtx::new()
+                    .add("namespace", topic.namespace.as_str())
+                    .add("topicId", topic.topic_id.as_str()),
+            );
+
+            // The messages keep their attempts and wait in their queues before the
+            // next try, so a topic which keeps refusing them is not hammered, nor
+            // logged about, on every delivery round.
+            let now = DateTimeAsMicroseconds::now();
+
+            for dead_letter in to_topic {
+                if let Some(queue) = topic_data.queues.get_mut(dead_letter.queue_id.as_str()) {
+                    let delay = match queue.redelivery.as_ref() {
+                        Some(redelivery) => redelivery
+                            .get_delay(dead_letter.attempts)
+                            .max(DEAD_LETTER_TOPIC_RETRY_DELAY),
+                        None => DEAD_LETTER_TOPIC_RETRY_DELAY,
+                    };
+
+                    queue.delayed.add(now.add(delay), dead_letter.message.id);
+                }
+            }
+
+            app.persist_executor.trigger();
+        }
+    }
+
+    moved_to_queue
+}
+
+/// The dead-letter topic is created only where publishing creates topics, and it
+/// has to be within its quotas. Its forward rules and content router are not
+/// applied: the source topic is locked all the while, and forwarding from here
+/// could come back to it.
+fn publish_to_dead_letter_topic(
+    app: &AppContext,
+    topic: &Arc<Topic>,
+    dead_letters: &[DeadLetterMessage],
+) -> Result<(), OperationFailResult> {
+    let namespace = app
+        .namespaces
+        .get(topic.namespace.as_str())
+        .ok_or(OperationFailResult::Other(format!(
+            "Namespace {} is not found",
+            topic.namespace
+        )))?;
+
+    let dead_letter_topic_id = get_dead_letter_topic_id(topic.topic_id.as_str());
+
+    let dead_letter_topic = match namespace.topic_list.get(dead_letter_topic_id.as_str()) {
+        Some(topic) => topic,
+        None => {
+            if !app.settings.auto_create_topic_on_publish {
+                return Err(OperationFailResult::TopicNotFound {
+                    topic_id: dead_letter_topic_id,
+                });
+            }
+
+            super::check_persistence_route(app, namespace.as_ref())?;
+
+            namespace
+                .topic_list
+                .add_if_not_exists(dead_letter_topic_id.as_str())?
+        }
+    };
+
+    if dead_letter_topic.get_deleted() != 0 {
+        return Err(OperationFailResult::TopicIsDeleted {
+            topic_id: dead_letter_topic_id,
+        });
+    }
+
+    let messages: Vec<_> = dead_letters.iter().map(to_dead_letter_copy).collect();
+
+    let messages_count = messages.len();
+
+    // Lock order is always source topic -> its dead-letter topic.
+    let mut dead_letter_topic_data = dead_letter_topic.get_access();
+
+    super::check_publish_quotas(app, &namespace, &dead_letter_topic_data)?;
+
+    // The message is already gone from its queue, so there is nobody to refuse an
+    // ack to: a failed write to the write-ahead log is only reported.
+    if let Some(wal_flush) = dead_letter_topic_data.append_messages(messages) {
+        wal_flush.spawn("move_to_dead_letter", dead_letter_topic_id);
+    }
+
+    dead_letter_topic_data
+        .statistics
+        .update_messages_count(messages_count);
+
+    app.persist_executor.trigger();
+
+    crate::operations::delivery::try_to_deliver_to_subscribers(
+        app,
+        &dead_letter_topic,
+        &mut dead_letter_topic_data,
+    );
+
+    Ok(())
+}
+
+fn to_dead_letter_copy(dead_letter: &DeadLetterMessage) -> MessageToPublish {
+    let mut headers = dead_letter.message.headers.clone();
+
+    headers.add_header(
+        DEAD_LETTER_ATTEMPTS_HEADER.to_string(),
+        dead_letter.attempts.to_string(),
+    );
+
+    headers.add_header(
+        DEAD_LETTER_QUEUE_HEADER.to_string(),
+        dead_letter.queue_id.to_string(),
+    );
+
+    MessageToPublish {
+        headers,
+        content: dead_letter.message.content.clone(),
+    }
+}
+
+fn mark_as_dead_lettered(
+    topic: &Arc<Topic>,
+    topic_data: &mut TopicInner,
+    dead_letter: &DeadLetterMessage,
+) {
+    let Some(queue) = topic_data.queues.get_mut(dead_letter.queue_id.as_str()) else {
+        return;
+    };
+
+    queue.delivery_attempts.reset(dead_letter.message.id);
+    queue.dead_lettered += 1;
+
+    my_logger::LOGGER.write_info(
+        "move_to_dead_letter",
+        "Message is dead-lettered",
+        LogEventCtx::new()
+            .add("namespace", topic.namespace.as_str())
+            .add("topicId", topic.topic_id.as_str())
+            .add("queueId", dead_letter.queue_id.as_str())
+            .add("messageId", dead_letter.message.id.get_value().to_string())
+            .add("attempts", dead_letter.attempts.to_string()),
+    );
+}
@@ -1,3 +1,4 @@
+use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
 use my_service_bus::abstractions::AsMessageId;
 use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StopWatch};
@@ -7,8 +8,9 @@ use std::sync::Arc;
 use crate::{
     app::AppContext,
     messages_page::MessagesPageList,
+    operations::dead_letter::DeadLetterMessage,
     queue_subscribers::SubscriberId,
-    queues::TopicQueue,
+    queues::{TopicQueue, MAX_MESSAGES_WAITING_FOR_KEY},
     sessions::MyServiceBusSession,
     sub_page::GetMessageResult,
     topics::{Topic, TopicInner},
@@ -23,9 +25,20 @@ pub fn try_to_deliver_to_subscribers(
 ) {
     let sw = StopWatch::new();
     let mut to_send = Vec::new();
+    let mut dead_letters = Vec::new();
 
     let topic_persist = topic_data.persist;
 
+    // Messages created before this moment are past the topic TTL.
+    let expired_before = topic_data.ttl.map(|ttl| {
+        let ttl = i64::try_from(ttl.as_micros()).unwrap_or(i64::MAX);
+        DateTimeAsMicroseconds::new(
+            DateTimeAsMicroseconds::now()
+                .unix_microseconds
+                .saturating_sub(ttl),
+        )
+    });
+
     // DEBUG: trace delivery for the queue selected via /api/Debug/Console/Target
     let dbg_topic = app.debug_console.matches_topic(topic.topic_id.as_str());
     if dbg_topic {
@@ -40,9 +53,11 @@ pub fn try_to_deliver_to_subscribers(
             app,
             topic,
             &mut to_send,
+            &mut dead_letters,
             topic_queue,
             &mut topic_data.pages,
             topic_persist,
+            expired_before,
         );
     }
 
@@ -55,15 +70,32 @@ pub fn try_to_deliver_to_subscribers(
             );
         }
     }
+
+    if dead_letters.len() > 0 {
+        let moved_to_queue = crate::operations::dead_letter::move_to_dead_letter(
+            app,
+            topic,
+            topic_data,
+            dead_letters,
+        );
+
+        // Sibling dead-letter queues may have subscribers waiting. Moved messages
+        // start there with no failed attempts, so this does not loop.
+        if moved_to_queue {
+            try_to_deliver_to_subscribers(app, topic, topic_data);
+        }
+    }
 }
 
 fn compile_packages(
     app: &AppContext,
     topic: &Arc<Topic>,
     to_send: &mut Vec<SubscriberPackageBuilder>,
+    dead_letters: &mut Vec<DeadLetterMessage>,
     topic_queue: &mut TopicQueue,
     pages: &mut MessagesPageList,
     topic_persist: bool,
+    expired_before: Option<DateTimeAsMicroseconds>,
 ) {
     let mut not_engaged_topics = Vec::new();
 
@@ -71,6 +103,14 @@ fn compile_packages(
     let dbg = app
         .debug_console
         .matches(topic.topic_id.as_str(), topic_queue.queue_id.as_str());
+
+    if topic_queue.paused {
+        if dbg {
+            app.debug_console
+                .write("[compile_packages] queue is PAUSED -> skip");
+        }
+        return;
+    }
     if dbg {
         app.debug_console.write(format!(
             "[compile_packages] {}/{}: queue_size={}",
@@ -106,9 +146,11 @@ fn compile_packages(
             topic,
             topic_queue,
             pages,
+            dead_letters,
             subscriber_id,
             &session,
             topic_persist,
+            expired_before,
         );
 
         if let Some(package_builder) = package_builder {
@@ -140,9 +182,11 @@ fn compile_package(
     topic: &Arc<Topic>,
     topic_queue: &mut TopicQueue,
     pages: &mut MessagesPageList,
+    dead_letters: &mut Vec<DeadLetterMessage>,
     subscriber_id: SubscriberId,
     session: &MyServiceBusSession,
     topic_persist: bool,
+    expired_before: Option<DateTimeAsMicroseconds>,
 ) -> Option<SubscriberPackageBuilder> {
     let mut package_builder: Option<SubscriberPackageBuilder> = None;
 
@@ -158,7 +202,15 @@ fn compile_package(
 
     let last_access = DateTimeAsMicroseconds::now();
 
+    // Messages whose ordering key is on delivery to another subscriber. They go
+    // back to the queue once the package is compiled.
+    let mut waiting_for_key = QueueWithIntervals::new();
+
     while payload_size < app.get_max_delivery_size() {
+        if waiting_for_key.queue_size() >= MAX_MESSAGES_WAITING_FOR_KEY {
+            break;
+        }
+
         if let Some(max_messages_per_payload) = topic_queue.max_messages_per_payload {
             if let Some(package_builder) = package_builder.as_ref() {
                 if package_builder.messages_on_delivery.queue_size() >= max_messages_per_payload {
@@ -207,7 +259,7 @@ fn compile_package(
                     }
                     app.restore_page_scheduler
                         .schedule_load_sub_page(topic.clone(), sub_page_id);
-                    return package_builder;
+                    break;
                 }
 
                 // Always advance past the missing sub_page so peek() cannot land on it again.
@@ -230,6 +282,73 @@ fn compile_package(
 
         match sub_page.get_message(message_id.as_message_id()) {
             GetMessageResult::Message(message_content) => {
+                if let Some(expired_before) = expired_before {
+                    // A scheduled message lives its TTL from the moment it is due.
+                    let created = crate::topics::get_scheduled_deliver_at(
+                        &message_content.headers,
+                        message_content.time,
+                    )
+                    .unwrap_or(message_content.time);
+
+                    if created.unix_microseconds < expired_before.unix_microseconds {
+                        if dbg {
+                            app.debug_console.write(format!(
+                                "[compile_package] msg {} EXPIRED -> skip (dequeued)",
+                                message_id.get_value()
+                            ));
+                        }
+                        topic_queue.delivery_attempts.reset(message_content.id);
+                        topic_queue.expired += 1;
+                        continue;
+                    }
+                }
+
+                if let Some(header_filter) = topic_queue.header_filter.as_ref() {
+                    if !header_filter.matches(&message_content.headers) {
+                        if dbg {
+                            app.debug_console.write(format!(
+                                "[compile_package] msg {} does not match header filter -> confirm (dequeued)",
+                                message_id.get_value()
+                            ));
+                        }
+                        topic_queue.delivery_attempts.reset(message_content.id);
+                        topic_queue.filtered_out += 1;
+                        continue;
+                    }
+                }
+
+                if let Some(attempts) = topic_queue.get_dead_letter_attempts(message_content.id) {
+                    if dbg {
+                        app.debug_console.write(format!(
+                            "[compile_package] msg {} failed {} times -> dead letter",
+                            message_id.get_value(),
+                            attempts
+                        ));
+                    }
+                    dead_letters.push(DeadLetterMessage {
+                        queue_id: topic_queue.queue_id.clone(),
+                        attempts,
+                        message: message_content.clone(),
+                    });
+                    continue;
+                }
+
+                if let Some(ordering_keys) = topic_queue.ordering_keys.as_mut() {
+                    if let Some(key) = ordering_keys.get_key(&message_content.headers) {
+                        if !ordering_keys.try_pin(key, subscriber_id, &topic_queue.subscribers) {
+                            if dbg {
+                                app.debug_console.write(format!(
+                                    "[compile_package] msg {} key '{}' is on delivery to another subscriber -> wait",
+                                    message_id.get_value(),
+                                    key
+                                ));
+                            }
+                            waiting_for_key.enqueue(message_id.get_value());
+                            continue;
+                        }
+                    }
+                }
+
                 if dbg {
                     app.debug_console.write(format!(
                         "[compile_package] msg {} LOADED -> add to package",
@@ -270,7 +389,7 @@ fn compile_package(
                 if topic_persist {
                     app.restore_page_scheduler
                         .schedule_load_sub_page(topic.clone(), sub_page_id);
-                    return package_builder;
+                    break;
                 }
                 // persist=false: nothing to reload from. The id is already dequeued, just skip it.
             }
@@ -282,6 +401,10 @@ fn compile_package(
         };
     }
 
+    if waiting_for_key.queue_size() > 0 {
+        topic_queue.queue.merge(waiting_for_key);
+    }
+
     package_builder
 }
 
@@ -393,7 +516,12 @@ mod tests {
         let messages_to_persist = vec![msg1, msg2];
 
         app.persistence_client
-            .save_messages(None, TOPIC_NAME, messages_to_persist)
+            .save_messages(
+                None,
+                TOPIC_NAME,
+                messages_to_persist,
+                crate::persistence::PersistenceCompression::None,
+            )
             .await
             .unwrap();
 
@@ -436,4 +564,676 @@ mod tests {
         assert_eq!(QUEUE_NAME, packet.queue_id.as_str());
         println!("ConfirmationId: {}", packet.subscriber_id.get_value());
     }
+    #[tokio::test]
+    async fn test_message_is_moved_to_dead_letter_queue() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+        const DEAD_LETTER_QUEUE_NAME: &str = "test-queue-dlq";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let subscriber_id = crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            test_session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_dead_letter(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            crate::queues::DeadLetterSettings::restore(
+                Some(1),
+                Some(DEAD_LETTER_QUEUE_NAME.to_string()),
+            ),
+        )
+        .await
+        .unwrap();
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        app.restore_page_scheduler.emulate_event_loop_tick().await;
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 1);
+
+        crate::operations::delivery_confirmation::all_fail(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+        )
+        .await
+        .unwrap();
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 0);
+
+        let topic = namespace.topic_list.get(TOPIC_NAME).unwrap();
+        let topic_data = topic.get_access();
+
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(queue.get_queue_size(), 0);
+        assert_eq!(queue.dead_lettered, 1);
+
+        let dead_letter_queue = topic_data.queues.get(DEAD_LETTER_QUEUE_NAME).unwrap();
+        assert_eq!(dead_letter_queue.get_queue_size(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_topic_is_not_created_if_publish_does_not_create_topics() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+
+        let mut settings = crate::test_tools::create_test_settings();
+        settings.auto_create_topic_on_publish = false;
+
+        let app = crate::test_tools::create_app_context_with_settings(
+            std::sync::Arc::new(crate::grpc_client::MessagesPagesMockRepo::new()),
+            settings,
+        )
+        .await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let subscriber_id = crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            test_session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_dead_letter(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            crate::queues::DeadLetterSettings::restore(Some(1), None),
+        )
+        .await
+        .unwrap();
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        app.restore_page_scheduler.emulate_event_loop_tick().await;
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 1);
+
+        crate::operations::delivery_confirmation::all_fail(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+        )
+        .await
+        .unwrap();
+
+        let dead_letter_topic_id = crate::queues::get_dead_letter_topic_id(TOPIC_NAME);
+        assert!(namespace
+            .topic_list
+            .get(dead_letter_topic_id.as_str())
+            .is_none());
+
+        // The message is kept in its queue instead, and waits before the next try.
+        let topic = namespace.topic_list.get(TOPIC_NAME).unwrap();
+        let mut topic_data = topic.get_access();
+
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(queue.get_queue_size(), 0);
+        assert_eq!(queue.delayed.len(), 1);
+        assert_eq!(queue.dead_lettered, 0);
+
+        assert!(!topic_data.enqueue_due_redeliveries(DateTimeAsMicroseconds::now()));
+
+        let due = DateTimeAsMicroseconds::now()
+            .add(crate::operations::dead_letter::DEAD_LETTER_TOPIC_RETRY_DELAY);
+        assert!(topic_data.enqueue_due_redeliveries(due));
+
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(queue.get_queue_size(), 1);
+        assert_eq!(queue.delayed.len(), 0);
+    }
+    #[tokio::test]
+    async fn test_expired_messages_are_skipped() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        topic.get_access().queues.add_queue_if_not_exists(
+            topic.topic_id.clone(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+        );
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        crate::operations::update_topic_ttl(
+            &namespace,
+            TOPIC_NAME.to_string(),
+            Some(std::time::Duration::from_millis(10)),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            test_session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 0);
+
+        let topic_data = topic.get_access();
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(queue.get_queue_size(), 0);
+        assert_eq!(queue.expired, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_message_lives_its_ttl_from_the_moment_it_is_due() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        crate::operations::update_topic_ttl(
+            &namespace,
+            TOPIC_NAME.to_string(),
+            Some(std::time::Duration::from_millis(200)),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            test_session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new().add(
+                crate::topics::SCHEDULED_DELAY_MS_HEADER.to_string(),
+                "300".to_string(),
+            ),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(350)).await;
+
+        {
+            let mut topic_data = topic.get_access();
+            assert!(topic_data.enqueue_due_scheduled_messages(DateTimeAsMicroseconds::now()));
+
+            super::try_to_deliver_to_subscribers(&app, &topic, &mut topic_data);
+
+            let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+            assert_eq!(queue.expired, 0);
+        }
+
+        assert_eq!(test_session.get_list_of_packets_and_clear_them().len(), 1);
+    }
+    #[tokio::test]
+    async fn test_failed_message_waits_for_redelivery_delay() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let subscriber_id = crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            test_session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_redelivery_policy(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            crate::queues::RedeliveryPolicy::restore(None, Some(60_000), None),
+        )
+        .await
+        .unwrap();
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 1);
+
+        crate::operations::delivery_confirmation::all_fail(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+        )
+        .await
+        .unwrap();
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 0);
+
+        let mut topic_data = topic.get_access();
+
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(queue.get_queue_size(), 0);
+        assert_eq!(queue.delayed.len(), 1);
+
+        let due = DateTimeAsMicroseconds::now().add(std::time::Duration::from_secs(61));
+        assert!(topic_data.enqueue_due_redeliveries(due));
+
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(queue.get_queue_size(), 1);
+        assert_eq!(queue.delayed.len(), 0);
+    }
+    #[tokio::test]
+    async fn test_ordering_key_is_not_delivered_to_two_subscribers_at_once() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+        const KEY_HEADER: &str = "aggregate-id";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let session_a = app.sessions.add_test(namespace.clone());
+        let session_b = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session_a.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let subscriber_a = crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            session_a.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            session_b.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_max_messages_per_payload(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            Some(1),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_ordering_key(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            Some(KEY_HEADER.to_string()),
+        )
+        .await
+        .unwrap();
+
+        let messages = ["key-1", "key-1", "key-2"]
+            .into_iter()
+            .map(|key| MessageToPublish {
+                headers: SbMessageHeaders::new().add(KEY_HEADER.to_string(), key.to_string()),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            messages,
+            false,
+            session_a.session_id,
+        )
+        .await
+        .unwrap();
+
+        // The second "key-1" message waits while the first one is on delivery.
+        assert_eq!(session_a.get_list_of_packets_and_clear_them().len(), 1);
+        assert_eq!(session_b.get_list_of_packets_and_clear_them().len(), 1);
+
+        crate::operations::delivery_confirmation::all_confirmed(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_a,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(session_a.get_list_of_packets_and_clear_them().len(), 1);
+        assert_eq!(session_b.get_list_of_packets_and_clear_them().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_looks_past_a_busy_key_only_so_far() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+        const KEY_HEADER: &str = "aggregate-id";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let session_a = app.sessions.add_test(namespace.clone());
+        let session_b = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session_a.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            session_a.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_max_messages_per_payload(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            Some(1),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_ordering_key(
+            &namespace,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            Some(KEY_HEADER.to_string()),
+        )
+        .await
+        .unwrap();
+
+        // The first message goes to the only subscriber, and pins "key-1" to it.
+        let messages = (0..MAX_MESSAGES_WAITING_FOR_KEY + 1)
+            .map(|_| "key-1")
+            .chain(["key-2"])
+            .map(|key| MessageToPublish {
+                headers: SbMessageHeaders::new().add(KEY_HEADER.to_string(), key.to_string()),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            messages,
+            false,
+            session_a.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(session_a.get_list_of_packets_and_clear_them().len(), 1);
+
+        crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            session_b.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        // "key-2" is further on than the look-ahead, so the round gives up before it.
+        assert_eq!(session_b.get_list_of_packets_and_clear_them().len(), 0);
+
+        let topic = namespace.topic_list.get(TOPIC_NAME).unwrap();
+        let topic_data = topic.get_access();
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(MAX_MESSAGES_WAITING_FOR_KEY + 1, queue.get_queue_size());
+    }
+
+    #[tokio::test]
+    async fn test_paused_queue_delivers_after_resume() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            &namespace,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+            test_session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        crate::operations::queues::set_paused(&app, &namespace, TOPIC_NAME, QUEUE_NAME, true)
+            .await
+            .unwrap();
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        app.restore_page_scheduler.emulate_event_loop_tick().await;
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 0);
+
+        {
+            let topic = namespace.topic_list.get(TOPIC_NAME).unwrap();
+            let topic_data = topic.get_access();
+            let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+            assert_eq!(queue.get_queue_size(), 1);
+        }
+
+        let was_paused =
+            crate::operations::queues::set_paused(&app, &namespace, TOPIC_NAME, QUEUE_NAME, false)
+                .await
+                .unwrap();
+        assert!(was_paused);
+
+        app.restore_page_scheduler.emulate_event_loop_tick().await;
+
+        let result_packets = test_session.get_list_of_packets_and_clear_them();
+        assert_eq!(result_packets.len(), 1);
+    }
 }
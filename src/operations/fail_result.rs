@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use my_service_bus::shared::validators::InvalidTopicName;
 
-use crate::queue_subscribers::SubscriberId;
+use crate::{queue_subscribers::SubscriberId, quotas::QuotaExceeded};
 
 #[derive(Debug)]
 pub enum OperationFailResult {
@@ -15,6 +17,12 @@ pub enum OperationFailResult {
     Other(String),
     ShuttingDown,
     TopicOrQueueValidationError(InvalidTopicName),
+    QuotaExceeded(QuotaExceeded),
+    PublishIsThrottled(String),
+    /// The messages are published, but were not saved in time.
+    PersistenceTimeout(Duration),
+    /// The persistence of the namespace could not be read at start.
+    PersistenceRouteIsNotRestored { route: String },
 }
 
 impl From<InvalidTopicName> for OperationFailResult {
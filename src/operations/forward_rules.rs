@@ -0,0 +1,396 @@
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+
+use crate::{
+    app::AppContext,
+    namespaces::Namespace,
+    queues::HeaderFilter,
+    topics::{ForwardBatch, ForwardRule, Topic},
+};
+
+use super::OperationFailResult;
+
+/// Adds the rule, or replaces the one the topic already has to the same target.
+/// A rule which would let a message come back to a topic it has already been
+/// through is refused.
+pub async fn add_forward_rule(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    target_namespace: Option<String>,
+    target_topic_id: String,
+    filter: Option<HeaderFilter>,
+) -> Result<(), OperationFailResult> {
+    let topic = match namespace.topic_list.get(topic_id.as_str()) {
+        Some(topic) => topic,
+        None => return Err(OperationFailResult::TopicNotFound { topic_id }),
+    };
+
+    let target_namespace = app
+        .namespaces
+        .get_or_create_optional(target_namespace.as_deref())
+        .map_err(|err| OperationFailResult::Other(format!("Invalid namespace. {}", err)))?;
+
+    if target_namespace
+        .topic_list
+        .get(target_topic_id.as_str())
+        .is_none()
+    {
+        return Err(OperationFailResult::TopicNotFound {
+            topic_id: target_topic_id,
+        });
+    }
+
+    if leads_to(
+        app,
+        target_namespace.name.as_str(),
+        target_topic_id.as_str(),
+        namespace.name.as_str(),
+        topic_id.as_str(),
+    ) {
+        return Err(OperationFailResult::Other(format!(
+            "Forwarding {}/{} to {}/{} makes a cycle",
+            namespace.name, topic_id, target_namespace.name, target_topic_id
+        )));
+    }
+
+    my_logger::LOGGER.write_info(
+        "add_forward_rule",
+        "Forward rule is set",
+        LogEventCtx::new()
+            .add("namespace", namespace.name.as_str())
+            .add("topicId", topic_id.as_str())
+            .add("targetNamespace", target_namespace.name.as_str())
+            .add("targetTopicId", target_topic_id.as_str()),
+    );
+
+    topic.get_access().forward_rules.set(ForwardRule::new(
+        target_namespace.name.clone(),
+        target_topic_id,
+        filter,
+    ));
+
+    app.persist_executor.trigger();
+
+    Ok(())
+}
+
+/// Returns false if the topic had no rule to that target.
+pub async fn remove_forward_rule(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    target_namespace: Option<String>,
+    target_topic_id: String,
+) -> Result<bool, OperationFailResult> {
+    let topic = match namespace.topic_list.get(topic_id.as_str()) {
+        Some(topic) => topic,
+        None => return Err(OperationFailResult::TopicNotFound { topic_id }),
+    };
+
+    let target_namespace = match target_namespace {
+        Some(target_namespace) if !target_namespace.is_empty() => target_namespace,
+        _ => app.namespaces.get_default().name.clone(),
+    };
+
+    let removed = topic
+        .get_access()
+        .forward_rules
+        .remove(target_namespace.as_str(), target_topic_id.as_str());
+
+    if removed {
+        app.persist_executor.trigger();
+    }
+
+    Ok(removed)
+}
+
+/// Takes the copies to the targets of the rules, and further on by the rules of
+/// the targets. One topic is locked at a time. A copy never enters a topic it
+/// has already been through: rules are checked for cycles when they are added,
+/// this only guards against the ones which slip in meanwhile.
+pub fn forward_messages(app: &AppContext, source: &Topic, batches: Vec<ForwardBatch>) {
+    let source_path = vec![(source.namespace.clone(), source.topic_id.to_string())];
+
+    let mut to_forward: Vec<_> = batches
+        .into_iter()
+        .map(|batch| (batch, source_path.clone()))
+        .collect();
+
+    while let Some((batch, mut path)) = to_forward.pop() {
+        let amount = batch.messages.len();
+
+        let visited = path.iter().any(|(namespace, topic_id)| {
+            namespace == &batch.namespace && topic_id == &batch.topic_id
+        });
+
+        if visited {
+            report_failed(&batch, "Forwarding makes a cycle");
+            continue;
+        }
+
+        let Some((namespace, topic)) = get_target_topic(app, &batch) else {
+            report_failed(&batch, "Target topic is not found");
+            continue;
+        };
+
+        if topic.get_deleted() != 0 {
+            report_failed(&batch, "Target topic is deleted");
+            continue;
+        }
+
+        let next = {
+            let mut topic_data = topic.get_access();
+
+            if let Err(err) = super::check_publish_quotas(app, &namespace, &topic_data) {
+                report_failed(&batch, format!("{:?}", err).as_str());
+                continue;
+            }
+
+            let published = topic_data.forward_messages(batch.messages);
+
+            if let Some(wal_flush) = published.wal_flush {
+                wal_flush.spawn("forward_messages", batch.topic_id.clone());
+            }
+
+            topic_data
+                .statistics
+                .update_messages_count(published.stored);
+
+            crate::operations::delivery::try_to_deliver_to_subscribers(
+                app,
+                &topic,
+                &mut topic_data,
+            );
+
+            published.to_forward
+        };
+
+        batch.statistics.add_forwarded(amount);
+
+        path.push((batch.namespace, batch.topic_id));
+
+        for next_batch in next {
+            to_forward.push((next_batch, path.clone()));
+        }
+    }
+
+    app.persist_executor.trigger();
+}
+
+fn get_target_topic(
+    app: &AppContext,
+    batch: &ForwardBatch,
+) -> Option<(Arc<Namespace>, Arc<Topic>)> {
+    let namespace = app.namespaces.get(batch.namespace.as_str())?;
+
+    if let Some(topic) = namespace.topic_list.get(batch.topic_id.as_str()) {
+        return Some((namespace, topic));
+    }
+
+    if !batch.auto_create_target || !app.settings.auto_create_topic_on_publish {
+        return None;
+    }
+
+    super::check_persistence_route(app, namespace.as_ref()).ok()?;
+
+    let topic = namespace
+        .topic_list
+        .add_if_not_exists(batch.topic_id.as_str())
+        .ok()?;
+
+    Some((namespace, topic))
+}
+
+fn report_failed(batch: &ForwardBatch, reason: &str) {
+    batch.statistics.add_failed(batch.messages.len());
+
+    my_logger::LOGGER.write_error(
+        "forward_messages",
+        format!("{}. Messages are not forwarded", reason),
+        LogEventCtx::new()
+            .add("targetNamespace", batch.namespace.as_str())
+            .add("targetTopicId", batch.topic_id.as_str())
+            .add("messages", batch.messages.len().to_string()),
+    );
+}
+
+/// True if messages published to the first topic get to the second one by the
+/// forward rules and the content routers as they are now.
+pub(crate) fn leads_to(
+    app: &AppContext,
+    from_namespace: &str,
+    from_topic_id: &str,
+    to_namespace: &str,
+    to_topic_id: &str,
+) -> bool {
+    let mut to_check = vec![(from_namespace.to_string(), from_topic_id.to_string())];
+    let mut checked = Vec::new();
+
+    while let Some((namespace, topic_id)) = to_check.pop() {
+        if namespace == to_namespace && topic_id == to_topic_id {
+            return true;
+        }
+
+        if checked.contains(&(namespace.clone(), topic_id.clone())) {
+            continue;
+        }
+
+        let topic = app
+            .namespaces
+            .get(namespace.as_str())
+            .and_then(|itm| itm.topic_list.get(topic_id.as_str()));
+
+        if let Some(topic) = topic {
+            let topic_data = topic.get_access();
+            for rule in topic_data.forward_rules.iter() {
+                to_check.push((rule.namespace.clone(), rule.topic_id.clone()));
+            }
+
+            if let Some(router) = topic_data.router.as_ref() {
+                let destinations = router
+                    .get_routes()
+                    .iter()
+                    .map(|route| &route.topic_id)
+                    .chain(router.fallback_topic_id.iter());
+
+                for destination in destinations {
+                    to_check.push((router.namespace.clone(), destination.clone()));
+                }
+            }
+        }
+
+        checked.push((namespace, topic_id));
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    use crate::queues::HeaderFilter;
+
+    #[tokio::test]
+    async fn test_messages_are_forwarded_and_cycles_are_refused() {
+        const ORDERS: &str = "orders";
+        const ORDERS_AUDIT: &str = "orders-audit";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let orders = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            ORDERS,
+        )
+        .await
+        .unwrap();
+
+        let orders_audit = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            ORDERS_AUDIT,
+        )
+        .await
+        .unwrap();
+
+        super::add_forward_rule(
+            &app,
+            &namespace,
+            ORDERS.to_string(),
+            None,
+            ORDERS_AUDIT.to_string(),
+            Some(HeaderFilter::Equals {
+                header: "type".to_string(),
+                value: "order".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let result = super::add_forward_rule(
+            &app,
+            &namespace,
+            ORDERS_AUDIT.to_string(),
+            None,
+            ORDERS.to_string(),
+            None,
+        )
+        .await;
+
+        assert!(result.is_err());
+
+        let messages = vec![
+            MessageToPublish {
+                headers: SbMessageHeaders::new().add("type".to_string(), "order".to_string()),
+                content: vec![1u8],
+            },
+            MessageToPublish {
+                headers: SbMessageHeaders::new().add("type".to_string(), "refund".to_string()),
+                content: vec![2u8],
+            },
+        ];
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            ORDERS,
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(2, orders.get_message_id().get_value());
+        assert_eq!(1, orders_audit.get_message_id().get_value());
+
+        let topic_data = orders.get_access();
+        let rule = topic_data.forward_rules.iter().next().unwrap();
+        assert_eq!(1, rule.statistics.get_forwarded());
+    }
+
+    #[tokio::test]
+    async fn test_forward_rule_back_to_router_is_refused() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        for topic_id in ["orders", "orders-eu"] {
+            crate::operations::create_topic_if_not_exists(
+                &namespace,
+                Some(session.session_id),
+                topic_id,
+            )
+            .await
+            .unwrap();
+        }
+
+        crate::operations::set_content_router(
+            &app,
+            &namespace,
+            "orders".to_string(),
+            "region".to_string(),
+            vec![("eu".to_string(), "orders-eu".to_string())],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let result = super::add_forward_rule(
+            &app,
+            &namespace,
+            "orders-eu".to_string(),
+            None,
+            "orders".to_string(),
+            None,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
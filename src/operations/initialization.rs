@@ -2,11 +2,13 @@ use std::{sync::Arc, time::Duration};
 
 use my_logger::LogEventCtx;
 use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
-use rust_extensions::StopWatch;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, StopWatch};
 
+use crate::queues::OrderingKeys;
 use crate::topics::TopicSnapshot;
 
 use crate::app::AppContext;
+use crate::wal::MessagesWal;
 
 pub async fn init(app: Arc<AppContext>) {
     let sw = StopWatch::new();
@@ -47,19 +49,82 @@ pub async fn init(app: Arc<AppContext>) {
             topic_and_queues.deleted,
         );
 
+        if topic_and_queues.ttl.is_some() {
+            topic.update_ttl(topic_and_queues.ttl);
+        }
+
+        if topic_and_queues.persist_compression.is_some() {
+            topic.update_persist_compression(topic_and_queues.persist_compression);
+        }
+
+        if topic_and_queues.dedup_window.is_some() || topic_and_queues.dedup_max_keys.is_some() {
+            topic.update_dedup(
+                topic_and_queues.dedup_window,
+                topic_and_queues.dedup_max_keys,
+            );
+        }
+
+        if topic_and_queues.forward_rules.len() > 0 {
+            let mut topic_data = topic.get_access();
+
+            for rule in topic_and_queues.forward_rules {
+                topic_data
+                    .forward_rules
+                    .set(crate::topics::ForwardRule::new(
+                        rule.namespace,
+                        rule.topic_id,
+                        rule.filter,
+                    ));
+            }
+        }
+
+        if let Some(router) = topic_and_queues.router {
+            topic.get_access().router = Some(crate::topics::ContentRouter::new(
+                topic.namespace.clone(),
+                router.header,
+                router.routes,
+                router.fallback_topic_id,
+            ));
+        }
+
+        if !topic_and_queues.quotas.is_empty() {
+            topic.update_quotas(topic_and_queues.quotas);
+        }
+
+        if topic_and_queues.scheduled.len() > 0 {
+            let mut topic_data = topic.get_access();
+
+            for (message_id, deliver_at) in topic_and_queues.scheduled {
+                topic_data
+                    .scheduled
+                    .add(DateTimeAsMicroseconds::new(deliver_at), message_id.into());
+            }
+        }
+
         for queue in topic_and_queues.queues {
             let queue_with_intervals = QueueWithIntervals::restore(queue.ranges);
 
             let mut topic_data = topic.get_access();
-            topic_data.queues.restore(
+            let topic_queue = topic_data.queues.restore(
                 topic.topic_id.clone(),
                 queue.queue_id.into(),
                 queue.queue_type,
                 queue_with_intervals,
             );
+
+            topic_queue.dead_letter = queue.dead_letter;
+            topic_queue.redelivery = queue.redelivery;
+            topic_queue.ordering_keys = queue.ordering_key_header.map(OrderingKeys::new);
+            topic_queue.header_filter = queue.header_filter;
+            topic_queue.paused = queue.paused;
+            topic_queue.delivery_timeout = queue.delivery_timeout;
         }
     }
 
+    if let Some(wal) = app.wal.as_ref() {
+        replay_wal(&app, wal).await;
+    }
+
     if let Some(persistence_version) = app.persistence_client.get_persistence_version().await {
         app.persistence_version.update(persistence_version.as_str());
     }
@@ -75,6 +140,68 @@ pub async fn init(app: Arc<AppContext>) {
     println!("Application is initialized in {:?}", sw.duration());
 }
 
+/// Puts what the previous run published but did not get persisted back into the
+/// topics, and sends it to persistence. Topics the snapshot does not know yet are
+/// created: they appeared after the last snapshot was saved.
+async fn replay_wal(app: &Arc<AppContext>, wal: &MessagesWal) {
+    let topics = match wal.read_all() {
+        Ok(topics) => topics,
+        Err(err) => {
+            my_logger::LOGGER.write_error(
+                "Initialization",
+                format!("Can not read the write-ahead log: {:?}", err),
+                LogEventCtx::new(),
+            );
+            return;
+        }
+    };
+
+    for wal_topic in topics {
+        let topic = app
+            .namespaces
+            .get_or_create(wal_topic.namespace.as_str())
+            .ok()
+            .and_then(|namespace| {
+                namespace
+                    .topic_list
+                    .add_if_not_exists(wal_topic.topic_id.as_str())
+                    .ok()
+            });
+
+        let Some(topic) = topic else {
+            my_logger::LOGGER.write_error(
+                "Initialization",
+                format!(
+                    "Write-ahead log of {}/{} is skipped: not a valid namespace or topic name",
+                    wal_topic.namespace, wal_topic.topic_id
+                ),
+                LogEventCtx::new()
+                    .add("namespace", wal_topic.namespace.as_str())
+                    .add("topicId", wal_topic.topic_id.as_str()),
+            );
+            continue;
+        };
+
+        let messages_count = wal_topic.messages.len();
+
+        topic.get_access().restore_from_wal(wal_topic.messages);
+
+        my_logger::LOGGER.write_info(
+            "Initialization",
+            format!(
+                "Replayed {} messages of {}/{} from the write-ahead log",
+                messages_count, wal_topic.namespace, wal_topic.topic_id
+            ),
+            LogEventCtx::new()
+                .add("namespace", wal_topic.namespace.as_str())
+                .add("topicId", wal_topic.topic_id.as_str()),
+        );
+
+        // A failure is retried with the rest of the persist queue.
+        let _ = super::persist_topic_messages(app, &topic).await;
+    }
+}
+
 async fn restore_topics_and_queues(app: &AppContext) -> Vec<TopicSnapshot> {
     let mut attempt = 0;
     loop {
@@ -1,3 +1,4 @@
+pub mod dead_letter;
 mod delete_topic;
 pub mod delivery;
 mod fail_result;
@@ -12,6 +13,7 @@ pub mod send_package;
 pub mod delivery_confirmation;
 pub mod publisher;
 pub mod queues;
+pub mod request_reply;
 pub mod sessions;
 pub mod subscriber;
 
@@ -25,6 +27,20 @@ mod restore_topic;
 pub use restore_topic::*;
 mod update_topic_persist;
 pub use update_topic_persist::*;
+mod update_topic_ttl;
+pub use update_topic_ttl::*;
+mod update_topic_persist_compression;
+pub use update_topic_persist_compression::*;
+mod update_topic_dedup;
+pub use update_topic_dedup::*;
+mod forward_rules;
+pub use forward_rules::*;
+mod content_router;
+pub use content_router::*;
+mod quotas;
+pub use quotas::*;
+mod persistence_route;
+pub use persistence_route::*;
 mod gc_message_pages;
 //pub use gc_message_pages::*;
 
@@ -8,15 +8,17 @@ use my_logger::LogEventCtx;
 use my_service_bus::shared::sub_page::SubPageId;
 
 use crate::{
-    grpc_client::{PersistenceError, PersistenceGrpcService},
+    grpc_client::PersistenceError,
     messages_page::MySbCachedMessage,
+    persistence::{PersistenceBackend, PersistenceCompression},
     sub_page::{SubPage, SubPageInner},
     topics::Topic,
 };
 
 pub async fn load_page(
     topic: &Topic,
-    messages_pages_repo: &Arc<PersistenceGrpcService>,
+    messages_pages_repo: &Arc<dyn PersistenceBackend>,
+    compression: PersistenceCompression,
     sub_page_id: SubPageId,
 ) -> SubPage {
     let mut attempt_no = 0;
@@ -28,6 +30,7 @@ pub async fn load_page(
                 sub_page_id.into(),
                 sub_page_id.get_first_message_id(),
                 sub_page_id.get_last_message_id(),
+                compression,
             )
             .await;
 
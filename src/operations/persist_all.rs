@@ -1,17 +1,34 @@
 use std::sync::Arc;
 
 use my_logger::LogEventCtx;
+use my_service_bus::shared::validators::DEFAULT_NAMESPACE;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::{
     app::AppContext,
+    grpc_client::PersistenceError,
     persistence_grpc::{
-        QueueIndexRangeGrpcModel, QueueSnapshotGrpcModel, TopicAndQueuesSnapshotGrpcModel,
+        ContentRouteGrpcModel, ContentRouterGrpcModel, ForwardRuleGrpcModel,
+        QueueIndexRangeGrpcModel, QueueSnapshotGrpcModel, ScheduledMessageGrpcModel,
+        TopicAndQueuesSnapshotGrpcModel,
     },
 };
 
 pub async fn persist_all(app: &Arc<AppContext>) {
     let namespaces = app.namespaces.get_all();
 
+    // Each persistence route backs off on its own after a failed write, and the
+    // metrics timer triggers us again once one of them is due. Until then a route
+    // which is down does not hold the others back.
+    let now = DateTimeAsMicroseconds::now();
+
+    if !namespaces.iter().any(|namespace| {
+        app.get_persistence_health(namespace.name.as_str())
+            .is_time_to_write(now)
+    }) {
+        return;
+    }
+
     // Every namespace goes into one stream: persistence keeps a single snapshot
     // where each record names its namespace, and that is also how the node learns
     // which namespaces exist when it restores.
@@ -30,7 +47,7 @@ pub async fn persist_all(app: &Arc<AppContext>) {
                         .get_snapshot(|itm| QueueSnapshotGrpcModel {
                             queue_id: itm.queue_id.to_string(),
                             ranges: itm
-                                .queue
+                                .get_queue_to_persist()
                                 .get_intervals()
                                 .iter()
                                 .map(|itm| QueueIndexRangeGrpcModel {
@@ -39,10 +56,92 @@ pub async fn persist_all(app: &Arc<AppContext>) {
                                 })
                                 .collect(),
                             queue_type: itm.queue_type.into_u8() as i32,
+                            max_delivery_attempts: itm
+                                .dead_letter
+                                .as_ref()
+                                .map(|itm| itm.max_delivery_attempts),
+                            dead_letter_queue_id: itm
+                                .dead_letter
+                                .as_ref()
+                                .and_then(|itm| itm.get_dead_letter_queue_id())
+                                .map(|itm| itm.to_string()),
+                            redelivery_back_off: itm
+                                .redelivery
+                                .as_ref()
+                                .map(|itm| itm.back_off.into_u8() as i32),
+                            redelivery_delay_ms: itm
+                                .redelivery
+                                .as_ref()
+                                .map(|itm| itm.delay.as_millis() as i64),
+                            redelivery_max_delay_ms: itm
+                                .redelivery
+                                .as_ref()
+                                .map(|itm| itm.max_delay.as_millis() as i64),
+                            ordering_key_header: itm
+                                .ordering_keys
+                                .as_ref()
+                                .map(|itm| itm.header.clone()),
+                            header_filter: itm.header_filter.clone(),
+                            paused: itm.paused,
+                            delivery_timeout: itm.delivery_timeout,
                         }),
                     persist: Some(topic_data.persist),
                     deleted: topic_data.deleted,
                     namespace: grpc_namespace.clone(),
+                    scheduled_messages: topic_data
+                        .scheduled
+                        .get_snapshot()
+                        .into_iter()
+                        .map(|(message_id, deliver_at)| ScheduledMessageGrpcModel {
+                            message_id,
+                            deliver_at,
+                        })
+                        .collect(),
+                    ttl_ms: topic_data.ttl.map(|itm| itm.as_millis() as i64),
+                    dedup_window_ms: topic_data
+                        .dedup
+                        .as_ref()
+                        .and_then(|itm| itm.get_window())
+                        .map(|itm| itm.as_millis() as i64),
+                    dedup_max_keys: topic_data
+                        .dedup
+                        .as_ref()
+                        .map(|itm| itm.get_max_keys() as i64),
+                    forward_rules: topic_data
+                        .forward_rules
+                        .iter()
+                        .map(|itm| ForwardRuleGrpcModel {
+                            namespace: if itm.namespace == DEFAULT_NAMESPACE {
+                                None
+                            } else {
+                                Some(itm.namespace.clone())
+                            },
+                            topic_id: itm.topic_id.clone(),
+                            header_filter: itm.filter.as_ref().map(|itm| itm.to_json()),
+                        })
+                        .collect(),
+                    router: topic_data
+                        .router
+                        .as_ref()
+                        .map(|itm| ContentRouterGrpcModel {
+                            header: itm.header.clone(),
+                            routes: itm
+                                .get_routes()
+                                .iter()
+                                .map(|route| ContentRouteGrpcModel {
+                                    value: route.value.clone(),
+                                    topic_id: route.topic_id.clone(),
+                                })
+                                .collect(),
+                            fallback_topic_id: itm.fallback_topic_id.clone(),
+                        }),
+                    max_memory_size: topic_data.quotas.max_memory_size.map(|itm| itm as i64),
+                    max_persist_queue_size: topic_data
+                        .quotas
+                        .max_persist_queue_size
+                        .map(|itm| itm as i64),
+                    max_queue_size: topic_data.quotas.max_queue_size.map(|itm| itm as i64),
+                    persist_compression: topic_data.persist_compression.map(|itm| itm.as_grpc()),
                 }
             }));
         }
@@ -53,17 +152,51 @@ pub async fn persist_all(app: &Arc<AppContext>) {
         .save_topic_and_queues(topics_snapshots)
         .await;
 
-    if let Err(err) = result {
-        my_logger::LOGGER.write_error(
-            "persist_all",
-            format!("Failed to save topics and queues snapshot: {:?}", err),
-            LogEventCtx::new(),
-        );
+    let failed_routes = match result {
+        Ok(()) => Vec::new(),
+        Err(PersistenceError::RoutesFailed(failed_routes)) => failed_routes,
+        // Not a routed persistence: the one route there is has failed.
+        Err(err) => vec![(String::new(), format!("{:?}", err))],
+    };
+
+    let mut routes: Vec<String> = namespaces
+        .iter()
+        .map(|namespace| {
+            app.persistence_client
+                .get_route_name(namespace.name.as_str())
+        })
+        .collect();
+    routes.sort();
+    routes.dedup();
+
+    for route in routes {
+        let health = app.persistence_health.get(route.as_str());
+
+        match failed_routes.iter().find(|(name, _)| name == &route) {
+            Some((_, err)) => {
+                super::persistence_write_failed(app.as_ref(), &health, err.clone());
+
+                my_logger::LOGGER.write_error(
+                    "persist_all",
+                    format!("Failed to save topics and queues snapshot: {}", err),
+                    LogEventCtx::new().add("route", route.as_str()),
+                );
+            }
+            None => super::persistence_write_succeeded(app.as_ref(), &health),
+        }
     }
 
     for namespace in namespaces.iter() {
+        let health = app.get_persistence_health(namespace.name.as_str());
+
         for topic in namespace.topic_list.get_all().iter() {
-            crate::operations::persist_topic_messages(app, topic).await;
+            if !health.is_time_to_write(DateTimeAsMicroseconds::now()) {
+                break;
+            }
+
+            if super::persist_topic_messages(app, topic).await.is_err() {
+                break;
+            }
         }
     }
 }
@@ -1,15 +1,35 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
+use my_logger::LogEventCtx;
 use my_service_bus::shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{app::AppContext, messages_page::MessagesToPersistBucket, topics::Topic};
+use crate::{
+    app::{AppContext, PersistenceHealth},
+    grpc_client::PersistenceError,
+    messages_page::MessagesToPersistBucket,
+    topics::Topic,
+};
+
+use super::OperationFailResult;
 
 //pub const PERSIST_PAYLOAD_MAX_SIZE: usize = 1024 * 1024 * 4;
 
-pub async fn persist_topic_messages(app: &Arc<AppContext>, topic: &Arc<Topic>) {
+/// Messages of a sub-page which fail to save stay queued to persist, and the
+/// rest of the topic is left for the next attempt: persistence is likely down.
+pub async fn persist_topic_messages(
+    app: &Arc<AppContext>,
+    topic: &Arc<Topic>,
+) -> Result<(), PersistenceError> {
+    let _persist_guard = topic.persist_messages_lock.lock().await;
+
     let messages_to_persist: Vec<(SubPageId, Vec<MessageProtobufModel>)> =
         topic.get_messages_to_persist(|itm| itm.into());
 
+    let compression = topic.get_persist_compression(app.settings.persist_compression);
+
+    let health = app.get_persistence_health(topic.namespace.as_str());
+
     for (sub_page_id, messages_to_persist) in messages_to_persist {
         let mut bucket = MessagesToPersistBucket::new(sub_page_id);
 
@@ -17,15 +37,305 @@ pub async fn persist_topic_messages(app: &Arc<AppContext>, topic: &Arc<Topic>) {
             bucket.add(msg);
         }
 
-        app.persistence_client
+        let result = app
+            .persistence_client
             .save_messages(
                 topic.as_grpc_namespace(),
                 topic.topic_id.as_str(),
                 bucket.get(),
+                compression,
+            )
+            .await;
+
+        if let Err(err) = result {
+            persistence_write_failed(app, &health, format!("{:?}", err));
+
+            my_logger::LOGGER.write_error(
+                "persist_topic_messages",
+                format!(
+                    "Failed to save messages of sub page {}: {:?}. Retrying in {:?}",
+                    sub_page_id.get_value(),
+                    err,
+                    health.get_back_off()
+                ),
+                LogEventCtx::new()
+                    .add("namespace", topic.namespace.as_str())
+                    .add("topicId", topic.topic_id.as_str()),
+            );
+
+            return Err(err);
+        }
+
+        persistence_write_succeeded(app, &health);
+
+        topic.mark_messages_as_persisted(&bucket);
+    }
+
+    Ok(())
+}
+
+pub fn persistence_write_succeeded(app: &AppContext, health: &PersistenceHealth) {
+    health.write_succeeded();
+    app.prometheus
+        .update_persistence_health(&app.persistence_health);
+}
+
+pub fn persistence_write_failed(app: &AppContext, health: &PersistenceHealth, err: String) {
+    health.write_failed(DateTimeAsMicroseconds::now(), err);
+    app.prometheus.persistence_write_failed();
+    app.prometheus
+        .update_persistence_health(&app.persistence_health);
+}
+
+/// Saves the messages of the topic right away, for a publisher which wants its
+/// ack only once they are persisted. Whatever the persist job has in flight is
+/// waited for first, so once this returns the messages published before it are
+/// saved. Timing out does not stop the save, it only stops waiting for it. With
+/// the circuit of the persistence of the topic open there is no point in waiting
+/// at all.
+pub async fn persist_topic_messages_now(
+    app: &Arc<AppContext>,
+    topic: &Arc<Topic>,
+) -> Result<(), OperationFailResult> {
+    if app
+        .get_persistence_health(topic.namespace.as_str())
+        .is_circuit_open()
+    {
+        return Err(OperationFailResult::PersistenceError(
+            "Persistence is unavailable. Messages are published and will be saved once it is back"
+                .to_string(),
+        ));
+    }
+
+    let started = Instant::now();
+
+    let save = {
+        let app = app.clone();
+        let topic = topic.clone();
+        tokio::spawn(async move { persist_topic_messages(&app, &topic).await })
+    };
+
+    match tokio::time::timeout(app.settings.persist_ack_timeout, save).await {
+        Ok(Ok(Ok(()))) => {
+            app.prometheus.observe_persisted_ack(
+                topic.namespace.as_str(),
+                topic.topic_id.as_str(),
+                started.elapsed(),
+            );
+            Ok(())
+        }
+        Ok(Ok(Err(err))) => Err(OperationFailResult::PersistenceError(format!("{:?}", err))),
+        Ok(Err(err)) => Err(OperationFailResult::PersistenceError(format!("{:?}", err))),
+        Err(_) => {
+            app.prometheus
+                .persisted_ack_timed_out(topic.namespace.as_str(), topic.topic_id.as_str());
+
+            Err(OperationFailResult::PersistenceTimeout(
+                app.settings.persist_ack_timeout,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::{
+        abstractions::{publisher::MessageToPublish, SbMessageHeaders},
+        shared::sub_page::SubPageId,
+    };
+
+    use std::sync::Arc;
+
+    use crate::grpc_client::MessagesPagesMockRepo;
+    use crate::persistence::{
+        NamespaceRoutedPersistence, PersistenceBackend, PersistenceCompression,
+    };
+
+    #[tokio::test]
+    async fn test_messages_failed_to_persist_stay_queued_and_are_not_gc() {
+        let repo = Arc::new(MessagesPagesMockRepo::new());
+        let app = crate::test_tools::create_app_context_with_persistence(repo.clone()).await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "test-topic",
+        )
+        .await
+        .unwrap();
+
+        let messages = (0..2)
+            .map(|_| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        repo.set_fail_writes(true);
+
+        crate::operations::persist_all(&app).await;
+
+        assert_eq!(2, topic.get_topic_size_metrics().persist_size);
+        assert!(app.persistence_health.get_snapshot().is_some());
+
+        {
+            let mut topic_data = topic.get_access();
+            topic_data.gc();
+
+            let sub_page = topic_data.pages.get_mut(SubPageId::new(0)).unwrap();
+            assert_eq!(2, sub_page.unwrap_all_messages_with_content().len());
+        }
+
+        repo.set_fail_writes(false);
+
+        super::persist_topic_messages(&app, &topic).await.unwrap();
+
+        assert_eq!(0, topic.get_topic_size_metrics().persist_size);
+        assert!(app.persistence_health.get_snapshot().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_saved_with_compression_of_the_topic() {
+        let repo = Arc::new(MessagesPagesMockRepo::new());
+        let app = crate::test_tools::create_app_context_with_persistence(repo.clone()).await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "test-topic",
+        )
+        .await
+        .unwrap();
+
+        crate::operations::update_topic_persist_compression(
+            &app,
+            &namespace,
+            "test-topic".to_string(),
+            Some(PersistenceCompression::Zstd),
+        )
+        .await
+        .unwrap();
+
+        let messages = vec![MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        }];
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        super::persist_topic_messages(&app, &topic).await.unwrap();
+
+        assert_eq!(
+            Some(PersistenceCompression::Zstd),
+            repo.get_last_save_compression()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_route_does_not_hold_back_other_namespaces() {
+        let tenants = Arc::new(MessagesPagesMockRepo::new());
+        let default = Arc::new(MessagesPagesMockRepo::new());
+
+        let tenants_backend: Arc<dyn PersistenceBackend> = tenants.clone();
+
+        let persistence = Arc::new(NamespaceRoutedPersistence::new(
+            "default".to_string(),
+            default.clone(),
+            vec![(
+                "tenant-*".to_string(),
+                "tenants".to_string(),
+                tenants_backend,
+            )],
+        ));
+
+        let app = crate::test_tools::create_app_context_with_persistence(persistence).await;
+
+        let mut topics = Vec::new();
+
+        for namespace in [
+            app.get_default_namespace(),
+            app.namespaces.get_or_create("tenant-a").unwrap(),
+        ] {
+            let session = app.sessions.add_test(namespace.clone());
+
+            let topic = crate::operations::create_topic_if_not_exists(
+                &namespace,
+                Some(session.session_id),
+                "test-topic",
             )
             .await
             .unwrap();
 
-        topic.mark_messages_as_persisted(&bucket);
+            let messages = vec![MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![0u8, 1u8, 2u8],
+            }];
+
+            crate::operations::publisher::publish(
+                &app,
+                &namespace,
+                "test-topic",
+                messages,
+                false,
+                session.session_id,
+            )
+            .await
+            .unwrap();
+
+            topics.push(topic);
+        }
+
+        tenants.set_fail_writes(true);
+
+        crate::operations::persist_all(&app).await;
+
+        assert_eq!(0, topics[0].get_topic_size_metrics().persist_size);
+        assert_eq!(1, topics[1].get_topic_size_metrics().persist_size);
+
+        assert!(app
+            .get_persistence_health("default")
+            .get_snapshot()
+            .is_none());
+        assert!(app
+            .get_persistence_health("tenant-a")
+            .get_snapshot()
+            .is_some());
+
+        let snapshot = app.persistence_health.get_snapshot().unwrap();
+        assert_eq!("tenants", snapshot.route);
+
+        tenants.set_fail_writes(false);
+
+        let back_off = app.get_persistence_health("tenant-a").get_back_off();
+        tokio::time::sleep(back_off + std::time::Duration::from_millis(50)).await;
+
+        crate::operations::persist_all(&app).await;
+
+        assert_eq!(0, topics[1].get_topic_size_metrics().persist_size);
+        assert!(app.persistence_health.get_snapshot().is_none());
     }
 }
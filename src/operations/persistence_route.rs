@@ -0,0 +1,25 @@
+use crate::{app::AppContext, namespaces::Namespace};
+
+use super::OperationFailResult;
+
+/// A namespace whose persistence route was not restored at start has none of
+/// its topics on the node. A topic created there would start from message id 0
+/// over the history the route keeps, and none of its messages could be saved;
+/// so nothing is created or published there until the node is restarted.
+pub fn check_persistence_route(
+    app: &AppContext,
+    namespace: &Namespace,
+) -> Result<(), OperationFailResult> {
+    if app
+        .persistence_client
+        .is_route_restored(namespace.name.as_str())
+    {
+        return Ok(());
+    }
+
+    Err(OperationFailResult::PersistenceRouteIsNotRestored {
+        route: app
+            .persistence_client
+            .get_route_name(namespace.name.as_str()),
+    })
+}
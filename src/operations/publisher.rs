@@ -1,23 +1,61 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use my_service_bus::abstractions::publisher::MessageToPublish;
 
-use crate::{app::AppContext, namespaces::Namespace, sessions::SessionId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{AppContext, RateLimitResult},
+    namespaces::Namespace,
+    sessions::SessionId,
+};
 
 use super::OperationFailResult;
 
+/// Applies the publish rate limits of the node. A delayed publish returns once
+/// its tokens are there; a rejected one is an error to give back to the publisher.
+pub async fn throttle(
+    app: &AppContext,
+    session_name: &str,
+    namespace: &Namespace,
+    topic_id: &str,
+    messages: &[MessageToPublish],
+) -> Result<(), OperationFailResult> {
+    let bytes = messages.iter().map(|itm| itm.content.len()).sum();
+
+    let result = app.publish_rate_limiter.check(
+        session_name,
+        namespace.name.as_str(),
+        topic_id,
+        messages.len(),
+        bytes,
+        DateTimeAsMicroseconds::now(),
+    );
+
+    match result {
+        RateLimitResult::Pass => Ok(()),
+        RateLimitResult::Delay(delay) => {
+            tokio::time::sleep(delay).await;
+            Ok(())
+        }
+        RateLimitResult::Reject(reason) => Err(OperationFailResult::PublishIsThrottled(reason)),
+    }
+}
+
 pub async fn publish(
     app: &Arc<AppContext>,
     namespace: &Arc<Namespace>,
     topic_id: &str,
     messages: Vec<MessageToPublish>,
-    _persist_immediately: bool,
+    persist_immediately: bool,
     session_id: SessionId,
 ) -> Result<(), OperationFailResult> {
     if app.states.is_shutting_down() {
         return Err(OperationFailResult::ShuttingDown);
     }
 
+    super::check_persistence_route(app.as_ref(), namespace)?;
+
     let mut topic = namespace.topic_list.get(topic_id);
 
     if topic.is_none() {
@@ -38,21 +76,455 @@ pub async fn publish(
         });
     }
 
-    let mut topic_data = topic.get_access();
+    let (to_forward, wait_for_persist, wal_flush) = {
+        let mut topic_data = topic.get_access();
+
+        super::check_publish_quotas(app.as_ref(), namespace, &topic_data)?;
+
+        app.reply_awaiters.complete_replies(
+            namespace.name.as_str(),
+            topic.topic_id.as_str(),
+            &messages,
+        );
+
+        let published = topic_data.publish_messages(session_id, messages);
+
+        // A topic which does not persist has nothing to wait for, and neither
+        // does a publish whose messages all turned out to be duplicates.
+        let wait_for_persist = persist_immediately && topic_data.persist && published.stored > 0;
+
+        topic_data
+            .statistics
+            .update_messages_count(published.stored);
+
+        app.persist_executor.trigger();
+
+        crate::operations::delivery::try_to_deliver_to_subscribers(
+            app.as_ref(),
+            &topic,
+            &mut topic_data,
+        );
+
+        (published.to_forward, wait_for_persist, published.wal_flush)
+    };
+
+    if to_forward.len() > 0 {
+        super::forward_messages(app.as_ref(), &topic, to_forward);
+    }
+
+    // The messages are delivered already, but without the write-ahead log they could
+    // be lost; the publisher gets no ack and publishes them again.
+    if let Some(wal_flush) = wal_flush {
+        wal_flush.wait().await.map_err(wal_failed)?;
+    }
+
+    if wait_for_persist {
+        super::persist_topic_messages_now(app, &topic).await?;
+    }
+
+    Ok(())
+}
+
+/// Publishes to several topics all or nothing. Every topic is validated first;
+/// then all of them are locked together and appended to, so no subscriber sees
+/// any of the messages before every topic has them.
+pub async fn publish_transaction(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    items: Vec<(String, Vec<MessageToPublish>)>,
+    session_id: SessionId,
+) -> Result<(), OperationFailResult> {
+    if app.states.is_shutting_down() {
+        return Err(OperationFailResult::ShuttingDown);
+    }
+
+    super::check_persistence_route(app.as_ref(), namespace)?;
+
+    // Topics are locked in topic id order, so two transactions never wait for
+    // each other with a lock each.
+    let mut by_topic: BTreeMap<String, Vec<MessageToPublish>> = BTreeMap::new();
+
+    for (topic_id, messages) in items {
+        by_topic.entry(topic_id).or_default().extend(messages);
+    }
+
+    if by_topic.is_empty() {
+        return Err(OperationFailResult::Other(
+            "Transaction has no topics to publish to".to_string(),
+        ));
+    }
+
+    // Everything which can refuse the transaction is checked before a missing topic
+    // is created, so a refused transaction leaves no empty topics behind.
+    for topic_id in by_topic.keys() {
+        match namespace.topic_list.get(topic_id) {
+            Some(topic) => {
+                let topic_data = topic.get_access();
+
+                if topic_data.deleted != 0 {
+                    return Err(OperationFailResult::TopicIsDeleted {
+                        topic_id: topic_id.to_string(),
+                    });
+                }
+
+                super::check_publish_quotas(app.as_ref(), namespace, &topic_data)?;
+            }
+            None => {
+                if !app.settings.auto_create_topic_on_publish {
+                    return Err(OperationFailResult::TopicNotFound {
+                        topic_id: topic_id.to_string(),
+                    });
+                }
+
+                my_service_bus::shared::validators::validate_topic_name(topic_id)?;
+
+                super::check_namespace_quotas(app.as_ref(), namespace, topic_id)?;
+            }
+        }
+    }
+
+    let mut topics = Vec::with_capacity(by_topic.len());
+
+    for topic_id in by_topic.keys() {
+        topics.push(namespace.topic_list.add_if_not_exists(topic_id)?);
+    }
 
-    let messages_count = messages.len();
+    let mut to_forward = Vec::new();
+    let mut wal_flushes = Vec::new();
 
-    topic_data.publish_messages(session_id, messages);
+    {
+        let mut topics_data: Vec<_> = topics.iter().map(|itm| itm.get_access()).collect();
 
-    topic_data.statistics.update_messages_count(messages_count);
+        // A topic could be deleted, or go over its quotas, while we were not
+        // holding its lock.
+        for (topic, topic_data) in topics.iter().zip(topics_data.iter()) {
+            if topic_data.deleted != 0 {
+                return Err(OperationFailResult::TopicIsDeleted {
+                    topic_id: topic.topic_id.to_string(),
+                });
+            }
+
+            super::check_publish_quotas(app.as_ref(), namespace, topic_data)?;
+        }
+
+        for (topic_data, messages) in topics_data.iter_mut().zip(by_topic.into_values()) {
+            app.reply_awaiters.complete_replies(
+                namespace.name.as_str(),
+                topic_data.topic_id.as_str(),
+                &messages,
+            );
+            let published = topic_data.publish_messages(session_id, messages);
+            topic_data
+                .statistics
+                .update_messages_count(published.stored);
+            to_forward.push(published.to_forward);
+
+            wal_flushes.extend(published.wal_flush);
+        }
+    }
 
     app.persist_executor.trigger();
 
-    crate::operations::delivery::try_to_deliver_to_subscribers(
-        app.as_ref(),
-        &topic,
-        &mut topic_data,
-    );
+    // Delivery may lock the dead-letter topic, so it goes topic by topic.
+    for topic in topics.iter() {
+        let mut topic_data = topic.get_access();
+        crate::operations::delivery::try_to_deliver_to_subscribers(
+            app.as_ref(),
+            topic,
+            &mut topic_data,
+        );
+    }
+
+    for (topic, to_forward) in topics.iter().zip(to_forward) {
+        if to_forward.len() > 0 {
+            super::forward_messages(app.as_ref(), topic, to_forward);
+        }
+    }
+
+    let results =
+        futures_util::future::join_all(wal_flushes.into_iter().map(|itm| itm.wait())).await;
+
+    for result in results {
+        result.map_err(wal_failed)?;
+    }
 
     Ok(())
 }
+
+fn wal_failed(err: std::io::Error) -> OperationFailResult {
+    OperationFailResult::PersistenceError(format!(
+        "Messages are not written to the write-ahead log: {:?}",
+        err
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    fn create_messages(amount: usize) -> Vec<MessageToPublish> {
+        (0..amount)
+            .map(|_| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_transaction_publishes_nothing_if_one_topic_is_deleted() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let topic_a = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "topic-a",
+        )
+        .await
+        .unwrap();
+
+        let topic_b = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "topic-b",
+        )
+        .await
+        .unwrap();
+
+        topic_b.set_deleted(1);
+
+        let result = super::publish_transaction(
+            &app,
+            &namespace,
+            vec![
+                ("topic-a".to_string(), create_messages(2)),
+                ("topic-b".to_string(), create_messages(1)),
+            ],
+            session.session_id,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(0, topic_a.get_message_id().get_value());
+
+        topic_b.set_deleted(0);
+
+        super::publish_transaction(
+            &app,
+            &namespace,
+            vec![
+                ("topic-a".to_string(), create_messages(2)),
+                ("topic-b".to_string(), create_messages(1)),
+            ],
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(2, topic_a.get_message_id().get_value());
+        assert_eq!(1, topic_b.get_message_id().get_value());
+    }
+
+    #[tokio::test]
+    async fn test_refused_transaction_creates_no_topics() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "topic-a",
+        )
+        .await
+        .unwrap();
+
+        crate::operations::update_topic_quotas(
+            &app,
+            &namespace,
+            "topic-a".to_string(),
+            crate::quotas::Quotas {
+                max_memory_size: Some(5),
+                max_persist_queue_size: None,
+                max_queue_size: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        super::publish(
+            &app,
+            &namespace,
+            "topic-a",
+            create_messages(2),
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let result = super::publish_transaction(
+            &app,
+            &namespace,
+            vec![
+                ("topic-a".to_string(), create_messages(1)),
+                ("topic-b".to_string(), create_messages(1)),
+            ],
+            session.session_id,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(namespace.topic_list.get("topic-b").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_persist_immediately_returns_once_messages_are_saved() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "test-topic",
+        )
+        .await
+        .unwrap();
+
+        super::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(2),
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(2, topic.get_topic_size_metrics().persist_size);
+
+        super::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(1),
+            true,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(0, topic.get_topic_size_metrics().persist_size);
+    }
+
+    #[tokio::test]
+    async fn test_nothing_is_published_to_a_namespace_whose_route_is_not_restored() {
+        use std::sync::Arc;
+
+        use crate::grpc_client::MessagesPagesMockRepo;
+        use crate::operations::OperationFailResult;
+        use crate::persistence::{NamespaceRoutedPersistence, PersistenceBackend};
+
+        let tenants = Arc::new(MessagesPagesMockRepo::new());
+        let tenants_backend: Arc<dyn PersistenceBackend> = tenants.clone();
+
+        let persistence = Arc::new(NamespaceRoutedPersistence::new(
+            "default".to_string(),
+            Arc::new(MessagesPagesMockRepo::new()),
+            vec![(
+                "tenant-*".to_string(),
+                "tenants".to_string(),
+                tenants_backend,
+            )],
+        ));
+
+        tenants.set_fail_reads(true);
+        persistence.get_queue_snapshot().await.unwrap();
+
+        let app = crate::test_tools::create_app_context_with_persistence(persistence).await;
+
+        let namespace = app.namespaces.get_or_create("tenant-a").unwrap();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let result = super::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(1),
+            false,
+            session.session_id,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(OperationFailResult::PersistenceRouteIsNotRestored { route }) if route == "tenants"
+        ));
+        assert!(namespace.topic_list.get("test-topic").is_none());
+
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        super::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(1),
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_publish_is_acked_once_its_messages_are_in_the_write_ahead_log() {
+        use std::sync::Arc;
+
+        use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+        use crate::grpc_client::MessagesPagesMockRepo;
+
+        let path = std::env::temp_dir().join(format!(
+            "my-sb-publish-wal-test-{}",
+            DateTimeAsMicroseconds::now().unix_microseconds
+        ));
+
+        let mut settings = crate::test_tools::create_test_settings();
+        settings.wal_path = Some(path.to_string_lossy().to_string());
+
+        let app = crate::test_tools::create_app_context_with_settings(
+            Arc::new(MessagesPagesMockRepo::new()),
+            settings,
+        )
+        .await;
+
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        super::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(2),
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let segment_path = path
+            .join(namespace.name.as_str())
+            .join("test-topic")
+            .join("0.wal");
+
+        let content = std::fs::read(segment_path).unwrap();
+        assert_eq!(2, crate::wal::read_segment(&content).len());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+use my_service_bus::abstractions::subscriber::TopicQueueType;
+
+use super::super::OperationFailResult;
+
+use crate::{app::AppContext, namespaces::Namespace};
+
+/// Creates a permanent queue `to_queue_id` which starts exactly where `from_queue_id`
+/// is now, including the messages which are on delivery from it. The new queue has
+/// no subscribers and takes none of the settings of the source. Returns its size.
+pub async fn clone_queue(
+    app: &AppContext,
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    from_queue_id: &str,
+    to_queue_id: &str,
+) -> Result<usize, OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    if topic.get_deleted() != 0 {
+        return Err(OperationFailResult::TopicIsDeleted {
+            topic_id: topic_id.to_string(),
+        });
+    }
+
+    let mut topic_data = topic.get_access();
+
+    let position = topic_data
+        .queues
+        .get(from_queue_id)
+        .ok_or(OperationFailResult::QueueNotFound {
+            queue_id: from_queue_id.to_string(),
+        })?
+        .get_position();
+
+    if topic_data.queues.get(to_queue_id).is_some() {
+        return Err(OperationFailResult::Other(format!(
+            "Queue {} already exists in topic {}",
+            to_queue_id, topic_id
+        )));
+    }
+
+    let topic_queue = topic_data.queues.restore(
+        topic.topic_id.clone(),
+        to_queue_id.into(),
+        TopicQueueType::Permanent,
+        position,
+    );
+
+    let size = topic_queue.get_queue_size();
+
+    my_logger::LOGGER.write_info(
+        "clone_queue",
+        format!("Queue {} is cloned into {}", from_queue_id, to_queue_id),
+        LogEventCtx::new()
+            .add("namespace", namespace.name.as_str())
+            .add("topicId", topic_id)
+            .add("queueId", from_queue_id)
+            .add("newQueueId", to_queue_id)
+            .add("size", size.to_string()),
+    );
+
+    app.persist_executor.trigger();
+
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cloned_queue_starts_at_the_same_position() {
+        const TOPIC_NAME: &str = "test-topic";
+        const QUEUE_NAME: &str = "test-queue";
+        const CLONED_QUEUE_NAME: &str = "test-queue-clone";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        topic.get_access().queues.add_queue_if_not_exists(
+            topic.topic_id.clone(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::PermanentWithSingleConnection,
+        );
+
+        let messages = (0..3)
+            .map(|_| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            messages,
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let size = clone_queue(&app, &namespace, TOPIC_NAME, QUEUE_NAME, CLONED_QUEUE_NAME)
+            .await
+            .unwrap();
+
+        assert_eq!(size, 3);
+
+        let topic_data = topic.get_access();
+        let cloned_queue = topic_data.queues.get(CLONED_QUEUE_NAME).unwrap();
+
+        assert!(cloned_queue.is_permanent());
+        assert!(!cloned_queue.queue_type.is_single_connection());
+        assert_eq!(cloned_queue.subscribers.get_amount(), 0);
+        assert_eq!(cloned_queue.get_queue_size(), 3);
+    }
+}
@@ -2,3 +2,21 @@ mod set_max_messages_per_payload;
 pub use set_max_messages_per_payload::*;
 mod set_message_id;
 pub use set_message_id::*;
+mod set_dead_letter;
+pub use set_dead_letter::*;
+mod set_redelivery_policy;
+pub use set_redelivery_policy::*;
+mod set_ordering_key;
+pub use set_ordering_key::*;
+mod set_header_filter;
+pub use set_header_filter::*;
+mod seek_by_time;
+pub use seek_by_time::*;
+mod set_paused;
+pub use set_paused::*;
+mod purge_queue;
+pub use purge_queue::*;
+mod clone_queue;
+pub use clone_queue::*;
+mod set_delivery_timeout;
+pub use set_delivery_timeout::*;
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+
+use super::super::OperationFailResult;
+
+use crate::namespaces::Namespace;
+
+/// Drops the backlog of the queue, leaving only the newest `keep_last` messages.
+/// Returns how many messages were dropped.
+pub async fn purge_queue(
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    keep_last: usize,
+) -> Result<usize, OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access();
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    let dropped = topic_queue.purge(keep_last);
+
+    my_logger::LOGGER.write_info(
+        "purge_queue",
+        format!("Queue is purged. Dropped {} messages", dropped),
+        LogEventCtx::new()
+            .add("namespace", namespace.name.as_str())
+            .add("topicId", topic_id)
+            .add("queueId", queue_id)
+            .add("keepLast", keep_last.to_string())
+            .add("dropped", dropped.to_string()),
+    );
+
+    Ok(dropped)
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use my_service_bus::abstractions::MessageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, namespaces::Namespace};
+
+use super::super::OperationFailResult;
+
+/// Resets the queue to the first message created at `from` or later. In-memory
+/// sub-pages are checked first, then the persistence; a topic which does not
+/// persist has nothing but the cache, so its oldest cached message is as far
+/// back as the queue goes. If no message is that new, the queue is moved to the
+/// end of the topic.
+///
+/// Returns the message id the queue starts from now.
+pub async fn seek_by_time(
+    app: &AppContext,
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    from: DateTimeAsMicroseconds,
+) -> Result<MessageId, OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let (from_cache, persist) = {
+        let topic_data = topic.get_access();
+
+        if topic_data.queues.get(queue_id).is_none() {
+            return Err(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            });
+        }
+
+        let from_cache = if topic_data.persist {
+            topic_data.pages.find_first_message_id_by_time(from)
+        } else {
+            topic_data.pages.find_first_cached_message_id_by_time(from)
+        };
+
+        (from_cache, topic_data.persist)
+    };
+
+    let message_id = match MessageId::from_opt_i64(from_cache) {
+        Some(message_id) => Some(message_id),
+        None if !persist => None,
+        None => app
+            .persistence_client
+            .get_first_message_id_by_date(topic.as_grpc_namespace(), topic_id, from)
+            .await
+            .map_err(|err| OperationFailResult::PersistenceError(format!("{:?}", err)))?,
+    };
+
+    let mut topic_data = topic.get_access();
+
+    let topic_message_id = topic_data.message_id;
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    let message_id = message_id.unwrap_or(topic_message_id);
+
+    topic_queue.set_message_id(message_id, topic_message_id);
+
+    Ok(message_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{
+        publisher::MessageToPublish, subscriber::TopicQueueType, SbMessageHeaders,
+    };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    const TOPIC_NAME: &str = "test-topic";
+    const QUEUE_NAME: &str = "test-queue";
+
+    #[tokio::test]
+    async fn test_seek_before_the_cache_of_a_topic_which_does_not_persist_replays_it() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        crate::operations::update_topic_persist(&namespace, TOPIC_NAME.to_string(), false)
+            .await
+            .unwrap();
+
+        let from = DateTimeAsMicroseconds::now();
+
+        let messages = (0..2)
+            .map(|_| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        topic.get_access().queues.add_queue_if_not_exists(
+            topic.topic_id.clone(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::Permanent,
+        );
+
+        let message_id =
+            super::seek_by_time(app.as_ref(), &namespace, TOPIC_NAME, QUEUE_NAME, from)
+                .await
+                .unwrap();
+
+        assert_eq!(0, message_id.get_value());
+
+        let topic_data = topic.get_access();
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(2, queue.get_queue_size());
+    }
+}
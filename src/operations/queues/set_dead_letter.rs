@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use super::super::OperationFailResult;
+
+use crate::{
+    namespaces::Namespace,
+    queues::{DeadLetterSettings, DeadLetterTarget},
+};
+
+pub async fn set_dead_letter(
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    dead_letter: Option<DeadLetterSettings>,
+) -> Result<(), OperationFailResult> {
+    if let Some(DeadLetterTarget::Queue(dead_letter_queue_id)) =
+        dead_letter.as_ref().map(|itm| &itm.target)
+    {
+        if dead_letter_queue_id == queue_id {
+            return Err(OperationFailResult::Other(format!(
+                "Queue {} can not be its own dead-letter queue",
+                queue_id
+            )));
+        }
+    }
+
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access();
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    topic_queue.dead_letter = dead_letter;
+
+    Ok(())
+}
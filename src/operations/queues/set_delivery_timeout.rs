@@ -0,0 +1,34 @@
+use std::{sync::Arc, time::Duration};
+
+use super::super::OperationFailResult;
+
+use crate::namespaces::Namespace;
+
+/// `None` makes the queue use the global delivery timeout again.
+pub async fn set_delivery_timeout(
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    delivery_timeout: Option<Duration>,
+) -> Result<(), OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access();
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    topic_queue.delivery_timeout = delivery_timeout;
+
+    Ok(())
+}
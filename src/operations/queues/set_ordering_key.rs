@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use super::super::OperationFailResult;
+
+use crate::{namespaces::Namespace, queues::OrderingKeys};
+
+/// `None` switches the ordering key mode off.
+pub async fn set_ordering_key(
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    header: Option<String>,
+) -> Result<(), OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access();
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    topic_queue.ordering_keys = header.map(OrderingKeys::new);
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use super::super::OperationFailResult;
+
+use crate::{app::AppContext, namespaces::Namespace};
+
+/// Returns the flag the queue had before the call.
+pub async fn set_paused(
+    app: &AppContext,
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    paused: bool,
+) -> Result<bool, OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access();
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    let was_paused = topic_queue.paused;
+    topic_queue.paused = paused;
+
+    if was_paused && !paused {
+        // Messages piled up while the queue was paused.
+        crate::operations::delivery::try_to_deliver_to_subscribers(app, &topic, &mut topic_data);
+    }
+
+    Ok(was_paused)
+}
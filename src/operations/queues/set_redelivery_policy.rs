@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use super::super::OperationFailResult;
+
+use crate::{namespaces::Namespace, queues::RedeliveryPolicy};
+
+pub async fn set_redelivery_policy(
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    queue_id: &str,
+    redelivery: Option<RedeliveryPolicy>,
+) -> Result<(), OperationFailResult> {
+    let topic = namespace
+        .topic_list
+        .get(topic_id)
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access();
+
+    let topic_queue =
+        topic_data
+            .queues
+            .get_mut(queue_id)
+            .ok_or(OperationFailResult::QueueNotFound {
+                queue_id: queue_id.to_string(),
+            })?;
+
+    topic_queue.redelivery = redelivery;
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+
+use crate::{
+    app::AppContext,
+    namespaces::Namespace,
+    quotas::{QuotaExceeded, Quotas},
+    topics::TopicInner,
+};
+
+use super::OperationFailResult;
+
+pub async fn update_topic_quotas(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    quotas: Quotas,
+) -> Result<(), OperationFailResult> {
+    let topic = match namespace.topic_list.get(topic_id.as_str()) {
+        Some(topic) => topic,
+        None => return Err(OperationFailResult::TopicNotFound { topic_id }),
+    };
+
+    my_logger::LOGGER.write_info(
+        "update_topic_quotas",
+        format!("Topic quotas are set: {:?}", quotas),
+        LogEventCtx::new()
+            .add("namespace", namespace.name.as_str())
+            .add("topicId", topic_id.as_str()),
+    );
+
+    topic.update_quotas(quotas);
+
+    app.persist_executor.trigger();
+
+    Ok(())
+}
+
+/// Called under the lock of the topic, right before the messages are appended.
+/// The quotas of the namespace are checked against the usage of the last second.
+pub fn check_publish_quotas(
+    app: &AppContext,
+    namespace: &Namespace,
+    topic_data: &TopicInner,
+) -> Result<(), OperationFailResult> {
+    if !topic_data.quotas.is_empty() {
+        if let Err(err) = topic_data.quotas.check(&topic_data.get_quota_usage()) {
+            return Err(quota_exceeded(
+                app,
+                namespace,
+                topic_data.topic_id.as_str(),
+                format!("topic {}/{}", namespace.name, topic_data.topic_id.as_str()),
+                err,
+            ));
+        }
+    }
+
+    check_namespace_quotas(app, namespace, topic_data.topic_id.as_str())
+}
+
+/// For a topic which is not there yet and so has no quotas of its own.
+pub fn check_namespace_quotas(
+    app: &AppContext,
+    namespace: &Namespace,
+    topic_id: &str,
+) -> Result<(), OperationFailResult> {
+    if let Some(quotas) = app.settings.get_namespace_quotas(namespace.name.as_str()) {
+        if let Err(err) = quotas.check(&namespace.quota_usage.get()) {
+            return Err(quota_exceeded(
+                app,
+                namespace,
+                topic_id,
+                format!("namespace {}", namespace.name),
+                err,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn quota_exceeded(
+    app: &AppContext,
+    namespace: &Namespace,
+    topic_id: &str,
+    scope: String,
+    err: QuotaExceeded,
+) -> OperationFailResult {
+    app.prometheus
+        .quota_rejected_publish(namespace.name.as_str(), topic_id, err.kind.as_str());
+
+    OperationFailResult::QuotaExceeded(QuotaExceeded { scope, ..err })
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    use crate::{
+        operations::OperationFailResult,
+        quotas::{QuotaExceeded, QuotaKind, Quotas},
+    };
+
+    fn create_messages() -> Vec<MessageToPublish> {
+        (0..2)
+            .map(|_| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![0u8, 1u8, 2u8],
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_publish_is_refused_once_topic_is_over_quota() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        let topic = crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            "test-topic",
+        )
+        .await
+        .unwrap();
+
+        super::update_topic_quotas(
+            &app,
+            &namespace,
+            "test-topic".to_string(),
+            Quotas {
+                max_memory_size: Some(5),
+                max_persist_queue_size: None,
+                max_queue_size: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(),
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let result = crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            "test-topic",
+            create_messages(),
+            false,
+            session.session_id,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(OperationFailResult::QuotaExceeded(QuotaExceeded {
+                kind: QuotaKind::MemorySize,
+                ..
+            }))
+        ));
+
+        assert_eq!(2, topic.get_message_id().get_value());
+    }
+}
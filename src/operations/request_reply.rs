@@ -0,0 +1,344 @@
+use std::{sync::Arc, time::Duration};
+
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, subscriber::TopicQueueType, SbMessageHeaders,
+};
+
+use crate::{
+    app::{AppContext, ReplyKey, ReplyMessage},
+    namespaces::Namespace,
+    queues::HeaderFilter,
+    sessions::{MyServiceBusSession, SessionId},
+    utils::get_header,
+};
+
+use super::OperationFailResult;
+
+/// Topic the replier should publish its reply to.
+pub const REPLY_TO_HEADER: &str = "reply-to";
+/// Ties a reply to its request. The replier copies it from the request as is.
+pub const CORRELATION_ID_HEADER: &str = "correlation-id";
+
+pub struct ReplyQueue {
+    pub topic_id: String,
+    pub queue_id: String,
+    /// Replies reach this queue only if their correlation id starts with it.
+    pub correlation_id_prefix: String,
+}
+
+/// Creates a `DeleteOnDisconnect` queue on the reply topic, subscribed by the
+/// session itself: it lives exactly as long as the session does. The queue only
+/// lets in the replies whose correlation id starts with its own prefix, so many
+/// requesters can share a single reply topic.
+pub async fn create_reply_queue(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    reply_topic_id: String,
+    session: MyServiceBusSession,
+) -> Result<ReplyQueue, OperationFailResult> {
+    let queue_id = get_reply_queue_id(session.session_id);
+    let correlation_id_prefix = get_correlation_id_prefix(session.session_id);
+
+    let topic = match namespace.topic_list.get(reply_topic_id.as_str()) {
+        Some(topic) => topic,
+        None => {
+            if app.settings.auto_create_topic_on_subscribe {
+                super::check_persistence_route(app.as_ref(), namespace)?;
+
+                namespace
+                    .topic_list
+                    .add_if_not_exists(reply_topic_id.as_str())?
+            } else {
+                return Err(OperationFailResult::TopicNotFound {
+                    topic_id: reply_topic_id,
+                });
+            }
+        }
+    };
+
+    if topic.get_deleted() != 0 {
+        return Err(OperationFailResult::TopicIsDeleted {
+            topic_id: reply_topic_id,
+        });
+    }
+
+    {
+        let mut topic_data = topic.get_access();
+        let topic_queue = topic_data.queues.add_queue_if_not_exists(
+            topic.topic_id.clone(),
+            queue_id.clone(),
+            TopicQueueType::DeleteOnDisconnect,
+        );
+
+        topic_queue.header_filter = Some(HeaderFilter::Prefix {
+            header: CORRELATION_ID_HEADER.to_string(),
+            value: correlation_id_prefix.clone(),
+        });
+    }
+
+    super::subscriber::subscribe_to_queue(
+        app,
+        namespace,
+        reply_topic_id.clone(),
+        queue_id.clone(),
+        TopicQueueType::DeleteOnDisconnect,
+        session,
+    )
+    .await?;
+
+    Ok(ReplyQueue {
+        topic_id: reply_topic_id,
+        queue_id,
+        correlation_id_prefix,
+    })
+}
+
+fn get_reply_queue_id(session_id: SessionId) -> String {
+    format!("reply-{}", session_id.get_value())
+}
+
+fn get_correlation_id_prefix(session_id: SessionId) -> String {
+    format!("{}:", get_reply_queue_id(session_id))
+}
+
+/// Publishes the request with `reply-to` and `correlation-id` set and waits for
+/// the reply carrying the same correlation id. `None` means the time is out.
+///
+/// A correlation id the message already has is kept; otherwise a new one is made
+/// with the prefix of the reply queue of the session, so the reply gets through
+/// its filter.
+pub async fn request(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: &str,
+    reply_topic_id: &str,
+    message: MessageToPublish,
+    timeout: Duration,
+    session_id: SessionId,
+) -> Result<Option<ReplyMessage>, OperationFailResult> {
+    let correlation_id = match get_header(&message.headers, CORRELATION_ID_HEADER) {
+        Some(correlation_id) => correlation_id.to_string(),
+        None => format!(
+            "{}{}",
+            get_correlation_id_prefix(session_id),
+            uuid::Uuid::new_v4()
+        ),
+    };
+
+    let mut headers = SbMessageHeaders::new();
+
+    for (key, value) in message.headers.iter() {
+        if key == REPLY_TO_HEADER || key == CORRELATION_ID_HEADER {
+            continue;
+        }
+
+        headers = headers.add(key.to_string(), value.to_string());
+    }
+
+    let headers = headers
+        .add(REPLY_TO_HEADER.to_string(), reply_topic_id.to_string())
+        .add(CORRELATION_ID_HEADER.to_string(), correlation_id.clone());
+
+    let message = MessageToPublish {
+        headers,
+        content: message.content,
+    };
+
+    let reply_key = ReplyKey {
+        namespace: namespace.name.clone(),
+        reply_topic_id: reply_topic_id.to_string(),
+        correlation_id,
+    };
+
+    // Engaged before publishing: the reply may come back before publish returns.
+    let awaiter = app
+        .reply_awaiters
+        .engage(reply_key.clone(), timeout)
+        .map_err(OperationFailResult::Other)?;
+
+    let publish_result =
+        super::publisher::publish(app, namespace, topic_id, vec![message], false, session_id).await;
+
+    if let Err(err) = publish_result {
+        app.reply_awaiters.cancel(&reply_key);
+        return Err(err);
+    }
+
+    awaiter
+        .get_result()
+        .await
+        .map_err(OperationFailResult::Other)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    use super::CORRELATION_ID_HEADER;
+
+    #[tokio::test]
+    async fn test_request_gets_correlated_reply() {
+        const REQUEST_TOPIC: &str = "requests";
+        const REPLY_TOPIC: &str = "replies";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        for topic_id in [REQUEST_TOPIC, REPLY_TOPIC] {
+            crate::operations::create_topic_if_not_exists(
+                &namespace,
+                Some(session.session_id),
+                topic_id,
+            )
+            .await
+            .unwrap();
+        }
+
+        let request = MessageToPublish {
+            headers: SbMessageHeaders::new()
+                .add(CORRELATION_ID_HEADER.to_string(), "req-1".to_string()),
+            content: vec![1u8],
+        };
+
+        let requester = {
+            let app = app.clone();
+            let namespace = namespace.clone();
+            let session_id = session.session_id;
+            tokio::spawn(async move {
+                super::request(
+                    &app,
+                    &namespace,
+                    REQUEST_TOPIC,
+                    REPLY_TOPIC,
+                    request,
+                    Duration::from_secs(5),
+                    session_id,
+                )
+                .await
+            })
+        };
+
+        while app.reply_awaiters.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let reply = MessageToPublish {
+            headers: SbMessageHeaders::new()
+                .add(CORRELATION_ID_HEADER.to_string(), "req-1".to_string()),
+            content: vec![2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            REPLY_TOPIC,
+            vec![reply],
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let reply = requester.await.unwrap().unwrap().unwrap();
+
+        assert_eq!(vec![2u8], reply.content);
+    }
+
+    #[tokio::test]
+    async fn test_reply_with_generated_correlation_id_gets_through_reply_queue() {
+        const REQUEST_TOPIC: &str = "requests";
+        const REPLY_TOPIC: &str = "replies";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+        let session = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(session.session_id),
+            REQUEST_TOPIC,
+        )
+        .await
+        .unwrap();
+
+        let reply_queue = super::create_reply_queue(
+            &app,
+            &namespace,
+            REPLY_TOPIC.to_string(),
+            session.clone().into(),
+        )
+        .await
+        .unwrap();
+
+        let request = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![1u8],
+        };
+
+        let requester = {
+            let app = app.clone();
+            let namespace = namespace.clone();
+            let session_id = session.session_id;
+            tokio::spawn(async move {
+                super::request(
+                    &app,
+                    &namespace,
+                    REQUEST_TOPIC,
+                    REPLY_TOPIC,
+                    request,
+                    Duration::from_secs(5),
+                    session_id,
+                )
+                .await
+            })
+        };
+
+        while app.reply_awaiters.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        // The replier copies the correlation id from the request as is.
+        let correlation_id = {
+            let topic = namespace.topic_list.get(REQUEST_TOPIC).unwrap();
+            let topic_data = topic.get_access();
+
+            match topic_data.get_message(0.into()).unwrap() {
+                crate::sub_page::GetMessageResult::Message(message) => {
+                    crate::utils::get_header(&message.headers, CORRELATION_ID_HEADER)
+                        .unwrap()
+                        .to_string()
+                }
+                _ => panic!("Request is not stored"),
+            }
+        };
+
+        assert!(correlation_id.starts_with(reply_queue.correlation_id_prefix.as_str()));
+
+        let reply = MessageToPublish {
+            headers: SbMessageHeaders::new().add(CORRELATION_ID_HEADER.to_string(), correlation_id),
+            content: vec![2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            REPLY_TOPIC,
+            vec![reply],
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let reply = requester.await.unwrap().unwrap().unwrap();
+        assert_eq!(vec![2u8], reply.content);
+
+        app.restore_page_scheduler.emulate_event_loop_tick().await;
+
+        // The reply is not filtered out of the reply queue of the session.
+        assert_eq!(session.get_list_of_packets_and_clear_them().len(), 1);
+    }
+}
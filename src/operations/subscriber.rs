@@ -28,6 +28,7 @@ pub async fn subscribe_to_queue(
             Some(result) => result,
             None => {
                 if app.settings.auto_create_topic_on_subscribe {
+                    super::check_persistence_route(app.as_ref(), namespace)?;
                     namespace.topic_list.add_if_not_exists(topic_id.as_str())?
                 } else {
                     return Err(OperationFailResult::TopicNotFound { topic_id });
@@ -100,6 +101,8 @@ pub async fn subscribe_to_queue(
         }
     }
 
+    topic_queue.rebalance_ordering_keys();
+
     crate::operations::delivery::try_to_deliver_to_subscribers(
         app.as_ref(),
         &topic,
@@ -115,6 +118,8 @@ pub fn remove_subscriber(queue: &mut TopicQueue, mut subscriber: QueueSubscriber
     if let Some(delivery_state_data) = &messages {
         queue.confirm_non_delivered(&delivery_state_data.bucket.to_be_confirmed);
     }
+
+    queue.rebalance_ordering_keys();
 }
 
 #[cfg(test)]
@@ -0,0 +1,24 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::namespaces::Namespace;
+
+use super::OperationFailResult;
+
+pub async fn update_topic_dedup(
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    window: Option<Duration>,
+    max_keys: Option<usize>,
+) -> Result<(), OperationFailResult> {
+    let topic = namespace.topic_list.get(topic_id.as_str());
+
+    if topic.is_none() {
+        return Err(OperationFailResult::TopicNotFound { topic_id });
+    }
+
+    let topic = topic.unwrap();
+
+    topic.update_dedup(window, max_keys);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+
+use crate::{app::AppContext, namespaces::Namespace, persistence::PersistenceCompression};
+
+use super::OperationFailResult;
+
+/// `None` brings the topic back to the compression of the settings.
+pub async fn update_topic_persist_compression(
+    app: &Arc<AppContext>,
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    persist_compression: Option<PersistenceCompression>,
+) -> Result<(), OperationFailResult> {
+    let topic = match namespace.topic_list.get(topic_id.as_str()) {
+        Some(topic) => topic,
+        None => return Err(OperationFailResult::TopicNotFound { topic_id }),
+    };
+
+    my_logger::LOGGER.write_info(
+        "update_topic_persist_compression",
+        format!(
+            "Topic persist compression is set: {}",
+            match persist_compression {
+                Some(compression) => compression.as_str(),
+                None => "default",
+            }
+        ),
+        LogEventCtx::new()
+            .add("namespace", namespace.name.as_str())
+            .add("topicId", topic_id.as_str()),
+    );
+
+    topic.update_persist_compression(persist_compression);
+
+    app.persist_executor.trigger();
+
+    Ok(())
+}
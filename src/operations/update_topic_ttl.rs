@@ -0,0 +1,71 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::namespaces::Namespace;
+
+use super::OperationFailResult;
+
+/// Ten years. Anything longer is no TTL at all, and it has to fit in the
+/// microseconds the expiry moment is counted in.
+pub const MAX_TOPIC_TTL: Duration = Duration::from_secs(10 * 365 * 24 * 60 * 60);
+
+pub async fn update_topic_ttl(
+    namespace: &Arc<Namespace>,
+    topic_id: String,
+    ttl: Option<Duration>,
+) -> Result<(), OperationFailResult> {
+    if let Some(ttl) = ttl {
+        if ttl > MAX_TOPIC_TTL {
+            return Err(OperationFailResult::Other(format!(
+                "TTL {:?} is longer than {:?}",
+                ttl, MAX_TOPIC_TTL
+            )));
+        }
+    }
+
+    let topic = namespace.topic_list.get(topic_id.as_str());
+
+    if topic.is_none() {
+        return Err(OperationFailResult::TopicNotFound { topic_id });
+    }
+
+    let topic = topic.unwrap();
+
+    topic.update_ttl(ttl);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_ttl_longer_than_the_max_is_refused() {
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let topic = crate::operations::create_topic_if_not_exists(&namespace, None, "test-topic")
+            .await
+            .unwrap();
+
+        let result = super::update_topic_ttl(
+            &namespace,
+            "test-topic".to_string(),
+            Some(Duration::from_millis(u64::MAX)),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(None, topic.get_access().ttl);
+
+        super::update_topic_ttl(
+            &namespace,
+            "test-topic".to_string(),
+            Some(super::MAX_TOPIC_TTL),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Some(super::MAX_TOPIC_TTL), topic.get_access().ttl);
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How messages travel to and from the persistence service. Values on the wire are
+/// the ones of `PersistenceCompressionGrpcEnum`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PersistenceCompression {
+    None,
+    Deflate,
+    Zstd,
+}
+
+impl PersistenceCompression {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src.to_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "deflate" => Some(Self::Deflate),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Deflate => "deflate",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    pub fn as_grpc(&self) -> i32 {
+        match self {
+            Self::None => 0,
+            Self::Deflate => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    pub fn from_grpc(src: i32) -> Option<Self> {
+        match src {
+            0 => Some(Self::None),
+            1 => Some(Self::Deflate),
+            2 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn compress(&self, src: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(src.to_vec()),
+            Self::Deflate => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(src)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::encode_all(src, 0),
+        }
+    }
+
+    /// Deflate data comes with the zlib header and checksum, so a zip page of the
+    /// legacy `GetPageCompressed` fails to decode instead of decoding to garbage.
+    pub fn decompress(&self, src: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(src.to_vec()),
+            Self::Deflate => {
+                let mut result = Vec::new();
+                flate2::read::ZlibDecoder::new(src).read_to_end(&mut result)?;
+                Ok(result)
+            }
+            Self::Zstd => zstd::decode_all(src),
+        }
+    }
+}
+
+/// Bytes which went through compression, before and after it. Only transfers which
+/// were compressed are counted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CompressionStats {
+    pub uploaded: u64,
+    pub uploaded_compressed: u64,
+    pub downloaded: u64,
+    pub downloaded_compressed: u64,
+}
+
+impl CompressionStats {
+    pub fn append(&mut self, other: &CompressionStats) {
+        self.uploaded += other.uploaded;
+        self.uploaded_compressed += other.uploaded_compressed;
+        self.downloaded += other.downloaded;
+        self.downloaded_compressed += other.downloaded_compressed;
+    }
+}
+
+#[derive(Default)]
+pub struct CompressionCounters {
+    uploaded: AtomicU64,
+    uploaded_compressed: AtomicU64,
+    downloaded: AtomicU64,
+    downloaded_compressed: AtomicU64,
+}
+
+impl CompressionCounters {
+    pub fn uploaded(&self, size: usize, compressed_size: usize) {
+        self.uploaded.fetch_add(size as u64, Ordering::Relaxed);
+        self.uploaded_compressed
+            .fetch_add(compressed_size as u64, Ordering::Relaxed);
+    }
+
+    pub fn downloaded(&self, size: usize, compressed_size: usize) {
+        self.downloaded.fetch_add(size as u64, Ordering::Relaxed);
+        self.downloaded_compressed
+            .fetch_add(compressed_size as u64, Ordering::Relaxed);
+    }
+
+    pub fn get_stats(&self) -> CompressionStats {
+        CompressionStats {
+            uploaded: self.uploaded.load(Ordering::Relaxed),
+            uploaded_compressed: self.uploaded_compressed.load(Ordering::Relaxed),
+            downloaded: self.downloaded.load(Ordering::Relaxed),
+            downloaded_compressed: self.downloaded_compressed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let src: Vec<u8> = (0..10_000).map(|itm| (itm % 7) as u8).collect();
+
+        for compression in [
+            PersistenceCompression::None,
+            PersistenceCompression::Deflate,
+            PersistenceCompression::Zstd,
+        ] {
+            let compressed = compression.compress(&src).unwrap();
+
+            if compression != PersistenceCompression::None {
+                assert!(compressed.len() < src.len());
+            }
+
+            assert_eq!(src, compression.decompress(&compressed).unwrap());
+            assert_eq!(
+                Some(compression),
+                PersistenceCompression::from_grpc(compression.as_grpc())
+            );
+            assert_eq!(
+                Some(compression),
+                PersistenceCompression::parse(compression.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_garbage_does_not_decompress() {
+        let garbage = vec![0x50, 0x4b, 0x03, 0x04, 1, 2, 3, 4];
+
+        assert!(PersistenceCompression::Deflate
+            .decompress(&garbage)
+            .is_err());
+        assert!(PersistenceCompression::Zstd.decompress(&garbage).is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::{
+    page_id::PageId, protobuf_models::MessageProtobufModel, sub_page::SubPageId,
+    validators::DEFAULT_NAMESPACE,
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::io::AsyncWriteExt;
+
+use crate::grpc_client::PersistenceError;
+use crate::messages_page::MySbMessageContent;
+use crate::persistence_grpc::{MessageContentGrpcModel, TopicAndQueuesSnapshotGrpcModel};
+use crate::topics::TopicSnapshot;
+use crate::utils::{read_protobuf_records, write_protobuf_record};
+
+use super::{PersistenceBackend, PersistenceCompression};
+
+const SNAPSHOT_FILE_NAME: &str = "topics_and_queues.snapshot";
+const SUB_PAGE_EXTENSION: &str = "messages";
+
+/// Keeps everything in a local folder, so a node can run without the separate
+/// persistence process:
+/// - `{path}/topics_and_queues.snapshot` - the topics and queues snapshot;
+/// - `{path}/{namespace}/{topic_id}/{sub_page_id}.messages` - messages of a sub-page.
+///
+/// Message files are only appended to. A message saved twice is read back as
+/// the last copy of it.
+pub struct FilePersistence {
+    path: PathBuf,
+}
+
+impl FilePersistence {
+    pub fn new(path: String) -> Self {
+        Self {
+            path: PathBuf::from(path),
+        }
+    }
+
+    fn get_topic_path(&self, namespace: Option<&str>, topic_id: &str) -> PathBuf {
+        self.path
+            .join(namespace.unwrap_or(DEFAULT_NAMESPACE))
+            .join(topic_id)
+    }
+
+    fn get_sub_page_path(&self, topic_path: &Path, sub_page_id: SubPageId) -> PathBuf {
+        topic_path.join(format!(
+            "{}.{}",
+            sub_page_id.get_value(),
+            SUB_PAGE_EXTENSION
+        ))
+    }
+
+    async fn read_sub_page(
+        &self,
+        topic_path: &Path,
+        sub_page_id: SubPageId,
+    ) -> Result<Vec<MessageContentGrpcModel>, PersistenceError> {
+        let content = read_if_exists(self.get_sub_page_path(topic_path, sub_page_id)).await?;
+        Ok(read_protobuf_records(&content))
+    }
+}
+
+#[async_trait::async_trait]
+impl PersistenceBackend for FilePersistence {
+    async fn load_page(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        _page_id: PageId,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+        _compression: PersistenceCompression,
+    ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
+        let topic_path = self.get_topic_path(namespace.as_deref(), topic_id);
+
+        let from_sub_page: SubPageId = from_message_id.into();
+        let to_sub_page: SubPageId = to_message_id.into();
+
+        let mut result = BTreeMap::new();
+
+        for sub_page_id in from_sub_page.get_value()..=to_sub_page.get_value() {
+            for message in self
+                .read_sub_page(&topic_path, SubPageId::new(sub_page_id))
+                .await?
+            {
+                if message.message_id >= from_message_id.get_value()
+                    && message.message_id <= to_message_id.get_value()
+                {
+                    result.insert(message.message_id, message.into());
+                }
+            }
+        }
+
+        Ok(Some(result))
+    }
+
+    async fn get_message(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MessageContentGrpcModel>, PersistenceError> {
+        let topic_path = self.get_topic_path(namespace.as_deref(), topic_id);
+
+        let result = self
+            .read_sub_page(&topic_path, message_id.into())
+            .await?
+            .into_iter()
+            .filter(|itm| itm.message_id == message_id.get_value())
+            .last();
+
+        Ok(result)
+    }
+
+    async fn get_first_message_id_by_date(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        from: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageId>, PersistenceError> {
+        let topic_path = self.get_topic_path(namespace.as_deref(), topic_id);
+
+        let mut sub_page_ids = Vec::new();
+
+        if topic_path.exists() {
+            let mut dir = tokio::fs::read_dir(&topic_path).await?;
+
+            while let Some(entry) = dir.next_entry().await? {
+                let path = entry.path();
+
+                if path.extension().map(|itm| itm == SUB_PAGE_EXTENSION) != Some(true) {
+                    continue;
+                }
+
+                let sub_page_id = path
+                    .file_stem()
+                    .and_then(|itm| itm.to_str())
+                    .and_then(|itm| itm.parse::<i64>().ok());
+
+                if let Some(sub_page_id) = sub_page_id {
+                    sub_page_ids.push(sub_page_id);
+                }
+            }
+        }
+
+        sub_page_ids.sort();
+
+        // Ids grow with time, so the first sub-page which has a message created
+        // late enough has the answer.
+        for sub_page_id in sub_page_ids {
+            let result = self
+                .read_sub_page(&topic_path, SubPageId::new(sub_page_id))
+                .await?
+                .iter()
+                .filter(|itm| itm.created >= from.unix_microseconds)
+                .map(|itm| itm.message_id)
+                .min();
+
+            if result.is_some() {
+                return Ok(MessageId::from_opt_i64(result));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn save_messages(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        messages: Vec<MessageProtobufModel>,
+        _compression: PersistenceCompression,
+    ) -> Result<(), PersistenceError> {
+        let topic_path = self.get_topic_path(namespace.as_deref(), topic_id);
+
+        let mut by_sub_page: BTreeMap<i64, Vec<u8>> = BTreeMap::new();
+
+        for message in messages {
+            let sub_page_id: SubPageId = message.get_message_id().into();
+            let message: MessageContentGrpcModel = message.into();
+
+            let buffer = by_sub_page.entry(sub_page_id.get_value()).or_default();
+            write_protobuf_record(buffer, &message);
+        }
+
+        tokio::fs::create_dir_all(&topic_path).await?;
+
+        for (sub_page_id, buffer) in by_sub_page {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.get_sub_page_path(&topic_path, SubPageId::new(sub_page_id)))
+                .await?;
+
+            file.write_all(&buffer).await?;
+            file.sync_data().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_persistence_version(&self) -> Option<String> {
+        Some(format!("File {}", crate::app::APP_VERSION))
+    }
+
+    async fn hard_delete_topic(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+    ) -> Result<(), PersistenceError> {
+        match tokio::fs::remove_dir_all(self.get_topic_path(namespace.as_deref(), topic_id)).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+        let content = read_if_exists(self.path.join(SNAPSHOT_FILE_NAME)).await?;
+
+        let result = read_protobuf_records::<TopicAndQueuesSnapshotGrpcModel>(&content)
+            .into_iter()
+            .map(|itm| itm.into())
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Written next to the current snapshot and then moved over it: a crash in
+    /// the middle leaves the previous snapshot in place.
+    async fn save_topic_and_queues(
+        &self,
+        data: Vec<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<(), PersistenceError> {
+        let mut buffer = Vec::new();
+
+        for topic in data.iter() {
+            write_protobuf_record(&mut buffer, topic);
+        }
+
+        tokio::fs::create_dir_all(&self.path).await?;
+
+        let tmp_path = self.path.join(format!("{}.tmp", SNAPSHOT_FILE_NAME));
+
+        let mut file = tokio::fs::File::create(&tmp_path).await?;
+        file.write_all(&buffer).await?;
+        file.sync_data().await?;
+
+        tokio::fs::rename(tmp_path, self.path.join(SNAPSHOT_FILE_NAME)).await?;
+
+        Ok(())
+    }
+}
+
+async fn read_if_exists(path: PathBuf) -> Result<Vec<u8>, PersistenceError> {
+    match tokio::fs::read(path).await {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{MessageId, SbMessageHeaders};
+    use my_service_bus::shared::{page_id::PageId, protobuf_models::MessageProtobufModel};
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::messages_page::MySbMessageContent;
+    use crate::persistence::{PersistenceBackend, PersistenceCompression};
+
+    use super::FilePersistence;
+
+    fn create_message(id: i64) -> MessageProtobufModel {
+        let message = MySbMessageContent::new(
+            id.into(),
+            vec![1, 2, 3],
+            SbMessageHeaders::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        (&message).into()
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_read_back_by_page_and_by_id() {
+        let path = std::env::temp_dir().join(format!(
+            "my-sb-file-persistence-test-{}",
+            DateTimeAsMicroseconds::now().unix_microseconds
+        ));
+
+        let persistence = FilePersistence::new(path.to_str().unwrap().to_string());
+
+        persistence
+            .save_messages(
+                None,
+                "test-topic",
+                vec![create_message(1), create_message(2), create_message(1_001)],
+                PersistenceCompression::None,
+            )
+            .await
+            .unwrap();
+
+        let page = persistence
+            .load_page(
+                None,
+                "test-topic",
+                PageId::new(0),
+                MessageId::new(0),
+                MessageId::new(999),
+                PersistenceCompression::None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(vec![1, 2], page.keys().copied().collect::<Vec<_>>());
+
+        let message = persistence
+            .get_message(None, "test-topic", MessageId::new(1_001))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(1_001, message.message_id);
+
+        assert!(persistence
+            .get_message(Some("alpha".to_string()), "test-topic", MessageId::new(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        persistence
+            .hard_delete_topic(None, "test-topic")
+            .await
+            .unwrap();
+
+        let page = persistence
+            .load_page(
+                None,
+                "test-topic",
+                PageId::new(0),
+                MessageId::new(0),
+                MessageId::new(999),
+                PersistenceCompression::None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(page.is_empty());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+mod persistence_backend;
+pub use persistence_backend::*;
+mod compression;
+pub use compression::*;
+mod file_persistence;
+pub use file_persistence::*;
+mod persistence_target;
+pub use persistence_target::*;
+mod replicated_persistence;
+pub use replicated_persistence::*;
+mod namespace_routed_persistence;
+pub use namespace_routed_persistence::*;
+
+use std::sync::Arc;
+
+use crate::{
+    grpc_client::PersistenceGrpcService,
+    settings::{PersistenceSettings, SettingsModel},
+};
+
+pub fn create_persistence_backend(settings: Arc<SettingsModel>) -> Arc<dyn PersistenceBackend> {
+    let (name, default) = create_default_backend(&settings.persistence);
+
+    if settings.persistence_routes.is_empty() {
+        return default;
+    }
+
+    let routes = settings
+        .persistence_routes
+        .iter()
+        .map(|route| {
+            let backend: Arc<dyn PersistenceBackend> =
+                Arc::new(PersistenceGrpcService::new(route.grpc_url.clone()));
+            (route.namespace.clone(), route.grpc_url.clone(), backend)
+        })
+        .collect();
+
+    Arc::new(NamespaceRoutedPersistence::new(name, default, routes))
+}
+
+/// Returns the name the backend is reported by as well.
+fn create_default_backend(settings: &PersistenceSettings) -> (String, Arc<dyn PersistenceBackend>) {
+    match settings {
+        PersistenceSettings::Grpc { urls, write_policy } => {
+            if urls.len() == 1 {
+                let backend: Arc<dyn PersistenceBackend> =
+                    Arc::new(PersistenceGrpcService::new(urls[0].clone()));
+                return (urls[0].clone(), backend);
+            }
+
+            let targets = urls
+                .iter()
+                .map(|url| {
+                    let backend: Arc<dyn PersistenceBackend> =
+                        Arc::new(PersistenceGrpcService::new(url.clone()));
+                    (url.clone(), backend)
+                })
+                .collect();
+
+            let backend: Arc<dyn PersistenceBackend> =
+                Arc::new(ReplicatedPersistence::new(targets, *write_policy));
+            ("default".to_string(), backend)
+        }
+        PersistenceSettings::File { path } => {
+            let backend: Arc<dyn PersistenceBackend> = Arc::new(FilePersistence::new(path.clone()));
+            (path.clone(), backend)
+        }
+    }
+}
@@ -0,0 +1,517 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::{
+    page_id::PageId, protobuf_models::MessageProtobufModel, validators::DEFAULT_NAMESPACE,
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::grpc_client::PersistenceError;
+use crate::messages_page::MySbMessageContent;
+use crate::persistence_grpc::{MessageContentGrpcModel, TopicAndQueuesSnapshotGrpcModel};
+use crate::topics::TopicSnapshot;
+
+use super::{
+    CompressionStats, PersistenceBackend, PersistenceCompression, PersistenceTarget,
+    PersistenceTargetSnapshot, ReplicationWrite,
+};
+
+/// `tenant-a` matches that namespace only. A pattern may have one `*` standing
+/// for any part of the name: `tenant-*`, `*-eu`, `*`.
+pub fn namespace_matches(pattern: &str, namespace: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            namespace.len() >= prefix.len() + suffix.len()
+                && namespace.starts_with(prefix)
+                && namespace.ends_with(suffix)
+        }
+        None => pattern == namespace,
+    }
+}
+
+struct RoutedTarget {
+    /// `None` for the default target, which gets every namespace no route matches.
+    pattern: Option<String>,
+    /// Nothing is queued to it: whatever it did not get is written again by the
+    /// next persist round, so there is no catch-up task to drain a queue.
+    target: PersistenceTarget,
+    /// `false` if the topics and queues of the route could not be read at start.
+    /// Nothing is written to such a route: the node does not know what is there,
+    /// and would overwrite it. It takes a restart once the route is back.
+    restored: AtomicBool,
+}
+
+impl RoutedTarget {
+    fn check_restored(&self) -> Result<(), PersistenceError> {
+        if self.restored.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        Err(PersistenceError::RouteIsNotRestored(
+            self.target.name.clone(),
+        ))
+    }
+}
+
+/// Sends everything about a namespace to the persistence its route points to.
+/// Routes are tried in the order of the settings; the first match wins.
+pub struct NamespaceRoutedPersistence {
+    /// The default target is the last one.
+    targets: Vec<RoutedTarget>,
+}
+
+impl NamespaceRoutedPersistence {
+    /// `routes` are `(pattern, name, backend)`.
+    pub fn new(
+        default_name: String,
+        default: Arc<dyn PersistenceBackend>,
+        routes: Vec<(String, String, Arc<dyn PersistenceBackend>)>,
+    ) -> Self {
+        let mut targets: Vec<_> = routes
+            .into_iter()
+            .map(|(pattern, name, backend)| {
+                let mut target = PersistenceTarget::new(name, true, backend);
+                target.namespaces = Some(pattern.clone());
+
+                RoutedTarget {
+                    pattern: Some(pattern),
+                    target,
+                    restored: AtomicBool::new(true),
+                }
+            })
+            .collect();
+
+        targets.push(RoutedTarget {
+            pattern: None,
+            target: PersistenceTarget::new(default_name, true, default),
+            restored: AtomicBool::new(true),
+        });
+
+        Self { targets }
+    }
+
+    fn get_target_index(&self, namespace: Option<&str>) -> usize {
+        let namespace = namespace
+            .filter(|itm| !itm.is_empty())
+            .unwrap_or(DEFAULT_NAMESPACE);
+
+        self.targets
+            .iter()
+            .position(|itm| match itm.pattern.as_deref() {
+                Some(pattern) => namespace_matches(pattern, namespace),
+                None => true,
+            })
+            .unwrap()
+    }
+
+    fn get_target(&self, namespace: Option<&str>) -> &PersistenceTarget {
+        &self.targets[self.get_target_index(namespace)].target
+    }
+
+    fn get_restored_target(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<&PersistenceTarget, PersistenceError> {
+        let routed_target = &self.targets[self.get_target_index(namespace)];
+        routed_target.check_restored()?;
+        Ok(&routed_target.target)
+    }
+
+    /// Nothing is queued for a route the snapshot fails to reach: the route is
+    /// reported as failed, and the next snapshot, which replaces this one, is
+    /// written once it is due.
+    async fn save_target_topic_and_queues(
+        routed_target: &RoutedTarget,
+        data: Vec<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<(), String> {
+        routed_target
+            .check_restored()
+            .map_err(|err| format!("{:?}", err))?;
+
+        routed_target
+            .target
+            .write(ReplicationWrite::TopicsAndQueues(data))
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+}
+
+#[async_trait::async_trait]
+impl PersistenceBackend for NamespaceRoutedPersistence {
+    async fn load_page(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        page_id: PageId,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+        compression: PersistenceCompression,
+    ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
+        self.get_target(namespace.as_deref())
+            .backend
+            .load_page(
+                namespace,
+                topic_id,
+                page_id,
+                from_message_id,
+                to_message_id,
+                compression,
+            )
+            .await
+    }
+
+    async fn get_message(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MessageContentGrpcModel>, PersistenceError> {
+        self.get_target(namespace.as_deref())
+            .backend
+            .get_message(namespace, topic_id, message_id)
+            .await
+    }
+
+    async fn get_first_message_id_by_date(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        from: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageId>, PersistenceError> {
+        self.get_target(namespace.as_deref())
+            .backend
+            .get_first_message_id_by_date(namespace, topic_id, from)
+            .await
+    }
+
+    async fn save_messages(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        messages: Vec<MessageProtobufModel>,
+        compression: PersistenceCompression,
+    ) -> Result<(), PersistenceError> {
+        self.get_restored_target(namespace.as_deref())?
+            .write(ReplicationWrite::Messages {
+                namespace,
+                topic_id: topic_id.to_string(),
+                messages,
+                compression,
+            })
+            .await
+    }
+
+    async fn get_persistence_version(&self) -> Option<String> {
+        self.targets
+            .last()
+            .unwrap()
+            .target
+            .backend
+            .get_persistence_version()
+            .await
+    }
+
+    async fn hard_delete_topic(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+    ) -> Result<(), PersistenceError> {
+        self.get_restored_target(namespace.as_deref())?
+            .write(ReplicationWrite::HardDeleteTopic {
+                namespace,
+                topic_id: topic_id.to_string(),
+            })
+            .await
+    }
+
+    /// A persistence may still keep topics of a namespace which is routed
+    /// elsewhere since; those are left out.
+    ///
+    /// A route which can not be read is reported and left out as well, with all
+    /// of its namespaces, so one persistence which is down does not hold the node
+    /// back. It fails only if no route can be read.
+    async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+        let mut result = Vec::new();
+        let mut last_err = None;
+
+        for (index, itm) in self.targets.iter().enumerate() {
+            let topics = match itm.target.backend.get_queue_snapshot().await {
+                Ok(topics) => topics,
+                Err(err) => {
+                    itm.restored.store(false, Ordering::Relaxed);
+
+                    my_logger::LOGGER.write_error(
+                        "NamespaceRoutedPersistence",
+                        format!(
+                            "Topics and queues are not restored: {:?}. Namespaces of the route are skipped and nothing is written to it until the node is restarted",
+                            err
+                        ),
+                        LogEventCtx::new()
+                            .add("target", itm.target.name.as_str())
+                            .add("namespaces", itm.pattern.as_deref().unwrap_or("*")),
+                    );
+
+                    last_err = Some(err);
+                    continue;
+                }
+            };
+
+            itm.restored.store(true, Ordering::Relaxed);
+
+            for topic in topics {
+                if self.get_target_index(Some(topic.namespace.as_str())) == index {
+                    result.push(topic);
+                }
+            }
+        }
+
+        let restored_any = self
+            .targets
+            .iter()
+            .any(|itm| itm.restored.load(Ordering::Relaxed));
+
+        match last_err {
+            Some(err) if !restored_any => Err(err),
+            _ => Ok(result),
+        }
+    }
+
+    /// Every target gets its part, even an empty one: the snapshot replaces the
+    /// previous one, and the topics of that target could all be gone. The routes
+    /// which did not get it come back by name in [`PersistenceError::RoutesFailed`];
+    /// the others have it all the same.
+    async fn save_topic_and_queues(
+        &self,
+        data: Vec<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<(), PersistenceError> {
+        let mut by_target: Vec<Vec<TopicAndQueuesSnapshotGrpcModel>> =
+            self.targets.iter().map(|_| Vec::new()).collect();
+
+        for topic in data {
+            by_target[self.get_target_index(topic.namespace.as_deref())].push(topic);
+        }
+
+        let results = futures_util::future::join_all(
+            self.targets
+                .iter()
+                .zip(by_target)
+                .map(|(itm, data)| Self::save_target_topic_and_queues(itm, data)),
+        )
+        .await;
+
+        let failed: Vec<(String, String)> = self
+            .targets
+            .iter()
+            .zip(results)
+            .filter_map(|(itm, result)| result.err().map(|err| (itm.target.name.clone(), err)))
+            .collect();
+
+        if failed.is_empty() {
+            return Ok(());
+        }
+
+        Err(PersistenceError::RoutesFailed(failed))
+    }
+
+    fn get_route_name(&self, namespace: &str) -> String {
+        self.get_target(Some(namespace)).name.clone()
+    }
+
+    fn is_route_restored(&self, namespace: &str) -> bool {
+        self.get_restored_target(Some(namespace)).is_ok()
+    }
+
+    /// A target which is replicated itself reports each of its services.
+    fn get_targets(&self) -> Vec<PersistenceTargetSnapshot> {
+        let now = DateTimeAsMicroseconds::now();
+
+        let mut result = Vec::new();
+
+        for itm in self.targets.iter() {
+            let targets = itm.target.backend.get_targets();
+
+            if targets.is_empty() {
+                result.push(itm.target.get_snapshot(now));
+            } else {
+                result.extend(targets.into_iter().map(|mut target| {
+                    target.namespaces = itm.target.namespaces.clone();
+                    target
+                }));
+            }
+        }
+
+        result
+    }
+
+    fn get_compression_stats(&self) -> CompressionStats {
+        let mut result = CompressionStats::default();
+
+        for itm in self.targets.iter() {
+            result.append(&itm.target.backend.get_compression_stats());
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use my_service_bus::abstractions::{MessageId, SbMessageHeaders};
+    use my_service_bus::shared::page_id::PageId;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::grpc_client::MessagesPagesMockRepo;
+    use crate::messages_page::MySbMessageContent;
+    use crate::persistence::{PersistenceBackend, PersistenceCompression};
+
+    use super::*;
+
+    #[test]
+    fn test_namespace_patterns() {
+        assert!(namespace_matches("tenant-a", "tenant-a"));
+        assert!(!namespace_matches("tenant-a", "tenant-ab"));
+        assert!(namespace_matches("tenant-*", "tenant-a"));
+        assert!(namespace_matches("tenant-*", "tenant-"));
+        assert!(!namespace_matches("tenant-*", "default"));
+        assert!(namespace_matches("*-eu", "tenant-eu"));
+        assert!(!namespace_matches("a*a", "a"));
+        assert!(namespace_matches("*", "default"));
+    }
+
+    #[tokio::test]
+    async fn test_messages_go_to_the_persistence_of_their_namespace() {
+        let tenants = Arc::new(MessagesPagesMockRepo::new());
+        let default = Arc::new(MessagesPagesMockRepo::new());
+
+        let tenants_backend: Arc<dyn PersistenceBackend> = tenants.clone();
+
+        let persistence = NamespaceRoutedPersistence::new(
+            "default".to_string(),
+            default.clone(),
+            vec![(
+                "tenant-*".to_string(),
+                "tenants".to_string(),
+                tenants_backend,
+            )],
+        );
+
+        let message = MySbMessageContent::new(
+            MessageId::new(1),
+            vec![1, 2, 3],
+            SbMessageHeaders::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        persistence
+            .save_messages(
+                Some("tenant-a".to_string()),
+                "test-topic",
+                vec![(&message).into()],
+                PersistenceCompression::Zstd,
+            )
+            .await
+            .unwrap();
+
+        let load_page = |repo: Arc<MessagesPagesMockRepo>| async move {
+            repo.load_page(
+                None,
+                "test-topic",
+                PageId::new(0),
+                MessageId::new(0),
+                MessageId::new(999),
+                PersistenceCompression::Zstd,
+            )
+            .await
+            .unwrap()
+        };
+
+        assert!(load_page(tenants.clone()).await.is_some());
+        assert_eq!(
+            Some(PersistenceCompression::Zstd),
+            tenants.get_last_save_compression()
+        );
+        assert!(load_page(default.clone()).await.is_none());
+
+        default.set_fail_writes(true);
+
+        assert!(persistence
+            .save_messages(
+                None,
+                "test-topic",
+                vec![(&message).into()],
+                PersistenceCompression::None,
+            )
+            .await
+            .is_err());
+
+        let targets = persistence.get_targets();
+        assert_eq!(2, targets.len());
+        assert_eq!(Some("tenant-*".to_string()), targets[0].namespaces);
+        assert_eq!(0, targets[0].failures);
+        assert_eq!(1, targets[1].failures);
+    }
+
+    #[tokio::test]
+    async fn test_restore_skips_a_route_which_is_down() {
+        let tenants = Arc::new(MessagesPagesMockRepo::new());
+        let default = Arc::new(MessagesPagesMockRepo::new());
+
+        let tenants_backend: Arc<dyn PersistenceBackend> = tenants.clone();
+
+        let persistence = NamespaceRoutedPersistence::new(
+            "default".to_string(),
+            default.clone(),
+            vec![(
+                "tenant-*".to_string(),
+                "tenants".to_string(),
+                tenants_backend,
+            )],
+        );
+
+        tenants.set_fail_reads(true);
+
+        persistence.get_queue_snapshot().await.unwrap();
+
+        tenants.set_fail_reads(false);
+
+        let message = MySbMessageContent::new(
+            MessageId::new(1),
+            vec![1, 2, 3],
+            SbMessageHeaders::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        let result = persistence
+            .save_messages(
+                Some("tenant-a".to_string()),
+                "test-topic",
+                vec![(&message).into()],
+                PersistenceCompression::None,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PersistenceError::RouteIsNotRestored(route)) if route == "tenants"
+        ));
+
+        persistence
+            .save_messages(
+                None,
+                "test-topic",
+                vec![(&message).into()],
+                PersistenceCompression::None,
+            )
+            .await
+            .unwrap();
+
+        default.set_fail_reads(true);
+
+        assert!(persistence.get_queue_snapshot().await.is_err());
+    }
+}
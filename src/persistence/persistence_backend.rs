@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::{page_id::PageId, protobuf_models::MessageProtobufModel};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::grpc_client::PersistenceError;
+use crate::messages_page::MySbMessageContent;
+use crate::persistence_grpc::{MessageContentGrpcModel, TopicAndQueuesSnapshotGrpcModel};
+use crate::topics::TopicSnapshot;
+
+use super::{CompressionStats, PersistenceCompression, PersistenceTargetSnapshot};
+
+/// Where messages and the topics and queues snapshot are kept. `namespace` is
+/// `None` for the default namespace, the way the persistence contract has it.
+/// `compression` is how messages are to travel to and from the persistence; a
+/// backend which keeps them next to the node ignores it.
+#[async_trait::async_trait]
+pub trait PersistenceBackend: Send + Sync {
+    async fn load_page(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        page_id: PageId,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+        compression: PersistenceCompression,
+    ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError>;
+
+    async fn get_message(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MessageContentGrpcModel>, PersistenceError>;
+
+    /// Id of the first persisted message created at `from` or later.
+    async fn get_first_message_id_by_date(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        from: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageId>, PersistenceError>;
+
+    async fn save_messages(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        messages: Vec<MessageProtobufModel>,
+        compression: PersistenceCompression,
+    ) -> Result<(), PersistenceError>;
+
+    async fn get_persistence_version(&self) -> Option<String>;
+
+    async fn hard_delete_topic(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+    ) -> Result<(), PersistenceError>;
+
+    async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, PersistenceError>;
+
+    async fn save_topic_and_queues(
+        &self,
+        data: Vec<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<(), PersistenceError>;
+
+    /// Name of the persistence the namespace is routed to. It is the same one for
+    /// every namespace unless the backend routes them.
+    fn get_route_name(&self, _namespace: &str) -> String {
+        String::new()
+    }
+
+    /// `false` if the topics and queues of the route of the namespace could not
+    /// be read at start. Nothing is written to such a route.
+    fn is_route_restored(&self, _namespace: &str) -> bool {
+        true
+    }
+
+    /// Health of each persistence service the backend writes to. Empty when there
+    /// is a single one: `AppContext::persistence_health` tells all about it.
+    fn get_targets(&self) -> Vec<PersistenceTargetSnapshot> {
+        vec![]
+    }
+
+    fn get_compression_stats(&self) -> CompressionStats {
+        CompressionStats::default()
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use my_logger::LogEventCtx;
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use parking_lot::Mutex;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::grpc_client::PersistenceError;
+use crate::persistence_grpc::TopicAndQueuesSnapshotGrpcModel;
+
+use super::{PersistenceBackend, PersistenceCompression};
+
+/// Writes a target which is behind keeps for later. Next ones are dropped, and the
+/// target is out of sync: it is not read from until it is resynced by hand and the
+/// node is restarted.
+pub(crate) const MAX_QUEUED_WRITES: usize = 10_000;
+
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub enum ReplicationWrite {
+    Messages {
+        namespace: Option<String>,
+        topic_id: String,
+        messages: Vec<MessageProtobufModel>,
+        compression: PersistenceCompression,
+    },
+    HardDeleteTopic {
+        namespace: Option<String>,
+        topic_id: String,
+    },
+    TopicsAndQueues(Vec<TopicAndQueuesSnapshotGrpcModel>),
+}
+
+impl ReplicationWrite {
+    async fn apply(self, backend: &dyn PersistenceBackend) -> Result<(), PersistenceError> {
+        match self {
+            Self::Messages {
+                namespace,
+                topic_id,
+                messages,
+                compression,
+            } => {
+                backend
+                    .save_messages(namespace, topic_id.as_str(), messages, compression)
+                    .await
+            }
+            Self::HardDeleteTopic {
+                namespace,
+                topic_id,
+            } => {
+                backend
+                    .hard_delete_topic(namespace, topic_id.as_str())
+                    .await
+            }
+            Self::TopicsAndQueues(data) => backend.save_topic_and_queues(data).await,
+        }
+    }
+}
+
+struct QueuedWrite {
+    no: u64,
+    queued: DateTimeAsMicroseconds,
+    write: ReplicationWrite,
+}
+
+#[derive(Clone, Debug)]
+pub struct PersistenceTargetSnapshot {
+    pub name: String,
+    pub is_primary: bool,
+    pub namespaces: Option<String>,
+    /// Failed writes in a row. `0` while the target is healthy.
+    pub failures: u32,
+    pub last_error: Option<String>,
+    pub last_success: Option<DateTimeAsMicroseconds>,
+    pub queued_writes: usize,
+    /// Age of the oldest write the target has not got yet.
+    pub lag: Duration,
+    pub dropped_writes: usize,
+}
+
+struct PersistenceTargetInner {
+    queue: VecDeque<QueuedWrite>,
+    next_no: u64,
+    failures: u32,
+    last_error: Option<String>,
+    last_success: Option<DateTimeAsMicroseconds>,
+    dropped_writes: usize,
+}
+
+/// One of the persistence services the node writes to. A write the target did not
+/// get at once is queued, and a background task keeps retrying the queue in order.
+pub struct PersistenceTarget {
+    pub name: String,
+    pub is_primary: bool,
+    /// Pattern of the namespaces routed to the target. `None` if it gets them all.
+    pub namespaces: Option<String>,
+    pub backend: Arc<dyn PersistenceBackend>,
+    inner: Mutex<PersistenceTargetInner>,
+    queue_notify: tokio::sync::Notify,
+}
+
+impl PersistenceTarget {
+    pub fn new(name: String, is_primary: bool, backend: Arc<dyn PersistenceBackend>) -> Self {
+        Self {
+            name,
+            is_primary,
+            namespaces: None,
+            backend,
+            inner: Mutex::new(PersistenceTargetInner {
+                queue: VecDeque::new(),
+                next_no: 0,
+                failures: 0,
+                last_error: None,
+                last_success: None,
+                dropped_writes: 0,
+            }),
+            queue_notify: tokio::sync::Notify::new(),
+        }
+    }
+
+    /// A target which is behind must get the next writes through its queue as
+    /// well, or a newer snapshot could be overwritten by an older queued one.
+    pub fn has_queued_writes(&self) -> bool {
+        !self.inner.lock().queue.is_empty()
+    }
+
+    pub async fn write(&self, write: ReplicationWrite) -> Result<(), PersistenceError> {
+        let result = write.apply(self.backend.as_ref()).await;
+
+        match &result {
+            Ok(()) => self.write_succeeded(),
+            Err(err) => self.write_failed(err),
+        }
+
+        result
+    }
+
+    /// The target has dropped writes, so whatever it answers may miss data.
+    pub fn is_out_of_sync(&self) -> bool {
+        self.inner.lock().dropped_writes > 0
+    }
+
+    pub fn enqueue(&self, write: ReplicationWrite) {
+        {
+            let mut inner = self.inner.lock();
+
+            // Only the latest snapshot of topics and queues matters.
+            if let ReplicationWrite::TopicsAndQueues(_) = &write {
+                inner
+                    .queue
+                    .retain(|itm| !matches!(itm.write, ReplicationWrite::TopicsAndQueues(_)));
+            }
+
+            if inner.queue.len() >= MAX_QUEUED_WRITES {
+                inner.dropped_writes += 1;
+                drop(inner);
+
+                my_logger::LOGGER.write_error(
+                    "PersistenceTarget",
+                    format!(
+                        "Write is dropped: {} writes are already queued. The target is out of sync and is not read from until it is resynced",
+                        MAX_QUEUED_WRITES
+                    ),
+                    LogEventCtx::new().add("target", self.name.as_str()),
+                );
+
+                return;
+            }
+
+            let no = inner.next_no;
+            inner.next_no += 1;
+
+            inner.queue.push_back(QueuedWrite {
+                no,
+                queued: DateTimeAsMicroseconds::now(),
+                write,
+            });
+        }
+
+        self.queue_notify.notify_one();
+    }
+
+    pub fn get_snapshot(&self, now: DateTimeAsMicroseconds) -> PersistenceTargetSnapshot {
+        let inner = self.inner.lock();
+
+        let lag = match inner.queue.front() {
+            Some(oldest) => now.duration_since(oldest.queued).as_positive_or_zero(),
+            None => Duration::ZERO,
+        };
+
+        PersistenceTargetSnapshot {
+            name: self.name.clone(),
+            is_primary: self.is_primary,
+            namespaces: self.namespaces.clone(),
+            failures: inner.failures,
+            last_error: inner.last_error.clone(),
+            last_success: inner.last_success,
+            queued_writes: inner.queue.len(),
+            lag,
+            dropped_writes: inner.dropped_writes,
+        }
+    }
+
+    /// Keeps retrying the queued writes, oldest first, for as long as the node runs.
+    pub fn start_catching_up(target: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                let next = {
+                    let inner = target.inner.lock();
+                    inner.queue.front().map(|itm| (itm.no, itm.write.clone()))
+                };
+
+                let Some((no, write)) = next else {
+                    target.queue_notify.notified().await;
+                    continue;
+                };
+
+                if target.write(write).await.is_ok() {
+                    target.inner.lock().queue.retain(|itm| itm.no != no);
+                } else {
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            }
+        });
+    }
+
+    fn write_succeeded(&self) {
+        let mut inner = self.inner.lock();
+        inner.failures = 0;
+        inner.last_error = None;
+        inner.last_success = Some(DateTimeAsMicroseconds::now());
+    }
+
+    fn write_failed(&self, err: &PersistenceError) {
+        {
+            let mut inner = self.inner.lock();
+            inner.failures += 1;
+            inner.last_error = Some(format!("{:?}", err));
+        }
+
+        my_logger::LOGGER.write_error(
+            "PersistenceTarget",
+            format!("Write to persistence failed: {:?}", err),
+            LogEventCtx::new().add("target", self.name.as_str()),
+        );
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use my_logger::LogEventCtx;
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::{page_id::PageId, protobuf_models::MessageProtobufModel};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::grpc_client::PersistenceError;
+use crate::messages_page::MySbMessageContent;
+use crate::persistence_grpc::{MessageContentGrpcModel, TopicAndQueuesSnapshotGrpcModel};
+use crate::topics::TopicSnapshot;
+
+use super::{
+    CompressionStats, PersistenceBackend, PersistenceCompression, PersistenceTarget,
+    PersistenceTargetSnapshot, ReplicationWrite,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum WritePolicy {
+    /// A write succeeds once every target has it.
+    All,
+    /// A write succeeds once most of the targets have it. The rest get it later.
+    Quorum,
+    /// A write succeeds once the primary has it. The rest get it later.
+    PrimaryWithAsyncSecondary,
+}
+
+/// Writes to several persistence services and reads from the primary one, failing
+/// over to the others. The primary is the first target.
+pub struct ReplicatedPersistence {
+    targets: Vec<Arc<PersistenceTarget>>,
+    write_policy: WritePolicy,
+}
+
+impl ReplicatedPersistence {
+    pub fn new(
+        targets: Vec<(String, Arc<dyn PersistenceBackend>)>,
+        write_policy: WritePolicy,
+    ) -> Self {
+        if targets.is_empty() {
+            panic!("Replicated persistence needs at least one target");
+        }
+
+        let targets: Vec<_> = targets
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, backend))| {
+                Arc::new(PersistenceTarget::new(name, index == 0, backend))
+            })
+            .collect();
+
+        for target in targets.iter() {
+            PersistenceTarget::start_catching_up(target.clone());
+        }
+
+        Self {
+            targets,
+            write_policy,
+        }
+    }
+
+    async fn write(&self, write: ReplicationWrite) -> Result<(), PersistenceError> {
+        match self.write_policy {
+            WritePolicy::All => {
+                let results = futures_util::future::join_all(
+                    self.targets
+                        .iter()
+                        .map(|target| target.write(write.clone())),
+                )
+                .await;
+
+                for result in results {
+                    result?;
+                }
+
+                Ok(())
+            }
+            WritePolicy::Quorum => self.write_to_quorum(write).await,
+            WritePolicy::PrimaryWithAsyncSecondary => {
+                self.targets[0].write(write.clone()).await?;
+
+                for target in self.targets.iter().skip(1) {
+                    target.enqueue(write.clone());
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    async fn write_to_quorum(&self, write: ReplicationWrite) -> Result<(), PersistenceError> {
+        let needed = self.targets.len() / 2 + 1;
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        for target in self.targets.iter() {
+            if target.has_queued_writes() {
+                target.enqueue(write.clone());
+                continue;
+            }
+
+            let target = target.clone();
+            let write = write.clone();
+            let sender = sender.clone();
+
+            // Not awaited past the quorum: the slower targets finish on their own.
+            tokio::spawn(async move {
+                let result = target.write(write.clone()).await;
+
+                if result.is_err() {
+                    target.enqueue(write);
+                }
+
+                let _ = sender.send(result.is_ok());
+            });
+        }
+
+        drop(sender);
+
+        let mut written = 0;
+
+        while let Some(is_ok) = receiver.recv().await {
+            if is_ok {
+                written += 1;
+
+                if written >= needed {
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(PersistenceError::QuorumIsNotReached { written, needed })
+    }
+
+    /// Up-to-date targets first, the primary first among them. A target which is
+    /// out of sync is not read from at all: a page from it could have holes.
+    fn get_read_order(&self) -> Vec<&Arc<PersistenceTarget>> {
+        let (mut result, behind): (Vec<_>, Vec<_>) = self
+            .targets
+            .iter()
+            .filter(|target| !target.is_out_of_sync())
+            .partition(|target| !target.has_queued_writes());
+
+        result.extend(behind);
+        result
+    }
+
+    async fn read<TResult, TFuture>(
+        &self,
+        read: impl Fn(Arc<dyn PersistenceBackend>) -> TFuture,
+    ) -> Result<TResult, PersistenceError>
+    where
+        TFuture: Future<Output = Result<TResult, PersistenceError>>,
+    {
+        let mut last_err = None;
+
+        for target in self.get_read_order() {
+            match read(target.backend.clone()).await {
+                Ok(result) => return Ok(result),
+                Err(err) => {
+                    my_logger::LOGGER.write_error(
+                        "ReplicatedPersistence",
+                        format!("Read failed, trying the next target: {:?}", err),
+                        LogEventCtx::new().add("target", target.name.as_str()),
+                    );
+
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or(PersistenceError::NoTargetInSync))
+    }
+}
+
+#[async_trait::async_trait]
+impl PersistenceBackend for ReplicatedPersistence {
+    async fn load_page(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        page_id: PageId,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+        compression: PersistenceCompression,
+    ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
+        let page_id = page_id.get_value();
+
+        self.read(|backend| {
+            let namespace = namespace.clone();
+            async move {
+                backend
+                    .load_page(
+                        namespace,
+                        topic_id,
+                        PageId::new(page_id),
+                        from_message_id,
+                        to_message_id,
+                        compression,
+                    )
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn get_message(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MessageContentGrpcModel>, PersistenceError> {
+        self.read(|backend| {
+            let namespace = namespace.clone();
+            async move { backend.get_message(namespace, topic_id, message_id).await }
+        })
+        .await
+    }
+
+    async fn get_first_message_id_by_date(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        from: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageId>, PersistenceError> {
+        self.read(|backend| {
+            let namespace = namespace.clone();
+            async move {
+                backend
+                    .get_first_message_id_by_date(namespace, topic_id, from)
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn save_messages(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+        messages: Vec<MessageProtobufModel>,
+        compression: PersistenceCompression,
+    ) -> Result<(), PersistenceError> {
+        self.write(ReplicationWrite::Messages {
+            namespace,
+            topic_id: topic_id.to_string(),
+            messages,
+            compression,
+        })
+        .await
+    }
+
+    async fn get_persistence_version(&self) -> Option<String> {
+        for target in self.get_read_order() {
+            if let Some(version) = target.backend.get_persistence_version().await {
+                return Some(version);
+            }
+        }
+
+        None
+    }
+
+    async fn hard_delete_topic(
+        &self,
+        namespace: Option<String>,
+        topic_id: &str,
+    ) -> Result<(), PersistenceError> {
+        self.write(ReplicationWrite::HardDeleteTopic {
+            namespace,
+            topic_id: topic_id.to_string(),
+        })
+        .await
+    }
+
+    async fn get_queue_snapshot(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+        self.read(|backend| async move { backend.get_queue_snapshot().await })
+            .await
+    }
+
+    async fn save_topic_and_queues(
+        &self,
+        data: Vec<TopicAndQueuesSnapshotGrpcModel>,
+    ) -> Result<(), PersistenceError> {
+        self.write(ReplicationWrite::TopicsAndQueues(data)).await
+    }
+
+    fn get_targets(&self) -> Vec<PersistenceTargetSnapshot> {
+        let now = DateTimeAsMicroseconds::now();
+
+        self.targets
+            .iter()
+            .map(|target| target.get_snapshot(now))
+            .collect()
+    }
+
+    fn get_compression_stats(&self) -> CompressionStats {
+        let mut result = CompressionStats::default();
+
+        for target in self.targets.iter() {
+            result.append(&target.backend.get_compression_stats());
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use my_service_bus::abstractions::{MessageId, SbMessageHeaders};
+    use my_service_bus::shared::{page_id::PageId, protobuf_models::MessageProtobufModel};
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::grpc_client::MessagesPagesMockRepo;
+    use crate::messages_page::MySbMessageContent;
+    use crate::persistence::{PersistenceBackend, PersistenceCompression};
+
+    use super::{ReplicatedPersistence, WritePolicy};
+
+    fn create_targets() -> Vec<Arc<MessagesPagesMockRepo>> {
+        (0..3)
+            .map(|_| Arc::new(MessagesPagesMockRepo::new()))
+            .collect()
+    }
+
+    fn create_persistence(
+        targets: &[Arc<MessagesPagesMockRepo>],
+        write_policy: WritePolicy,
+    ) -> ReplicatedPersistence {
+        let targets = targets
+            .iter()
+            .enumerate()
+            .map(|(index, repo)| {
+                let backend: Arc<dyn PersistenceBackend> = repo.clone();
+                (format!("target-{}", index), backend)
+            })
+            .collect();
+
+        ReplicatedPersistence::new(targets, write_policy)
+    }
+
+    fn create_messages() -> Vec<MessageProtobufModel> {
+        let message = MySbMessageContent::new(
+            MessageId::new(1),
+            vec![1, 2, 3],
+            SbMessageHeaders::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        vec![(&message).into()]
+    }
+
+    #[tokio::test]
+    async fn test_all_fails_if_one_target_fails() {
+        let targets = create_targets();
+        let persistence = create_persistence(&targets, WritePolicy::All);
+
+        targets[2].set_fail_writes(true);
+
+        assert!(persistence
+            .save_messages(
+                None,
+                "test-topic",
+                create_messages(),
+                PersistenceCompression::None,
+            )
+            .await
+            .is_err());
+
+        let snapshot = persistence.get_targets();
+        assert_eq!(0, snapshot[0].failures);
+        assert_eq!(1, snapshot[2].failures);
+        assert_eq!(0, snapshot[2].queued_writes);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_tolerates_a_failed_target_which_catches_up_later() {
+        let targets = create_targets();
+        let persistence = create_persistence(&targets, WritePolicy::Quorum);
+
+        targets[2].set_fail_writes(true);
+
+        persistence
+            .save_messages(
+                None,
+                "test-topic",
+                create_messages(),
+                PersistenceCompression::None,
+            )
+            .await
+            .unwrap();
+
+        // The failed write is queued once the spawned write to it is over.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let snapshot = persistence.get_targets();
+        assert!(snapshot[0].is_primary);
+        assert_eq!(1, snapshot[2].queued_writes);
+
+        targets[2].set_fail_writes(false);
+
+        for _ in 0..30 {
+            if persistence.get_targets()[2].queued_writes == 0 {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        let snapshot = persistence.get_targets();
+        assert_eq!(0, snapshot[2].queued_writes);
+        assert_eq!(0, snapshot[2].failures);
+    }
+
+    #[tokio::test]
+    async fn test_target_which_dropped_writes_is_not_read_from() {
+        let targets = create_targets();
+        let persistence = create_persistence(&targets[..2], WritePolicy::PrimaryWithAsyncSecondary);
+
+        targets[1].set_fail_writes(true);
+
+        for _ in 0..=crate::persistence::MAX_QUEUED_WRITES {
+            persistence.targets[1].enqueue(super::ReplicationWrite::Messages {
+                namespace: None,
+                topic_id: "test-topic".to_string(),
+                messages: create_messages(),
+                compression: PersistenceCompression::None,
+            });
+        }
+
+        assert_eq!(1, persistence.get_targets()[1].dropped_writes);
+
+        // The secondary answers with what it has, but what it has may have holes.
+        targets[0].set_fail_reads(true);
+
+        let result = persistence
+            .load_page(
+                None,
+                "test-topic",
+                PageId::new(0),
+                MessageId::new(0),
+                MessageId::new(10),
+                PersistenceCompression::None,
+            )
+            .await;
+
+        assert!(result.is_err());
+
+        targets[0].set_fail_reads(false);
+
+        assert!(persistence
+            .load_page(
+                None,
+                "test-topic",
+                PageId::new(0),
+                MessageId::new(0),
+                MessageId::new(10),
+                PersistenceCompression::None,
+            )
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_quorum_is_not_reached_if_most_targets_fail() {
+        let targets = create_targets();
+        let persistence = create_persistence(&targets, WritePolicy::Quorum);
+
+        targets[1].set_fail_writes(true);
+        targets[2].set_fail_writes(true);
+
+        assert!(persistence
+            .save_messages(
+                None,
+                "test-topic",
+                create_messages(),
+                PersistenceCompression::None,
+            )
+            .await
+            .is_err());
+    }
+}
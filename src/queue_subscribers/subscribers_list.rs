@@ -10,7 +10,7 @@ use crate::{
     utils::*,
 };
 
-use super::{QueueSubscriber, SubscriberId};
+use super::{queue_subscriber::QueueSubscriberDeliveryState, QueueSubscriber, SubscriberId};
 
 pub struct DeadSubscriber {
     pub subscriber_id: SubscriberId,
@@ -101,6 +101,18 @@ impl SubscribersList {
             .find(|s| s.id.equals_to(subscriber_id))
     }
 
+    /// Rented or on delivery. A subscriber which is gone is not busy either.
+    pub fn is_busy(&self, subscriber_id: SubscriberId) -> bool {
+        match self.get_by_id(subscriber_id) {
+            Some(subscriber) => match subscriber.delivery_state {
+                QueueSubscriberDeliveryState::Idle => false,
+                QueueSubscriberDeliveryState::Rented => true,
+                QueueSubscriberDeliveryState::OnDelivery(_) => true,
+            },
+            None => false,
+        }
+    }
+
     fn has_subscriber_for_session(&self, session_id: SessionId) -> bool {
         self.subscribers
             .iter()
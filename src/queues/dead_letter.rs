@@ -0,0 +1,72 @@
+/// A dead-lettered message is republished as a copy which carries these headers
+/// on top of its own, so whoever drains the DLQ can tell where it came
+/// from and how many times it had already failed.
+pub const DEAD_LETTER_ATTEMPTS_HEADER: &str = "sb-delivery-attempts";
+pub const DEAD_LETTER_QUEUE_HEADER: &str = "sb-original-queue";
+
+pub const DEAD_LETTER_TOPIC_SUFFIX: &str = ".dlq";
+
+pub fn get_dead_letter_topic_id(topic_id: &str) -> String {
+    format!("{}{}", topic_id, DEAD_LETTER_TOPIC_SUFFIX)
+}
+
+#[derive(Debug, Clone)]
+pub enum DeadLetterTarget {
+    /// Another queue of the same topic. A copy with the dead-letter headers is
+    /// stored in the topic and enqueued to this queue only.
+    Queue(String),
+    /// The `<topic>.dlq` topic of the same namespace. The copy is published
+    /// there the regular way, so every queue of that topic gets it.
+    Topic,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeadLetterSettings {
+    /// A message which has failed this many deliveries is not handed out again.
+    pub max_delivery_attempts: i32,
+    pub target: DeadLetterTarget,
+}
+
+impl DeadLetterSettings {
+    pub fn is_exceeded(&self, attempts: i32) -> bool {
+        attempts >= self.max_delivery_attempts
+    }
+
+    /// Human readable target as the status API shows it.
+    pub fn get_target_as_string(&self, topic_id: &str) -> String {
+        match &self.target {
+            DeadLetterTarget::Queue(queue_id) => format!("queue:{}", queue_id),
+            DeadLetterTarget::Topic => format!("topic:{}", get_dead_letter_topic_id(topic_id)),
+        }
+    }
+
+    pub fn get_dead_letter_queue_id(&self) -> Option<&str> {
+        match &self.target {
+            DeadLetterTarget::Queue(queue_id) => Some(queue_id.as_str()),
+            DeadLetterTarget::Topic => None,
+        }
+    }
+
+    /// Snapshot form: no max attempts means dead-lettering is off; a queue id
+    /// picks a sibling queue, none picks the `<topic>.dlq` topic.
+    pub fn restore(
+        max_delivery_attempts: Option<i32>,
+        dead_letter_queue_id: Option<String>,
+    ) -> Option<Self> {
+        let max_delivery_attempts = max_delivery_attempts?;
+
+        if max_delivery_attempts <= 0 {
+            return None;
+        }
+
+        let target = match dead_letter_queue_id {
+            Some(queue_id) if !queue_id.is_empty() => DeadLetterTarget::Queue(queue_id),
+            _ => DeadLetterTarget::Topic,
+        };
+
+        Some(Self {
+            max_delivery_attempts,
+            target,
+        })
+    }
+}
@@ -23,6 +23,8 @@ impl DeliveryAttempts {
         }
     }
 
+    /// Attempt number subscribers get with the message on the wire: 0 until it
+    /// has failed twice, as it always was.
     pub fn get(&self, message_id: MessageId) -> i32 {
         if let Some(result) = self.attempts.get(&message_id) {
             result.attempt
@@ -31,10 +33,24 @@ impl DeliveryAttempts {
         }
     }
 
+    /// Failed deliveries of the message. The dead-letter threshold and the
+    /// redelivery back-off count these.
+    pub fn get_failed(&self, message_id: MessageId) -> i32 {
+        if let Some(result) = self.attempts.get(&message_id) {
+            result.attempt + 1
+        } else {
+            0
+        }
+    }
+
     pub fn reset(&mut self, message_id: MessageId) {
         self.attempts.remove(&message_id);
     }
 
+    pub fn clear(&mut self) {
+        self.attempts = SortedVec::new();
+    }
+
     pub fn add(&mut self, message_id: MessageId) {
         match self.attempts.insert_or_update(&message_id) {
             rust_extensions::sorted_vec::InsertOrUpdateEntry::Insert(entry) => {
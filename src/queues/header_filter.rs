@@ -0,0 +1,87 @@
+use my_service_bus::abstractions::SbMessageHeaders;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::get_header;
+
+/// Condition over message headers. Travels as JSON, e.g.
+/// `{"op":"and","items":[{"op":"eq","header":"type","value":"order"},{"op":"prefix","header":"region","value":"eu-"}]}`.
+///
+/// A condition over a header the message does not have is false.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub enum HeaderFilter {
+    #[serde(rename = "eq")]
+    Equals { header: String, value: String },
+    #[serde(rename = "in")]
+    In { header: String, values: Vec<String> },
+    #[serde(rename = "prefix")]
+    Prefix { header: String, value: String },
+    #[serde(rename = "and")]
+    And { items: Vec<HeaderFilter> },
+    #[serde(rename = "or")]
+    Or { items: Vec<HeaderFilter> },
+}
+
+impl HeaderFilter {
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        serde_json::from_str(src).map_err(|err| format!("Invalid header filter. {}", err))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn matches(&self, headers: &SbMessageHeaders) -> bool {
+        match self {
+            HeaderFilter::Equals { header, value } => {
+                get_header(headers, header).is_some_and(|itm| itm == value)
+            }
+            HeaderFilter::In { header, values } => get_header(headers, header)
+                .is_some_and(|itm| values.iter().any(|value| value == itm)),
+            HeaderFilter::Prefix { header, value } => {
+                get_header(headers, header).is_some_and(|itm| itm.starts_with(value.as_str()))
+            }
+            HeaderFilter::And { items } => items.iter().all(|itm| itm.matches(headers)),
+            HeaderFilter::Or { items } => items.iter().any(|itm| itm.matches(headers)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::SbMessageHeaders;
+
+    use super::HeaderFilter;
+
+    #[test]
+    fn test_and_or_conditions() {
+        let filter = HeaderFilter::from_json(
+            r#"{"op":"and","items":[
+                {"op":"eq","header":"type","value":"order"},
+                {"op":"or","items":[
+                    {"op":"prefix","header":"region","value":"eu-"},
+                    {"op":"in","header":"region","values":["us-east","us-west"]}
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let headers = SbMessageHeaders::new()
+            .add("type".to_string(), "order".to_string())
+            .add("region".to_string(), "eu-central".to_string());
+        assert!(filter.matches(&headers));
+
+        let headers = SbMessageHeaders::new()
+            .add("type".to_string(), "order".to_string())
+            .add("region".to_string(), "us-west".to_string());
+        assert!(filter.matches(&headers));
+
+        let headers = SbMessageHeaders::new()
+            .add("type".to_string(), "order".to_string())
+            .add("region".to_string(), "ap-south".to_string());
+        assert!(!filter.matches(&headers));
+
+        let headers = SbMessageHeaders::new().add("region".to_string(), "eu-central".to_string());
+        assert!(!filter.matches(&headers));
+    }
+}
@@ -10,3 +10,11 @@ pub use queues_list::TopicQueuesList;
 pub use delivery_bucket::DeliveryBucket;
 mod queue_id;
 pub use queue_id::*;
+mod dead_letter;
+pub use dead_letter::*;
+mod redelivery_policy;
+pub use redelivery_policy::*;
+mod ordering_keys;
+pub use ordering_keys::*;
+mod header_filter;
+pub use header_filter::*;
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use my_service_bus::abstractions::SbMessageHeaders;
+
+use crate::queue_subscribers::{SubscriberId, SubscribersList};
+
+/// How many messages whose key is busy a subscriber looks past for one it may
+/// take. Messages further on wait for the next round: without a bound, every
+/// round would go through the whole queue while a key is on delivery.
+pub const MAX_MESSAGES_WAITING_FOR_KEY: usize = 1_000;
+
+/// Ordering key mode of a queue: messages with the same value of `header` never
+/// are on delivery to two subscribers at the same time.
+///
+/// A key is pinned to the subscriber which got the last message with it. The pin
+/// is only honoured while that subscriber is busy (rented or on delivery); once it
+/// confirms, fails or leaves, the next message with the key may go to anybody.
+pub struct OrderingKeys {
+    pub header: String,
+    pins: HashMap<String, SubscriberId>,
+}
+
+impl OrderingKeys {
+    pub fn new(header: String) -> Self {
+        Self {
+            header,
+            pins: HashMap::new(),
+        }
+    }
+
+    pub fn get_key<'s>(&self, headers: &'s SbMessageHeaders) -> Option<&'s str> {
+        crate::utils::get_header(headers, self.header.as_str())
+    }
+
+    /// Returns false if the key is pinned to another busy subscriber and the
+    /// message has to wait in the queue.
+    pub fn try_pin(
+        &mut self,
+        key: &str,
+        subscriber_id: SubscriberId,
+        subscribers: &SubscribersList,
+    ) -> bool {
+        if let Some(pinned_to) = self.pins.get(key) {
+            if pinned_to.equals_to(subscriber_id) {
+                return true;
+            }
+
+            if subscribers.is_busy(*pinned_to) {
+                return false;
+            }
+        }
+
+        self.pins.insert(key.to_string(), subscriber_id);
+        true
+    }
+
+    /// Drops the pins of subscribers which are idle or gone, so their keys can be
+    /// spread over whoever is subscribed now.
+    pub fn rebalance(&mut self, subscribers: &SubscribersList) {
+        self.pins
+            .retain(|_, subscriber_id| subscribers.is_busy(*subscriber_id));
+    }
+
+    pub fn get_pinned_amount(&self) -> usize {
+        self.pins.len()
+    }
+}
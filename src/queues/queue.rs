@@ -3,14 +3,18 @@ use my_service_bus::abstractions::{
     subscriber::TopicQueueType,
     MessageId,
 };
-use rust_extensions::sorted_vec::EntityWithStrKey;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, sorted_vec::EntityWithStrKey};
+use std::time::Duration;
 
 use crate::{
     queue_subscribers::{SubscriberId, SubscribersList},
-    topics::TopicId,
+    topics::{ScheduledMessages, TopicId},
 };
 
-use super::{delivery_attempts::DeliveryAttempts, QueueId};
+use super::{
+    delivery_attempts::DeliveryAttempts, DeadLetterSettings, HeaderFilter, OrderingKeys, QueueId,
+    RedeliveryPolicy,
+};
 
 pub struct TopicQueue {
     pub topic_id: TopicId,
@@ -22,6 +26,34 @@ pub struct TopicQueue {
 
     pub debug: bool,
     pub max_messages_per_payload: Option<usize>,
+
+    /// `None` keeps the old behaviour: a failing message is redelivered forever.
+    pub dead_letter: Option<DeadLetterSettings>,
+    /// How many messages this queue has moved to its dead-letter target since start.
+    pub dead_lettered: usize,
+    /// How many messages this queue has dropped because of the topic TTL since start.
+    pub expired: usize,
+
+    /// `None` keeps the old behaviour: a failed message is handed out again right away.
+    pub redelivery: Option<RedeliveryPolicy>,
+    /// Failed messages waiting for their redelivery delay. They are out of `queue`,
+    /// so newer messages keep flowing meanwhile.
+    pub delayed: ScheduledMessages,
+
+    /// `None` - any idle subscriber gets any message.
+    pub ordering_keys: Option<OrderingKeys>,
+
+    /// `None` - every message is delivered. Messages which do not match are
+    /// confirmed on the server side without being sent to a subscriber.
+    pub header_filter: Option<HeaderFilter>,
+    /// How many messages this queue has confirmed because of the header filter since start.
+    pub filtered_out: usize,
+
+    /// Paused queue keeps getting messages but delivers nothing until it is resumed.
+    pub paused: bool,
+
+    /// `None` - the global delivery timeout of the node is used.
+    pub delivery_timeout: Option<Duration>,
 }
 
 impl EntityWithStrKey for TopicQueue {
@@ -41,6 +73,16 @@ impl TopicQueue {
             queue_type,
             debug: std::env::var("DEBUG").is_ok(),
             max_messages_per_payload: None,
+            dead_letter: None,
+            dead_lettered: 0,
+            expired: 0,
+            redelivery: None,
+            delayed: ScheduledMessages::new(),
+            ordering_keys: None,
+            header_filter: None,
+            filtered_out: 0,
+            paused: false,
+            delivery_timeout: None,
         }
     }
 
@@ -59,6 +101,16 @@ impl TopicQueue {
             queue_type,
             debug: std::env::var("DEBUG").is_ok(),
             max_messages_per_payload: None,
+            dead_letter: None,
+            dead_lettered: 0,
+            expired: 0,
+            redelivery: None,
+            delayed: ScheduledMessages::new(),
+            ordering_keys: None,
+            header_filter: None,
+            filtered_out: 0,
+            paused: false,
+            delivery_timeout: None,
         }
     }
 
@@ -114,6 +166,14 @@ impl TopicQueue {
 
     pub fn one_second_tick(&mut self) {
         self.subscribers.one_second_tick();
+        self.rebalance_ordering_keys();
+    }
+
+    /// Has to run when a subscriber joins or leaves.
+    pub fn rebalance_ordering_keys(&mut self) {
+        if let Some(ordering_keys) = self.ordering_keys.as_mut() {
+            ordering_keys.rebalance(&self.subscribers);
+        }
     }
 
     pub fn set_message_id(&mut self, message_id: MessageId, max_message_id: MessageId) {
@@ -127,6 +187,43 @@ impl TopicQueue {
         self.queue.reset(intervals);
     }
 
+    /// Drops every pending message except the newest `keep_last`, including the ones
+    /// waiting for their redelivery delay. Messages which are on delivery right now
+    /// are not touched. Returns how many messages were dropped.
+    pub fn purge(&mut self, keep_last: usize) -> usize {
+        let size_before = self.queue.queue_size() + self.delayed.len();
+
+        let mut to_keep = Vec::new();
+        let mut left = keep_last as i64;
+
+        for range in self.queue.get_snapshot().into_iter().rev() {
+            if left == 0 {
+                break;
+            }
+
+            let range_size = range.to_id - range.from_id + 1;
+
+            if range_size <= left {
+                left -= range_size;
+                to_keep.push(range);
+            } else {
+                to_keep.push(QueueIndexRange {
+                    from_id: range.to_id - left + 1,
+                    to_id: range.to_id,
+                });
+                left = 0;
+            }
+        }
+
+        to_keep.reverse();
+
+        self.queue.reset(to_keep);
+        self.delayed = ScheduledMessages::new();
+        self.delivery_attempts.clear();
+
+        size_before - self.queue.queue_size()
+    }
+
     pub fn confirm_delivered(&mut self, delivered_ids: &QueueWithIntervals) {
         for msg_id in delivered_ids {
             self.delivery_attempts.reset(msg_id.into());
@@ -148,7 +245,33 @@ impl TopicQueue {
             self.delivery_attempts.add(msg_id.into());
         }
 
-        self.queue.merge(ids.clone());
+        match self.redelivery.clone() {
+            Some(redelivery) => {
+                let now = DateTimeAsMicroseconds::now();
+
+                let mut retry_now = QueueWithIntervals::new();
+
+                for msg_id in ids.iter() {
+                    let message_id: MessageId = msg_id.into();
+
+                    // Over the dead-letter threshold: no point to wait, the next
+                    // delivery round moves it to the dead-letter target.
+                    if self.get_dead_letter_attempts(message_id).is_some() {
+                        retry_now.enqueue(msg_id);
+                        continue;
+                    }
+
+                    let attempt = self.delivery_attempts.get_failed(message_id);
+                    self.delayed
+                        .add(now.add(redelivery.get_delay(attempt)), message_id);
+                }
+
+                self.queue.merge(retry_now);
+            }
+            None => {
+                self.queue.merge(ids.clone());
+            }
+        }
 
         if self.debug {
             println!(
@@ -159,6 +282,61 @@ impl TopicQueue {
         }
     }
 
+    /// Returns true if some delayed messages are due and are back in the queue.
+    pub fn enqueue_due_redeliveries(&mut self, now: DateTimeAsMicroseconds) -> bool {
+        let Some(ids) = self.delayed.get_due(now) else {
+            return false;
+        };
+
+        self.queue.merge(ids);
+
+        true
+    }
+
+    /// Messages to put into the persistence snapshot: the ones waiting for the
+    /// redelivery delay are still owed to the queue.
+    pub fn get_queue_to_persist(&self) -> QueueWithIntervals {
+        let mut result = self.queue.clone();
+
+        for message_id in self.delayed.iter_message_ids() {
+            result.enqueue(message_id);
+        }
+
+        result
+    }
+
+    /// Everything the queue has not confirmed yet: the queue itself, messages waiting
+    /// for their redelivery delay and messages which are on delivery right now.
+    pub fn get_position(&self) -> QueueWithIntervals {
+        let mut result = self.get_queue_to_persist();
+
+        if let Some(subscribers) = self.subscribers.get_all() {
+            for subscriber in subscribers {
+                if let Some(on_delivery) = subscriber.get_messages_on_delivery() {
+                    for message_id in &on_delivery {
+                        result.enqueue(message_id);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Failed-delivery count of the message, when it is over the dead-letter
+    /// threshold and must not be handed out again.
+    pub fn get_dead_letter_attempts(&self, message_id: MessageId) -> Option<i32> {
+        let dead_letter = self.dead_letter.as_ref()?;
+
+        let attempts = self.delivery_attempts.get_failed(message_id);
+
+        if dead_letter.is_exceeded(attempts) {
+            return Some(attempts);
+        }
+
+        None
+    }
+
     pub fn get_messages_on_delivery(
         &self,
         subscriber_id: SubscriberId,
@@ -176,3 +354,62 @@ impl TopicQueue {
         !self.queue_type.is_auto_delete()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::subscriber::TopicQueueType;
+
+    use super::TopicQueue;
+    use crate::queues::DeadLetterSettings;
+
+    #[test]
+    fn test_purge_keeps_newest_messages() {
+        let mut queue = TopicQueue::new(
+            "test-topic".into(),
+            "test-queue".into(),
+            TopicQueueType::Permanent,
+        );
+
+        for message_id in [1, 2, 3, 7, 8] {
+            queue.queue.enqueue(message_id);
+        }
+
+        queue.delivery_attempts.add(1.into());
+
+        let dropped = queue.purge(3);
+
+        assert_eq!(dropped, 2);
+        assert_eq!(queue.get_queue_size(), 3);
+        assert_eq!(queue.get_min_msg_id().unwrap().get_value(), 3);
+        assert_eq!(queue.delivery_attempts.get(1.into()), 0);
+
+        let dropped = queue.purge(0);
+
+        assert_eq!(dropped, 3);
+        assert_eq!(queue.get_queue_size(), 0);
+    }
+
+    #[test]
+    fn test_first_failure_reaches_dead_letter_threshold_of_one() {
+        let mut queue = TopicQueue::new(
+            "test-topic".into(),
+            "test-queue".into(),
+            TopicQueueType::Permanent,
+        );
+
+        queue.dead_letter = DeadLetterSettings::restore(Some(1), None);
+
+        assert_eq!(queue.get_dead_letter_attempts(1.into()), None);
+
+        queue.delivery_attempts.add(1.into());
+
+        // Subscribers still see the attempt number they always did.
+        assert_eq!(queue.delivery_attempts.get(1.into()), 0);
+        assert_eq!(queue.get_dead_letter_attempts(1.into()), Some(1));
+
+        queue.delivery_attempts.add(1.into());
+
+        assert_eq!(queue.delivery_attempts.get(1.into()), 1);
+        assert_eq!(queue.get_dead_letter_attempts(1.into()), Some(2));
+    }
+}
@@ -59,14 +59,14 @@ impl TopicQueuesList {
         queue_id: QueueId,
         queue_type: TopicQueueType,
         queue: QueueWithIntervals,
-    ) -> &TopicQueue {
+    ) -> &mut TopicQueue {
         let topic_queue = TopicQueue::restore(topic_id, queue_id, queue_type, queue);
 
         let (index, _) = self.queues.insert_or_replace(topic_queue);
 
         self.snapshot_id += 1;
 
-        self.queues.get_by_index(index).unwrap()
+        self.queues.get_by_index_mut(index).unwrap()
     }
 
     pub fn remove(&mut self, queue_id: &str) -> Option<TopicQueue> {
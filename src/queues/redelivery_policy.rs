@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedeliveryBackOff {
+    Fixed,
+    Exponential,
+}
+
+impl RedeliveryBackOff {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RedeliveryBackOff::Fixed => "fixed",
+            RedeliveryBackOff::Exponential => "exponential",
+        }
+    }
+
+    pub fn from_str(src: &str) -> Option<Self> {
+        match src.trim().to_lowercase().as_str() {
+            "fixed" => Some(RedeliveryBackOff::Fixed),
+            "exponential" => Some(RedeliveryBackOff::Exponential),
+            _ => None,
+        }
+    }
+
+    pub fn into_u8(&self) -> u8 {
+        match self {
+            RedeliveryBackOff::Fixed => 0,
+            RedeliveryBackOff::Exponential => 1,
+        }
+    }
+
+    pub fn from_u8(src: u8) -> Self {
+        match src {
+            1 => RedeliveryBackOff::Exponential,
+            _ => RedeliveryBackOff::Fixed,
+        }
+    }
+}
+
+/// How long a negatively confirmed message waits before the queue hands it out again.
+#[derive(Debug, Clone)]
+pub struct RedeliveryPolicy {
+    pub back_off: RedeliveryBackOff,
+    pub delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RedeliveryPolicy {
+    /// `attempt` is the failed deliveries count, so the first retry waits `delay`.
+    pub fn get_delay(&self, attempt: i32) -> Duration {
+        let delay = match self.back_off {
+            RedeliveryBackOff::Fixed => self.delay,
+            RedeliveryBackOff::Exponential => {
+                let power = (attempt.max(1) - 1).min(31) as u32;
+                self.delay.saturating_mul(2u32.saturating_pow(power))
+            }
+        };
+
+        delay.min(self.max_delay)
+    }
+
+    /// Snapshot form: no delay means the old behaviour, retry right away. A cap
+    /// which is not set, or is below the delay, is the delay itself.
+    pub fn restore(
+        back_off: Option<i32>,
+        delay_ms: Option<i64>,
+        max_delay_ms: Option<i64>,
+    ) -> Option<Self> {
+        let delay_ms = delay_ms?;
+
+        if delay_ms <= 0 {
+            return None;
+        }
+
+        let delay = Duration::from_millis(delay_ms as u64);
+
+        let max_delay = match max_delay_ms {
+            Some(max_delay_ms) if max_delay_ms > delay_ms => {
+                Duration::from_millis(max_delay_ms as u64)
+            }
+            _ => delay,
+        };
+
+        Some(Self {
+            back_off: RedeliveryBackOff::from_u8(back_off.unwrap_or(0) as u8),
+            delay,
+            max_delay,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_exponential_delay_is_capped() {
+        let policy = RedeliveryPolicy {
+            back_off: RedeliveryBackOff::Exponential,
+            delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+        };
+
+        assert_eq!(policy.get_delay(1), Duration::from_secs(1));
+        assert_eq!(policy.get_delay(2), Duration::from_secs(2));
+        assert_eq!(policy.get_delay(3), Duration::from_secs(4));
+        assert_eq!(policy.get_delay(4), Duration::from_secs(5));
+        assert_eq!(policy.get_delay(100), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,4 @@
+mod quotas;
+pub use quotas::*;
+mod quota_usage;
+pub use quota_usage::*;
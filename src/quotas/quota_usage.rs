@@ -0,0 +1,59 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::QuotaKind;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QuotaUsage {
+    pub memory_size: usize,
+    pub persist_queue_size: usize,
+    pub queue_size: usize,
+}
+
+impl QuotaUsage {
+    pub fn get(&self, kind: QuotaKind) -> usize {
+        match kind {
+            QuotaKind::MemorySize => self.memory_size,
+            QuotaKind::PersistQueueSize => self.persist_queue_size,
+            QuotaKind::QueueSize => self.queue_size,
+        }
+    }
+
+    pub fn append(&mut self, other: &QuotaUsage) {
+        self.memory_size += other.memory_size;
+        self.persist_queue_size += other.persist_queue_size;
+        self.queue_size += other.queue_size;
+    }
+}
+
+/// Usage of a namespace as the metrics timer counted it last second. Summing it
+/// up on every publish would mean locking every topic of the namespace.
+pub struct SharedQuotaUsage {
+    memory_size: AtomicUsize,
+    persist_queue_size: AtomicUsize,
+    queue_size: AtomicUsize,
+}
+
+impl SharedQuotaUsage {
+    pub fn new() -> Self {
+        Self {
+            memory_size: AtomicUsize::new(0),
+            persist_queue_size: AtomicUsize::new(0),
+            queue_size: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get(&self) -> QuotaUsage {
+        QuotaUsage {
+            memory_size: self.memory_size.load(Ordering::Relaxed),
+            persist_queue_size: self.persist_queue_size.load(Ordering::Relaxed),
+            queue_size: self.queue_size.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn update(&self, usage: &QuotaUsage) {
+        self.memory_size.store(usage.memory_size, Ordering::Relaxed);
+        self.persist_queue_size
+            .store(usage.persist_queue_size, Ordering::Relaxed);
+        self.queue_size.store(usage.queue_size, Ordering::Relaxed);
+    }
+}
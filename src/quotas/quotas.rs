@@ -0,0 +1,131 @@
+use super::QuotaUsage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaKind {
+    /// Bytes of message content kept in memory.
+    MemorySize,
+    /// Messages waiting to be written to the persistence.
+    PersistQueueSize,
+    /// Messages waiting for delivery, summed over the queues.
+    QueueSize,
+}
+
+impl QuotaKind {
+    pub const ALL: [QuotaKind; 3] = [
+        QuotaKind::MemorySize,
+        QuotaKind::PersistQueueSize,
+        QuotaKind::QueueSize,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaKind::MemorySize => "memory_size",
+            QuotaKind::PersistQueueSize => "persist_queue_size",
+            QuotaKind::QueueSize => "queue_size",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct QuotaExceeded {
+    /// The topic or the namespace which is over its quota.
+    pub scope: String,
+    pub kind: QuotaKind,
+    pub limit: usize,
+    pub used: usize,
+}
+
+/// Limits of a topic or of a whole namespace. A publish is refused while any of
+/// them is reached; the batch which crosses the line is still taken.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quotas {
+    pub max_memory_size: Option<usize>,
+    pub max_persist_queue_size: Option<usize>,
+    pub max_queue_size: Option<usize>,
+}
+
+impl Quotas {
+    pub fn is_empty(&self) -> bool {
+        self.max_memory_size.is_none()
+            && self.max_persist_queue_size.is_none()
+            && self.max_queue_size.is_none()
+    }
+
+    pub fn get_limit(&self, kind: QuotaKind) -> Option<usize> {
+        match kind {
+            QuotaKind::MemorySize => self.max_memory_size,
+            QuotaKind::PersistQueueSize => self.max_persist_queue_size,
+            QuotaKind::QueueSize => self.max_queue_size,
+        }
+    }
+
+    pub fn check(&self, usage: &QuotaUsage) -> Result<(), QuotaExceeded> {
+        for kind in QuotaKind::ALL {
+            let Some(limit) = self.get_limit(kind) else {
+                continue;
+            };
+
+            let used = usage.get(kind);
+
+            if used >= limit {
+                return Err(QuotaExceeded {
+                    scope: String::new(),
+                    kind,
+                    limit,
+                    used,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Percent of each limit which is used. Only the limits which are set.
+    pub fn get_utilisation(&self, usage: &QuotaUsage) -> Vec<(QuotaKind, i64)> {
+        QuotaKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let limit = self.get_limit(kind)?;
+                let percent = if limit == 0 {
+                    100
+                } else {
+                    usage.get(kind) * 100 / limit
+                };
+                Some((kind, percent as i64))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_is_exceeded_once_limit_is_reached() {
+        let quotas = Quotas {
+            max_memory_size: Some(1000),
+            max_persist_queue_size: None,
+            max_queue_size: Some(10),
+        };
+
+        let mut usage = QuotaUsage {
+            memory_size: 999,
+            persist_queue_size: 1_000_000,
+            queue_size: 5,
+        };
+
+        assert!(quotas.check(&usage).is_ok());
+
+        usage.queue_size = 10;
+
+        let err = quotas.check(&usage).unwrap_err();
+        assert_eq!(QuotaKind::QueueSize, err.kind);
+
+        let utilisation = quotas.get_utilisation(&usage);
+        assert_eq!(
+            vec![(QuotaKind::MemorySize, 99), (QuotaKind::QueueSize, 100)],
+            utilisation
+        );
+    }
+}
@@ -1,15 +1,39 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
-use my_grpc_extensions::*;
 use serde::{Deserialize, Serialize};
 use tokio::{fs::File, io::AsyncReadExt};
 
+use crate::{
+    app::{PublishRateLimit, ThrottlePolicy},
+    persistence::{PersistenceCompression, WritePolicy},
+    quotas::Quotas,
+};
+
 #[cfg(test)]
 const TEST_GRPC_URL: &str = "test";
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SettingsModelYaml {
-    pub persistence_grpc_url: String,
+    pub persistence_grpc_url: Option<String>,
+
+    /// Several persistence services, the primary one first. Instead of `persistence_grpc_url`.
+    pub persistence_grpc_urls: Option<Vec<String>>,
+
+    /// `all` (default), `quorum` or `primary`.
+    pub persistence_write_policy: Option<String>,
+
+    /// `grpc` (default) or `file`.
+    pub persistence: Option<String>,
+
+    pub persistence_path: Option<String>,
+
+    pub persistence_routes: Option<Vec<PersistenceRouteSettingsYaml>>,
+
+    /// `none` (default), `deflate` or `zstd`.
+    pub persist_compression: Option<String>,
+
+    /// Legacy switch: `true` is `deflate`. `persist_compression` wins if both are set.
+    pub persist_compressed: Option<bool>,
 
     pub queue_gc_timeout: String,
 
@@ -24,10 +48,71 @@ pub struct SettingsModelYaml {
     pub auto_create_topic_on_subscribe: Option<bool>,
 
     pub listen_unix_socket: Option<String>,
+
+    pub namespace_quotas: Option<HashMap<String, QuotasSettingsYaml>>,
+
+    pub publish_rate_limits: Option<Vec<PublishRateLimitSettingsYaml>>,
+
+    pub persist_ack_timeout: Option<String>,
+
+    pub wal_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublishRateLimitSettingsYaml {
+    /// `*` - every session has a limit of its own.
+    pub session_name: Option<String>,
+    /// `*` - every topic has a limit of its own.
+    pub topic_id: Option<String>,
+    pub messages_per_second: Option<usize>,
+    pub bytes_per_second: Option<usize>,
+    /// `delay` (default) or `reject`.
+    pub policy: Option<String>,
+    /// A delayed publish which would wait longer than this is rejected. Default 1s.
+    pub max_delay: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PersistenceRouteSettingsYaml {
+    /// Namespace name, or a pattern with one `*`: `tenant-*`.
+    pub namespace: String,
+    pub grpc_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QuotasSettingsYaml {
+    pub max_memory_size: Option<usize>,
+    pub max_persist_queue_size: Option<usize>,
+    pub max_queue_size: Option<usize>,
+}
+
+pub enum PersistenceSettings {
+    /// The my-service-bus-persistence processes, the primary one first. Writes
+    /// go to all of them as `write_policy` says.
+    Grpc {
+        urls: Vec<String>,
+        write_policy: WritePolicy,
+    },
+    /// Embedded store in a local folder: no separate process is needed.
+    File { path: String },
+}
+
+#[derive(Debug)]
+pub struct PersistenceRoute {
+    pub namespace: String,
+    pub grpc_url: String,
 }
 
 pub struct SettingsModel {
-    pub persistence_grpc_url: String,
+    pub persistence: PersistenceSettings,
+
+    /// Namespaces kept in a persistence of their own. The first route which
+    /// matches wins; the rest go to `persistence`.
+    pub persistence_routes: Vec<PersistenceRoute>,
+
+    /// How messages travel to and from the persistence service, unless the topic
+    /// has a compression of its own.
+    pub persist_compression: PersistenceCompression,
     pub queue_gc_timeout: Duration,
 
     pub max_delivery_size: usize,
@@ -38,13 +123,18 @@ pub struct SettingsModel {
     pub auto_create_topic_on_subscribe: bool,
 
     pub listen_unix_socket: Option<String>,
-}
 
-#[async_trait::async_trait]
-impl GrpcClientSettings for SettingsModel {
-    async fn get_grpc_url(&self, _name: &'static str) -> GrpcUrl {
-        self.persistence_grpc_url.clone().into()
-    }
+    /// By namespace name. A namespace which is not here has no quotas.
+    pub namespace_quotas: HashMap<String, Quotas>,
+
+    pub publish_rate_limits: Vec<PublishRateLimit>,
+
+    /// How long a publish with `persist_immediately` waits for its messages to be saved.
+    pub persist_ack_timeout: Duration,
+
+    /// Folder of the write-ahead log of the messages waiting to be persisted. The
+    /// log is off while it is not set.
+    pub wal_path: Option<String>,
 }
 
 impl SettingsModel {
@@ -79,16 +169,29 @@ impl SettingsModel {
         result.into()
     }
 
+    pub fn get_namespace_quotas(&self, namespace: &str) -> Option<&Quotas> {
+        self.namespace_quotas.get(namespace)
+    }
+
     #[cfg(test)]
     pub fn create_test_settings(max_delivery_size: usize) -> Self {
         Self {
-            persistence_grpc_url: TEST_GRPC_URL.to_string(),
+            persistence: PersistenceSettings::Grpc {
+                urls: vec![TEST_GRPC_URL.to_string()],
+                write_policy: WritePolicy::All,
+            },
+            persistence_routes: Vec::new(),
+            persist_compression: PersistenceCompression::None,
             queue_gc_timeout: Duration::from_secs(1),
             max_delivery_size,
             delivery_timeout: None,
             auto_create_topic_on_publish: true,
             auto_create_topic_on_subscribe: true,
             listen_unix_socket: None,
+            namespace_quotas: HashMap::new(),
+            publish_rate_limits: Vec::new(),
+            persist_ack_timeout: Duration::from_secs(5),
+            wal_path: None,
         }
     }
 }
@@ -162,24 +265,213 @@ impl Into<SettingsModel> for SettingsModelYaml {
             false
         };
 
+        let mut namespace_quotas = HashMap::new();
+
+        for (namespace, quotas) in self.namespace_quotas.unwrap_or_default() {
+            let quotas = Quotas {
+                max_memory_size: quotas.max_memory_size,
+                max_persist_queue_size: quotas.max_persist_queue_size,
+                max_queue_size: quotas.max_queue_size,
+            };
+
+            println!("Namespace '{}' quotas: {:?}", namespace, quotas);
+
+            namespace_quotas.insert(namespace, quotas);
+        }
+
+        let mut publish_rate_limits = Vec::new();
+
+        for limit in self.publish_rate_limits.unwrap_or_default() {
+            let policy = match limit.policy.as_deref() {
+                None | Some("delay") => {
+                    let max_delay = match limit.max_delay {
+                        Some(src) => rust_extensions::duration_utils::parse_duration(src.as_str())
+                            .unwrap_or_else(|err| {
+                                panic!(
+                                    "Can not parse max delay of publish rate limit '{}'. Reason: {:?}",
+                                    src, err
+                                )
+                            }),
+                        None => Duration::from_secs(1),
+                    };
+
+                    ThrottlePolicy::Delay { max_delay }
+                }
+                Some("reject") => ThrottlePolicy::Reject,
+                Some(other) => panic!(
+                    "Invalid publish rate limit policy '{}'. Please use delay or reject",
+                    other
+                ),
+            };
+
+            let limit = PublishRateLimit {
+                session_name: limit.session_name,
+                topic_id: limit.topic_id,
+                messages_per_second: check_rate_limit(
+                    "MessagesPerSecond",
+                    limit.messages_per_second,
+                ),
+                bytes_per_second: check_rate_limit("BytesPerSecond", limit.bytes_per_second),
+                policy,
+            };
+
+            println!("Publish rate limit: {:?}", limit);
+
+            publish_rate_limits.push(limit);
+        }
+
+        let persistence = match self.persistence.as_deref() {
+            None | Some("grpc") => {
+                let urls = match (self.persistence_grpc_url, self.persistence_grpc_urls) {
+                    (Some(url), None) => vec![url],
+                    (None, Some(urls)) if urls.len() > 0 => urls,
+                    (Some(_), Some(_)) => panic!(
+                        "Please specify either PersistenceGrpcUrl or PersistenceGrpcUrls, not both"
+                    ),
+                    _ => panic!("Persistence is grpc, but PersistenceGrpcUrl is not specified"),
+                };
+
+                let write_policy = match self.persistence_write_policy.as_deref() {
+                    None | Some("all") => WritePolicy::All,
+                    Some("quorum") => WritePolicy::Quorum,
+                    Some("primary") => WritePolicy::PrimaryWithAsyncSecondary,
+                    Some(other) => panic!(
+                        "Invalid persistence write policy '{}'. Please use all, quorum or primary",
+                        other
+                    ),
+                };
+
+                if urls.len() == 1 {
+                    println!("Persistence is grpc at {}", urls[0]);
+                } else {
+                    println!(
+                        "Persistence is grpc at {:?}, write policy is {:?}",
+                        urls, write_policy
+                    );
+                }
+
+                PersistenceSettings::Grpc { urls, write_policy }
+            }
+            Some("file") => {
+                let path = self.persistence_path.clone().unwrap_or_else(|| {
+                    panic!("Persistence is file, but PersistencePath is not specified")
+                });
+
+                println!("Persistence is file at {}", path);
+                PersistenceSettings::File { path }
+            }
+            Some(other) => panic!("Invalid persistence '{}'. Please use grpc or file", other),
+        };
+
+        let mut persistence_routes = Vec::new();
+
+        for route in self.persistence_routes.unwrap_or_default() {
+            if route.namespace.matches('*').count() > 1 {
+                panic!(
+                    "Invalid persistence route namespace '{}'. Only one * is supported",
+                    route.namespace
+                );
+            }
+
+            let route = PersistenceRoute {
+                namespace: route.namespace,
+                grpc_url: route.grpc_url,
+            };
+
+            println!("Persistence route: {:?}", route);
+
+            persistence_routes.push(route);
+        }
+
+        let persist_compression = match (self.persist_compression, self.persist_compressed) {
+            (Some(src), _) => PersistenceCompression::parse(src.as_str()).unwrap_or_else(|| {
+                panic!(
+                    "Invalid persist compression '{}'. Please use none, deflate or zstd",
+                    src
+                )
+            }),
+            (None, Some(true)) => PersistenceCompression::Deflate,
+            (None, _) => PersistenceCompression::None,
+        };
+
+        println!("Persist compression is {}", persist_compression.as_str());
+
+        let persist_ack_timeout = match self.persist_ack_timeout {
+            Some(src) => {
+                let timeout = rust_extensions::duration_utils::parse_duration(src.as_str())
+                    .unwrap_or_else(|err| {
+                        panic!(
+                            "Can not parse Persist Ack Timeout value '{}'. Reason: {:?}",
+                            src, err
+                        )
+                    });
+
+                println!("Persist ack timeout is set {}", src);
+
+                timeout
+            }
+            None => {
+                println!("Persist ack timeout is 5s. To change please specify PersistAckTimeout: hh:mm:ss");
+                Duration::from_secs(5)
+            }
+        };
+
+        match self.wal_path.as_ref() {
+            Some(wal_path) => println!("Write-ahead log is kept at {}", wal_path),
+            None => println!(
+                "Write-ahead log is disabled. To enable please specify WalPath: /path/to/folder"
+            ),
+        }
+
         SettingsModel {
-            persistence_grpc_url: self.persistence_grpc_url,
+            persistence,
+            persistence_routes,
+            persist_compression,
             queue_gc_timeout,
             max_delivery_size: self.max_delivery_size,
             delivery_timeout,
             auto_create_topic_on_publish,
             auto_create_topic_on_subscribe,
             listen_unix_socket: self.listen_unix_socket,
+            namespace_quotas,
+            publish_rate_limits,
+            persist_ack_timeout,
+            wal_path: self.wal_path,
         }
     }
 }
 
+/// A limit of 0 never refills its bucket, and the first publish which has to
+/// wait for it would wait forever.
+fn check_rate_limit(name: &str, value: Option<usize>) -> Option<usize> {
+    if value == Some(0) {
+        panic!(
+            "{} of publish rate limit is 0. Please specify a positive value or leave it out",
+            name
+        );
+    }
+
+    value
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
 
     use rust_extensions::duration_utils::DurationExtensions;
 
+    #[test]
+    #[should_panic]
+    fn test_rate_limit_of_zero_is_refused() {
+        super::check_rate_limit("MessagesPerSecond", Some(0));
+    }
+
+    #[test]
+    fn test_rate_limit_which_is_set_or_left_out_passes() {
+        assert_eq!(Some(1), super::check_rate_limit("BytesPerSecond", Some(1)));
+        assert_eq!(None, super::check_rate_limit("BytesPerSecond", None));
+    }
+
     #[test]
     fn test() {
         let diration = Duration::from_str("100ms").unwrap();
@@ -95,6 +95,10 @@ impl SubPage {
         self.inner.is_pending_persist(message_id)
     }
 
+    pub fn has_messages_to_persist(&self) -> bool {
+        self.inner.has_messages_to_persist()
+    }
+
     pub fn is_ready_to_gc(&self, active_pages: &[SubPageId]) -> bool {
         if self.inner.has_messages_to_persist() {
             return false;
@@ -123,14 +123,31 @@ impl TcpServerEvents {
                 {
                     session.lock_namespace();
 
-                    let result = operations::publisher::publish(
-                        &self.app,
-                        &session.get_namespace(),
+                    let namespace = session.get_namespace();
+
+                    let throttled = operations::publisher::throttle(
+                        self.app.as_ref(),
+                        session.get_name_and_version().name.as_str(),
+                        &namespace,
                         topic_id.as_str(),
-                        data_to_publish,
-                        persist_immediately,
-                        session.session_id,
-                    ).await;
+                        &data_to_publish,
+                    )
+                    .await;
+
+                    let result = match throttled {
+                        Ok(()) => {
+                            operations::publisher::publish(
+                                &self.app,
+                                &namespace,
+                                topic_id.as_str(),
+                                data_to_publish,
+                                persist_immediately,
+                                session.session_id,
+                            )
+                            .await
+                        }
+                        Err(err) => Err(err),
+                    };
 
                     if let Err(err) = result {
                         connection
@@ -221,8 +238,11 @@ impl TcpServerEvents {
                     .sessions
                     .get_tcp_session_by_connection_id(connection.id)
                 {
+                    let namespace = session.get_namespace();
+                    operations::check_persistence_route(self.app.as_ref(), namespace.as_ref())?;
+
                     operations::create_topic_if_not_exists(
-                        &session.get_namespace(),
+                        &namespace,
                         Some(session.session_id),
                         topic_id.as_str(),
                     )
@@ -3,8 +3,8 @@ use std::sync::{Arc, Mutex};
 use my_service_bus::shared::sub_page::SubPageId;
 
 use crate::{
-    app::AppContext, background::RestorePageTask, grpc_client::PersistenceGrpcService,
-    settings::SettingsModel, topics::Topic,
+    app::AppContext, background::RestorePageTask, grpc_client::MessagesPagesMockRepo,
+    persistence::PersistenceBackend, settings::SettingsModel, topics::Topic,
 };
 
 #[derive(Default)]
@@ -50,6 +50,8 @@ impl SubPageLoaderSchedulerMock {
         crate::operations::page_loader::load_page_to_cache(
             &task.topic,
             &app.persistence_client,
+            task.topic
+                .get_persist_compression(app.settings.persist_compression),
             task.sub_page_id,
         )
         .await;
@@ -73,17 +75,28 @@ impl rust_extensions::background_executor::BackgroundJob for NoOpBackgroundJob {
 }
 
 pub async fn create_app_context() -> Arc<AppContext> {
+    create_app_context_with_persistence(Arc::new(MessagesPagesMockRepo::new())).await
+}
+
+/// For tests which need to reach the persistence of the app context.
+pub async fn create_app_context_with_persistence(
+    persistence_client: Arc<dyn PersistenceBackend>,
+) -> Arc<AppContext> {
+    create_app_context_with_settings(persistence_client, create_test_settings()).await
+}
+
+pub fn create_test_settings() -> SettingsModel {
     const DELIVERY_SIZE: usize = 16;
 
-    let settings = SettingsModel::create_test_settings(DELIVERY_SIZE);
+    SettingsModel::create_test_settings(DELIVERY_SIZE)
+}
 
-    let app = Arc::new(
-        AppContext::new(
-            PersistenceGrpcService::create_mock_instance(),
-            settings.into(),
-        )
-        .await,
-    );
+/// For tests which run with settings other than the test ones.
+pub async fn create_app_context_with_settings(
+    persistence_client: Arc<dyn PersistenceBackend>,
+    settings: SettingsModel,
+) -> Arc<AppContext> {
+    let app = Arc::new(AppContext::new(persistence_client, settings.into()).await);
 
     // Tests don't exercise the persist path; register a no-op so publish's
     // persist_executor.trigger() doesn't panic on an unstarted executor.
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use my_service_bus::abstractions::publisher::MessageToPublish;
+
+use crate::utils::get_header;
+
+use super::{ForwardBatch, ForwardRuleStatistics};
+
+pub struct ContentRoute {
+    pub value: String,
+    pub topic_id: String,
+    pub statistics: Arc<ForwardRuleStatistics>,
+}
+
+/// Makes the topic a router: a published message goes to the topic its header
+/// value is routed to, e.g. `region=eu` to `orders.eu`. Destinations live in the
+/// namespace of the router and are created on the way if the node auto-creates
+/// topics on publish.
+///
+/// A message with no route goes to the fallback topic. With no fallback it
+/// stays in the router topic itself.
+pub struct ContentRouter {
+    pub namespace: String,
+    pub header: String,
+    routes: Vec<ContentRoute>,
+    pub fallback_topic_id: Option<String>,
+    pub fallback_statistics: Arc<ForwardRuleStatistics>,
+}
+
+impl ContentRouter {
+    pub fn new(
+        namespace: String,
+        header: String,
+        routes: Vec<(String, String)>,
+        fallback_topic_id: Option<String>,
+    ) -> Self {
+        Self {
+            namespace,
+            header,
+            routes: routes
+                .into_iter()
+                .map(|(value, topic_id)| ContentRoute {
+                    value,
+                    topic_id,
+                    statistics: Arc::new(ForwardRuleStatistics::new()),
+                })
+                .collect(),
+            fallback_topic_id,
+            fallback_statistics: Arc::new(ForwardRuleStatistics::new()),
+        }
+    }
+
+    pub fn get_routes(&self) -> &[ContentRoute] {
+        self.routes.as_slice()
+    }
+
+    /// Returns the messages which stay in the router topic and the batches which
+    /// go to the destinations.
+    pub fn route(
+        &self,
+        messages: Vec<MessageToPublish>,
+    ) -> (Vec<MessageToPublish>, Vec<ForwardBatch>) {
+        let mut stay = Vec::new();
+        let mut batches: Vec<ForwardBatch> = Vec::new();
+
+        for msg in messages {
+            let route = get_header(&msg.headers, self.header.as_str())
+                .and_then(|value| self.routes.iter().find(|itm| itm.value == value));
+
+            let (topic_id, statistics) = match route {
+                Some(route) => (route.topic_id.as_str(), &route.statistics),
+                None => match self.fallback_topic_id.as_ref() {
+                    Some(fallback_topic_id) => {
+                        (fallback_topic_id.as_str(), &self.fallback_statistics)
+                    }
+                    None => {
+                        stay.push(msg);
+                        continue;
+                    }
+                },
+            };
+
+            match batches.iter_mut().find(|itm| itm.topic_id == topic_id) {
+                Some(batch) => batch.messages.push(msg),
+                None => batches.push(ForwardBatch {
+                    namespace: self.namespace.clone(),
+                    topic_id: topic_id.to_string(),
+                    messages: vec![msg],
+                    statistics: statistics.clone(),
+                    auto_create_target: true,
+                }),
+            }
+        }
+
+        (stay, batches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+
+    use super::ContentRouter;
+
+    fn create_message(region: Option<&str>) -> MessageToPublish {
+        let mut headers = SbMessageHeaders::new();
+
+        if let Some(region) = region {
+            headers = headers.add("region".to_string(), region.to_string());
+        }
+
+        MessageToPublish {
+            headers,
+            content: vec![0u8],
+        }
+    }
+
+    #[test]
+    fn test_messages_are_routed_by_header() {
+        let router = ContentRouter::new(
+            "default".to_string(),
+            "region".to_string(),
+            vec![
+                ("eu".to_string(), "orders.eu".to_string()),
+                ("us".to_string(), "orders.us".to_string()),
+            ],
+            None,
+        );
+
+        let (stay, batches) = router.route(vec![
+            create_message(Some("eu")),
+            create_message(Some("us")),
+            create_message(Some("eu")),
+            create_message(Some("ap")),
+            create_message(None),
+        ]);
+
+        assert_eq!(2, stay.len());
+        assert_eq!(2, batches.len());
+        assert_eq!("orders.eu", batches[0].topic_id);
+        assert_eq!(2, batches[0].messages.len());
+        assert_eq!("orders.us", batches[1].topic_id);
+
+        let router = ContentRouter::new(
+            "default".to_string(),
+            "region".to_string(),
+            vec![("eu".to_string(), "orders.eu".to_string())],
+            Some("orders.other".to_string()),
+        );
+
+        let (stay, batches) = router.route(vec![create_message(None)]);
+
+        assert_eq!(0, stay.len());
+        assert_eq!("orders.other", batches[0].topic_id);
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use my_service_bus::abstractions::publisher::MessageToPublish;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::utils::get_header;
+
+/// Idempotency key of a published message. A second publish with the same key
+/// inside the dedup window of the topic is acknowledged but not stored.
+pub const MESSAGE_KEY_HEADER: &str = "sb-message-key";
+
+/// Keys we keep when the window is set by time only.
+pub const DEFAULT_DEDUP_MAX_KEYS: usize = 100_000;
+
+/// Keys of the messages published lately. Bounded both by time and by the
+/// amount of keys: whatever comes first pushes the oldest key out.
+pub struct DedupWindow {
+    window: Option<Duration>,
+    max_keys: usize,
+    seen: HashMap<String, i64>,
+    order: VecDeque<String>,
+    /// Publishes dropped as duplicates since the window was set.
+    pub deduplicated: usize,
+}
+
+impl DedupWindow {
+    pub fn new(window: Option<Duration>, max_keys: Option<usize>) -> Self {
+        Self {
+            window,
+            max_keys: max_keys.unwrap_or(DEFAULT_DEDUP_MAX_KEYS),
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            deduplicated: 0,
+        }
+    }
+
+    pub fn get_window(&self) -> Option<Duration> {
+        self.window
+    }
+
+    pub fn get_max_keys(&self) -> usize {
+        self.max_keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Drops the messages whose key is already in the window, duplicates inside
+    /// the same batch included. Messages without a key always pass.
+    pub fn filter(
+        &mut self,
+        messages: Vec<MessageToPublish>,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<MessageToPublish> {
+        self.gc(now);
+
+        let mut result = Vec::with_capacity(messages.len());
+
+        for msg in messages {
+            if let Some(key) = get_header(&msg.headers, MESSAGE_KEY_HEADER) {
+                if self.seen.contains_key(key) {
+                    self.deduplicated += 1;
+                    continue;
+                }
+
+                self.add(key.to_string(), now);
+            }
+
+            result.push(msg);
+        }
+
+        result
+    }
+
+    fn add(&mut self, key: String, now: DateTimeAsMicroseconds) {
+        while self.order.len() >= self.max_keys {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.seen.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+
+        if self.max_keys == 0 {
+            return;
+        }
+
+        self.seen.insert(key.clone(), now.unix_microseconds);
+        self.order.push_back(key);
+    }
+
+    fn gc(&mut self, now: DateTimeAsMicroseconds) {
+        let Some(window) = self.window else {
+            return;
+        };
+
+        let expired_before = now.unix_microseconds - window.as_micros() as i64;
+
+        while let Some(oldest) = self.order.front() {
+            let seen_at = self.seen.get(oldest).copied().unwrap_or(i64::MIN);
+
+            if seen_at >= expired_before {
+                break;
+            }
+
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use my_service_bus::abstractions::{publisher::MessageToPublish, SbMessageHeaders};
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{DedupWindow, MESSAGE_KEY_HEADER};
+
+    fn create_message(key: Option<&str>) -> MessageToPublish {
+        let mut headers = SbMessageHeaders::new();
+
+        if let Some(key) = key {
+            headers = headers.add(MESSAGE_KEY_HEADER.to_string(), key.to_string());
+        }
+
+        MessageToPublish {
+            headers,
+            content: vec![0u8],
+        }
+    }
+
+    #[test]
+    fn test_duplicates_are_dropped_inside_the_window() {
+        let mut dedup = DedupWindow::new(Some(Duration::from_secs(60)), None);
+
+        let now = DateTimeAsMicroseconds::now();
+
+        let result = dedup.filter(
+            vec![
+                create_message(Some("a")),
+                create_message(Some("a")),
+                create_message(None),
+                create_message(Some("b")),
+            ],
+            now,
+        );
+
+        assert_eq!(3, result.len());
+        assert_eq!(1, dedup.deduplicated);
+        assert_eq!(2, dedup.len());
+
+        let result = dedup.filter(vec![create_message(Some("b"))], now);
+        assert_eq!(0, result.len());
+
+        let later = now.add(Duration::from_secs(61));
+        let result = dedup.filter(vec![create_message(Some("b"))], later);
+        assert_eq!(1, result.len());
+        assert_eq!(1, dedup.len());
+    }
+
+    #[test]
+    fn test_oldest_keys_are_evicted_by_amount() {
+        let mut dedup = DedupWindow::new(None, Some(2));
+
+        let now = DateTimeAsMicroseconds::now();
+
+        dedup.filter(
+            vec![
+                create_message(Some("a")),
+                create_message(Some("b")),
+                create_message(Some("c")),
+            ],
+            now,
+        );
+
+        assert_eq!(2, dedup.len());
+
+        let result = dedup.filter(vec![create_message(Some("a"))], now);
+        assert_eq!(1, result.len());
+
+        let result = dedup.filter(vec![create_message(Some("c"))], now);
+        assert_eq!(0, result.len());
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use my_service_bus::abstractions::publisher::MessageToPublish;
+
+use crate::queues::HeaderFilter;
+
+/// Messages published to the topic are appended to the target topic as well,
+/// which may live in another namespace. With a filter only the matching ones go.
+pub struct ForwardRule {
+    pub namespace: String,
+    pub topic_id: String,
+    pub filter: Option<HeaderFilter>,
+    pub statistics: Arc<ForwardRuleStatistics>,
+}
+
+impl ForwardRule {
+    pub fn new(namespace: String, topic_id: String, filter: Option<HeaderFilter>) -> Self {
+        Self {
+            namespace,
+            topic_id,
+            filter,
+            statistics: Arc::new(ForwardRuleStatistics::new()),
+        }
+    }
+
+    pub fn is_target(&self, namespace: &str, topic_id: &str) -> bool {
+        self.namespace == namespace && self.topic_id == topic_id
+    }
+}
+
+/// Shared with the batches in flight: forwarding happens outside of the lock of
+/// the source topic, so the counters can not live under it.
+pub struct ForwardRuleStatistics {
+    forwarded: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl ForwardRuleStatistics {
+    pub fn new() -> Self {
+        Self {
+            forwarded: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn add_forwarded(&self, amount: usize) {
+        self.forwarded.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    pub fn add_failed(&self, amount: usize) {
+        self.failed.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    pub fn get_forwarded(&self) -> usize {
+        self.forwarded.load(Ordering::Relaxed)
+    }
+
+    pub fn get_failed(&self) -> usize {
+        self.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// Copies of the published messages on their way to the target of a rule.
+pub struct ForwardBatch {
+    pub namespace: String,
+    pub topic_id: String,
+    pub messages: Vec<MessageToPublish>,
+    pub statistics: Arc<ForwardRuleStatistics>,
+    /// Routed by a content router: a missing target is created on the way.
+    pub auto_create_target: bool,
+}
+
+pub struct ForwardRules {
+    items: Vec<ForwardRule>,
+}
+
+impl ForwardRules {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ForwardRule> {
+        self.items.iter()
+    }
+
+    /// A rule to the same target replaces the old one.
+    pub fn set(&mut self, rule: ForwardRule) {
+        self.items
+            .retain(|itm| !itm.is_target(rule.namespace.as_str(), rule.topic_id.as_str()));
+        self.items.push(rule);
+    }
+
+    pub fn remove(&mut self, namespace: &str, topic_id: &str) -> bool {
+        let len_before = self.items.len();
+        self.items.retain(|itm| !itm.is_target(namespace, topic_id));
+        self.items.len() != len_before
+    }
+
+    pub fn get_batches(&self, messages: &[MessageToPublish]) -> Vec<ForwardBatch> {
+        let mut result = Vec::new();
+
+        for rule in self.items.iter() {
+            let messages: Vec<_> = messages
+                .iter()
+                .filter(|msg| match &rule.filter {
+                    Some(filter) => filter.matches(&msg.headers),
+                    None => true,
+                })
+                .map(|msg| MessageToPublish {
+                    headers: msg.headers.clone(),
+                    content: msg.content.clone(),
+                })
+                .collect();
+
+            if messages.is_empty() {
+                continue;
+            }
+
+            result.push(ForwardBatch {
+                namespace: rule.namespace.clone(),
+                topic_id: rule.topic_id.clone(),
+                messages,
+                statistics: rule.statistics.clone(),
+                auto_create_target: false,
+            });
+        }
+
+        result
+    }
+}
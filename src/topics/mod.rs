@@ -6,7 +6,9 @@ mod topic_statistics;
 mod topics_list;
 
 pub use topic::Topic;
-pub use topic_inner::TopicInner;
+pub use topic_inner::{PublishedMessages, TopicInner};
+pub use topic_snapshot::ContentRouterSnapshot;
+pub use topic_snapshot::ForwardRuleSnapshot;
 pub use topic_snapshot::TopicQueueSnapshot;
 pub use topic_snapshot::TopicSnapshot;
 pub use topic_statistics::*;
@@ -15,3 +17,11 @@ mod topic_publishers;
 pub use topic_publishers::*;
 mod topic_id;
 pub use topic_id::*;
+mod scheduled_messages;
+pub use scheduled_messages::*;
+mod dedup_window;
+pub use dedup_window::*;
+mod forward_rules;
+pub use forward_rules::*;
+mod content_router;
+pub use content_router::*;
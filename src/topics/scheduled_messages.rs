@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use my_service_bus::abstractions::{
+    queue_with_intervals::QueueWithIntervals, MessageId, SbMessageHeaders,
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::utils::get_header;
+
+/// Absolute due time: unix milliseconds or an RFC3339 date.
+pub const SCHEDULED_DELIVER_AT_HEADER: &str = "sb-deliver-at";
+/// Due time relative to the publish moment, in milliseconds.
+pub const SCHEDULED_DELAY_MS_HEADER: &str = "sb-delay-ms";
+
+/// Due moment of a just published message. `None` means deliver right away: no
+/// header, a value we can not parse or which is too far away to be a moment, or
+/// a moment which is already in the past.
+pub fn get_scheduled_deliver_at(
+    headers: &SbMessageHeaders,
+    now: DateTimeAsMicroseconds,
+) -> Option<DateTimeAsMicroseconds> {
+    let deliver_at = if let Some(value) = get_header(headers, SCHEDULED_DELIVER_AT_HEADER) {
+        crate::utils::parse_date_time(value)?
+    } else {
+        let delay_ms: i64 = get_header(headers, SCHEDULED_DELAY_MS_HEADER)?
+            .trim()
+            .parse()
+            .ok()?;
+        let unix_microseconds = delay_ms
+            .checked_mul(1000)
+            .and_then(|delay| now.unix_microseconds.checked_add(delay))?;
+        DateTimeAsMicroseconds::new(unix_microseconds)
+    };
+
+    if deliver_at.unix_microseconds <= now.unix_microseconds {
+        return None;
+    }
+
+    Some(deliver_at)
+}
+
+/// Messages which are already stored in the pages of the topic but are held out
+/// of every queue until they are due.
+pub struct ScheduledMessages {
+    by_due_time: BTreeMap<i64, Vec<i64>>,
+    amount: usize,
+}
+
+impl ScheduledMessages {
+    pub fn new() -> Self {
+        Self {
+            by_due_time: BTreeMap::new(),
+            amount: 0,
+        }
+    }
+
+    pub fn add(&mut self, deliver_at: DateTimeAsMicroseconds, message_id: MessageId) {
+        self.by_due_time
+            .entry(deliver_at.unix_microseconds)
+            .or_insert_with(Vec::new)
+            .push(message_id.get_value());
+
+        self.amount += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.amount
+    }
+
+    /// Takes out every message due at `now` or earlier.
+    pub fn get_due(&mut self, now: DateTimeAsMicroseconds) -> Option<QueueWithIntervals> {
+        let not_due = self.by_due_time.split_off(&(now.unix_microseconds + 1));
+        let due = std::mem::replace(&mut self.by_due_time, not_due);
+
+        if due.len() == 0 {
+            return None;
+        }
+
+        let mut result = QueueWithIntervals::new();
+
+        for message_id in due.into_values().flatten() {
+            result.enqueue(message_id);
+            self.amount -= 1;
+        }
+
+        Some(result)
+    }
+
+    pub fn get_min_message_id(&self) -> Option<i64> {
+        self.by_due_time.values().flatten().min().copied()
+    }
+
+    pub fn iter_message_ids(&self) -> impl Iterator<Item = i64> + '_ {
+        self.by_due_time.values().flatten().copied()
+    }
+
+    /// `(message_id, deliver_at)` pairs as they go to the persistence snapshot.
+    pub fn get_snapshot(&self) -> Vec<(i64, i64)> {
+        let mut result = Vec::with_capacity(self.amount);
+
+        for (deliver_at, message_ids) in self.by_due_time.iter() {
+            for message_id in message_ids {
+                result.push((*message_id, *deliver_at));
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::SbMessageHeaders;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    #[test]
+    fn test_delay_header_is_relative_to_now() {
+        let now = DateTimeAsMicroseconds::new(1_000_000);
+        let headers = SbMessageHeaders::new().add(
+            super::SCHEDULED_DELAY_MS_HEADER.to_string(),
+            "500".to_string(),
+        );
+
+        let deliver_at = super::get_scheduled_deliver_at(&headers, now).unwrap();
+
+        assert_eq!(deliver_at.unix_microseconds, 1_500_000);
+    }
+
+    #[test]
+    fn test_delay_which_overflows_is_invalid() {
+        let now = DateTimeAsMicroseconds::new(1_000_000);
+        let headers = SbMessageHeaders::new().add(
+            super::SCHEDULED_DELAY_MS_HEADER.to_string(),
+            u64::MAX.to_string(),
+        );
+
+        assert!(super::get_scheduled_deliver_at(&headers, now).is_none());
+
+        let headers = SbMessageHeaders::new().add(
+            super::SCHEDULED_DELAY_MS_HEADER.to_string(),
+            (i64::MAX / 1000).to_string(),
+        );
+
+        assert!(super::get_scheduled_deliver_at(&headers, now).is_none());
+    }
+
+    #[test]
+    fn test_due_time_in_the_past_is_delivered_right_away() {
+        let now = DateTimeAsMicroseconds::new(10_000_000);
+        let headers = SbMessageHeaders::new().add(
+            super::SCHEDULED_DELIVER_AT_HEADER.to_string(),
+            "5".to_string(),
+        );
+
+        assert!(super::get_scheduled_deliver_at(&headers, now).is_none());
+    }
+
+    #[test]
+    fn test_only_due_messages_are_taken() {
+        let mut scheduled = super::ScheduledMessages::new();
+
+        scheduled.add(DateTimeAsMicroseconds::new(100), 1.into());
+        scheduled.add(DateTimeAsMicroseconds::new(200), 2.into());
+
+        let due = scheduled.get_due(DateTimeAsMicroseconds::new(100)).unwrap();
+
+        assert_eq!(due.queue_size(), 1);
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled.get_min_message_id(), Some(2));
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use my_service_bus::abstractions::MessageId;
@@ -7,7 +8,10 @@ use parking_lot::Mutex;
 use rust_extensions::sorted_vec::EntityWithStrKey;
 
 use crate::messages_page::{MessagesToPersistBucket, MySbMessageContent, SizeMetrics};
+use crate::persistence::PersistenceCompression;
 use crate::queue_subscribers::DeadSubscriber;
+use crate::quotas::Quotas;
+use crate::wal::TopicWal;
 
 use super::topic_data_access::TopicDataAccess;
 use super::{TopicId, TopicInner};
@@ -19,6 +23,9 @@ pub struct Topic {
     pub namespace: String,
     pub topic_id: TopicId,
     inner: Mutex<TopicInner>,
+    /// One save of the messages of the topic at a time: the persist job and a
+    /// publish which waits for its messages to be saved must not send them twice.
+    pub persist_messages_lock: tokio::sync::Mutex<()>,
 }
 
 impl Topic {
@@ -28,14 +35,17 @@ impl Topic {
         message_id: i64,
         persist: bool,
         deleted: i64,
+        wal: Option<Arc<TopicWal>>,
     ) -> Self {
         let topic_id = TopicId::new(topic_id);
+        let mut inner = TopicInner::new(topic_id.clone(), message_id, persist, deleted);
+        inner.wal = wal;
+
         Self {
             namespace,
-            topic_id: topic_id.clone(),
-            inner: Mutex::new(TopicInner::new(
-                topic_id, message_id, persist, deleted,
-            )),
+            topic_id,
+            inner: Mutex::new(inner),
+            persist_messages_lock: tokio::sync::Mutex::new(()),
         }
     }
 
@@ -65,6 +75,8 @@ impl Topic {
         convert(&inner)
     }
 
+    /// `delivery_timeout_duration` is the global timeout, used by queues which do not
+    /// have their own one.
     pub fn find_subscribers_dead_on_delivery(
         &self,
         delivery_timeout_duration: Duration,
@@ -73,9 +85,11 @@ impl Topic {
         let mut topic_data = self.inner.lock();
 
         for queue in topic_data.queues.get_all_mut() {
+            let delivery_timeout = queue.delivery_timeout.unwrap_or(delivery_timeout_duration);
+
             let dead_subscribers = queue
                 .subscribers
-                .find_subscribers_dead_on_delivery(delivery_timeout_duration);
+                .find_subscribers_dead_on_delivery(delivery_timeout);
             if dead_subscribers.len() > 0 {
                 for dead_subscriber in dead_subscribers {
                     if !result.iter().any(|itm: &DeadSubscriber| {
@@ -113,6 +127,49 @@ impl Topic {
         write_access.persist = persist;
     }
 
+    pub fn update_ttl(&self, ttl: Option<Duration>) {
+        let mut write_access = self.get_access();
+        write_access.ttl = ttl;
+    }
+
+    pub fn update_quotas(&self, quotas: Quotas) {
+        let mut write_access = self.get_access();
+        write_access.quotas = quotas;
+    }
+
+    pub fn update_persist_compression(&self, persist_compression: Option<PersistenceCompression>) {
+        let mut write_access = self.get_access();
+        write_access.persist_compression = persist_compression;
+    }
+
+    /// `default` is the compression of the settings.
+    pub fn get_persist_compression(
+        &self,
+        default: PersistenceCompression,
+    ) -> PersistenceCompression {
+        self.inner.lock().persist_compression.unwrap_or(default)
+    }
+
+    /// `None` for both switches the dedup off. Changing the window forgets the keys seen so far.
+    pub fn update_dedup(&self, window: Option<Duration>, max_keys: Option<usize>) {
+        let mut write_access = self.get_access();
+
+        if window.is_none() && max_keys.is_none() {
+            write_access.dedup = None;
+            return;
+        }
+
+        if let Some(dedup) = write_access.dedup.as_ref() {
+            if dedup.get_window() == window
+                && dedup.get_max_keys() == max_keys.unwrap_or(super::DEFAULT_DEDUP_MAX_KEYS)
+            {
+                return;
+            }
+        }
+
+        write_access.dedup = Some(super::DedupWindow::new(window, max_keys));
+    }
+
     pub fn get_deleted(&self) -> i64 {
         self.inner.lock().deleted
     }
@@ -127,3 +184,87 @@ impl EntityWithStrKey for Topic {
         self.topic_id.as_str()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use my_service_bus::abstractions::{
+        publisher::MessageToPublish, subscriber::TopicQueueType, SbMessageHeaders,
+    };
+
+    #[tokio::test]
+    async fn test_queue_with_short_delivery_timeout_is_dead_first() {
+        const TOPIC_NAME: &str = "test-topic";
+        const SHORT_TIMEOUT_QUEUE: &str = "short-timeout-queue";
+        const GLOBAL_TIMEOUT_QUEUE: &str = "global-timeout-queue";
+
+        let app = crate::test_tools::create_app_context().await;
+        let namespace = app.get_default_namespace();
+
+        let test_session = app.sessions.add_test(namespace.clone());
+
+        crate::operations::create_topic_if_not_exists(
+            &namespace,
+            Some(test_session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let mut subscriber_ids = Vec::new();
+
+        for queue_id in [SHORT_TIMEOUT_QUEUE, GLOBAL_TIMEOUT_QUEUE] {
+            let subscriber_id = crate::operations::subscriber::subscribe_to_queue(
+                &app,
+                &namespace,
+                TOPIC_NAME.to_string(),
+                queue_id.to_string(),
+                TopicQueueType::Permanent,
+                test_session.clone().into(),
+            )
+            .await
+            .unwrap();
+
+            subscriber_ids.push(subscriber_id);
+        }
+
+        let topic = namespace.topic_list.get(TOPIC_NAME).unwrap();
+
+        topic
+            .get_access()
+            .queues
+            .get_mut(SHORT_TIMEOUT_QUEUE)
+            .unwrap()
+            .delivery_timeout = Some(Duration::from_millis(1));
+
+        let msg = MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![0u8, 1u8, 2u8],
+        };
+
+        crate::operations::publisher::publish(
+            &app,
+            &namespace,
+            TOPIC_NAME,
+            vec![msg],
+            false,
+            test_session.session_id,
+        )
+        .await
+        .unwrap();
+
+        app.restore_page_scheduler.emulate_event_loop_tick().await;
+
+        assert_eq!(test_session.get_list_of_packets_and_clear_them().len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let dead_subscribers = topic.find_subscribers_dead_on_delivery(Duration::from_secs(60));
+
+        assert_eq!(dead_subscribers.len(), 1);
+        assert!(dead_subscribers[0]
+            .subscriber_id
+            .equals_to(subscriber_ids[0]));
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use my_service_bus::abstractions::publisher::MessageToPublish;
@@ -9,14 +10,20 @@ use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::avg_value::AvgValue;
 use crate::messages_page::{ActiveSubPages, MessagesPageList, MySbMessageContent, SizeMetrics};
+use crate::persistence::PersistenceCompression;
 use crate::queue_subscribers::QueueSubscriber;
 use crate::queues::{TopicQueue, TopicQueuesList};
+use crate::quotas::{QuotaUsage, Quotas};
 use crate::sessions::SessionId;
 #[cfg(test)]
 use crate::sub_page::GetMessageResult;
 use crate::utils::MinMessageIdCalculator;
+use crate::wal::{TopicWal, WalFlush};
 
-use super::{TopicId, TopicPublishers, TopicStatistics};
+use super::{
+    ContentRouter, DedupWindow, ForwardBatch, ForwardRules, ScheduledMessages, TopicId,
+    TopicPublishers, TopicStatistics,
+};
 
 const BADGE_HIGHLIGHT_TIME_OUT: u8 = 2;
 
@@ -30,6 +37,31 @@ pub struct TopicInner {
     pub persist: bool,
     pub deleted: i64,
     pub avg_size: AvgValue,
+    pub scheduled: ScheduledMessages,
+    /// Messages older than this are dropped instead of being delivered.
+    pub ttl: Option<Duration>,
+    /// Publishes with an already seen message key are dropped while it is set.
+    pub dedup: Option<DedupWindow>,
+    pub forward_rules: ForwardRules,
+    pub router: Option<ContentRouter>,
+    /// Publishes are refused while the topic is over any of these.
+    pub quotas: Quotas,
+    /// Messages to persist are written here before the publish is acknowledged.
+    pub wal: Option<Arc<TopicWal>>,
+    /// How the messages travel to and from persistence. `None` - as the settings say.
+    pub persist_compression: Option<PersistenceCompression>,
+}
+
+pub struct PublishedMessages {
+    /// Duplicates caught by the dedup window are acknowledged but not stored, and
+    /// routed messages are stored by their destinations.
+    pub stored: usize,
+    /// Copies the forward rules send on to other topics, and the routed messages.
+    pub to_forward: Vec<ForwardBatch>,
+    /// To wait for, once the topic is let go, before the publisher gets its ack.
+    /// If it fails the messages are delivered and persisted all the same, but the
+    /// publisher must not get its ack.
+    pub wal_flush: Option<WalFlush>,
 }
 
 impl TopicInner {
@@ -44,6 +76,14 @@ impl TopicInner {
             persist,
             deleted,
             avg_size: AvgValue::new(),
+            scheduled: ScheduledMessages::new(),
+            ttl: None,
+            dedup: None,
+            forward_rules: ForwardRules::new(),
+            router: None,
+            quotas: Quotas::default(),
+            wal: None,
+            persist_compression: None,
         }
     }
 
@@ -52,30 +92,164 @@ impl TopicInner {
         self.publishers.add(session_id, BADGE_HIGHLIGHT_TIME_OUT);
     }
 
-    pub fn publish_messages(&mut self, session_id: SessionId, messages: Vec<MessageToPublish>) {
+    /// Duplicates caught by the dedup window are acknowledged to the publisher but
+    /// never get an id. The rest is matched against the forward rules and the
+    /// router; the caller takes the batches to their targets once it lets this
+    /// topic go.
+    pub fn publish_messages(
+        &mut self,
+        session_id: SessionId,
+        messages: Vec<MessageToPublish>,
+    ) -> PublishedMessages {
         self.set_publisher_as_active(session_id);
 
+        let messages = match self.dedup.as_mut() {
+            Some(dedup) => dedup.filter(messages, DateTimeAsMicroseconds::now()),
+            None => messages,
+        };
+
+        self.route_and_append(messages)
+    }
+
+    /// Messages which came in by a forward rule or a router of another topic. They
+    /// go on by the rules and the router of this topic as well.
+    pub fn forward_messages(&mut self, messages: Vec<MessageToPublish>) -> PublishedMessages {
+        self.route_and_append(messages)
+    }
+
+    fn route_and_append(&mut self, messages: Vec<MessageToPublish>) -> PublishedMessages {
+        let mut to_forward = self.forward_rules.get_batches(&messages);
+
+        let messages = match self.router.as_ref() {
+            Some(router) => {
+                let (stay, routed) = router.route(messages);
+                to_forward.extend(routed);
+                stay
+            }
+            None => messages,
+        };
+
+        let stored = messages.len();
+
+        let wal_flush = if stored > 0 {
+            self.append_messages(messages)
+        } else {
+            None
+        };
+
+        PublishedMessages {
+            stored,
+            to_forward,
+            wal_flush,
+        }
+    }
+
+    /// Stores the messages and hands their ids to every queue. Broker-side
+    /// publishes (dead-lettering) come straight here: they have no publisher
+    /// session to mark as active.
+    ///
+    /// A message with a due time in its headers is stored as well, but stays
+    /// out of the queues until [`Self::enqueue_due_scheduled_messages`] picks it up.
+    ///
+    /// The messages are only buffered for the write-ahead log: the returned flush
+    /// writes them once the caller lets the topic go.
+    pub fn append_messages(&mut self, messages: Vec<MessageToPublish>) -> Option<WalFlush> {
+        let now = DateTimeAsMicroseconds::now();
+
         let mut ids = QueueWithIntervals::new();
 
         for msg in messages {
-            let message = MySbMessageContent {
-                id: self.message_id.into(),
-                content: msg.content,
-                time: DateTimeAsMicroseconds::now(),
-                headers: msg.headers,
-            };
+            let deliver_at = super::get_scheduled_deliver_at(&msg.headers, now);
 
-            self.avg_size.add(message.content.len());
+            let message_id = self.store_message(msg, now);
+
+            match deliver_at {
+                Some(deliver_at) => self.scheduled.add(deliver_at, message_id),
+                None => ids.enqueue(message_id.get_value()),
+            }
+        }
 
-            ids.enqueue(message.id.into());
+        for topic_queue in self.queues.get_all_mut() {
+            topic_queue.enqueue_messages(&ids);
+        }
 
-            let page_id: SubPageId = message.id.into();
+        self.get_wal_flush()
+    }
 
-            let page = self.pages.get_or_create_mut(page_id);
-            page.update_last_accessed(message.time);
-            page.add_message(message, self.persist);
+    /// Returns true if some messages became due and were handed to the queues.
+    pub fn enqueue_due_scheduled_messages(&mut self, now: DateTimeAsMicroseconds) -> bool {
+        let Some(ids) = self.scheduled.get_due(now) else {
+            return false;
+        };
 
-            self.message_id.increment();
+        for topic_queue in self.queues.get_all_mut() {
+            topic_queue.enqueue_messages(&ids);
+        }
+
+        true
+    }
+
+    /// Returns true if some queue got back messages which waited for their redelivery delay.
+    pub fn enqueue_due_redeliveries(&mut self, now: DateTimeAsMicroseconds) -> bool {
+        let mut result = false;
+
+        for topic_queue in self.queues.get_all_mut() {
+            if topic_queue.enqueue_due_redeliveries(now) {
+                result = true;
+            }
+        }
+
+        result
+    }
+
+    /// Gives the messages ids and puts them into the pages, but enqueues them
+    /// nowhere: the caller decides which queues get them. These are copies the
+    /// broker makes itself, with no publisher waiting for an ack, so they go to
+    /// the write-ahead log in the background and a failed write is only reported.
+    pub fn store_messages(&mut self, messages: Vec<MessageToPublish>) -> QueueWithIntervals {
+        let now = DateTimeAsMicroseconds::now();
+
+        let mut ids = QueueWithIntervals::new();
+
+        for msg in messages {
+            let message_id = self.store_message(msg, now);
+            ids.enqueue(message_id.get_value());
+        }
+
+        if let Some(wal_flush) = self.get_wal_flush() {
+            wal_flush.spawn("store_messages", self.topic_id.to_string());
+        }
+
+        ids
+    }
+
+    /// Messages the previous run left in the write-ahead log. The ones newer than
+    /// the restored snapshot never reached the queues, so they are enqueued now;
+    /// all of them are persisted again, their segments stay until they are.
+    pub fn restore_from_wal(&mut self, messages: Vec<MySbMessageContent>) {
+        let now = DateTimeAsMicroseconds::now();
+        let restored_message_id = self.message_id.get_value();
+
+        let mut ids = QueueWithIntervals::new();
+
+        for message in messages {
+            let message_id = message.id.get_value();
+
+            if message_id >= restored_message_id {
+                match super::get_scheduled_deliver_at(&message.headers, now) {
+                    Some(deliver_at) => self.scheduled.add(deliver_at, message.id),
+                    None => ids.enqueue(message_id),
+                }
+            }
+
+            if message_id >= self.message_id.get_value() {
+                self.message_id = (message_id + 1).into();
+            }
+
+            self.avg_size.add(message.content.len());
+
+            let page = self.pages.get_or_create_mut(message.id.into());
+            page.add_message(message, true);
         }
 
         for topic_queue in self.queues.get_all_mut() {
@@ -83,6 +257,45 @@ impl TopicInner {
         }
     }
 
+    /// `None` if nothing goes to the write-ahead log. Messages which fail to be
+    /// written stay buffered, and go with the next flush.
+    fn get_wal_flush(&self) -> Option<WalFlush> {
+        if !self.persist {
+            return None;
+        }
+
+        self.wal.as_ref().map(|wal| wal.get_flush())
+    }
+
+    fn store_message(&mut self, msg: MessageToPublish, now: DateTimeAsMicroseconds) -> MessageId {
+        let message = MySbMessageContent {
+            id: self.message_id.into(),
+            content: msg.content,
+            time: now,
+            headers: msg.headers,
+        };
+
+        let message_id = message.id;
+
+        self.avg_size.add(message.content.len());
+
+        let page_id: SubPageId = message.id.into();
+
+        if self.persist {
+            if let Some(wal) = self.wal.as_ref() {
+                wal.append(page_id, &message);
+            }
+        }
+
+        let page = self.pages.get_or_create_mut(page_id);
+        page.update_last_accessed(message.time);
+        page.add_message(message, self.persist);
+
+        self.message_id.increment();
+
+        message_id
+    }
+
     pub fn one_second_tick(&mut self) {
         self.publishers.one_second_tick();
     }
@@ -105,8 +318,11 @@ impl TopicInner {
             let min_id = topic_queue.queue.get_min_id();
             min_message_id.add(min_id);
             min_message_id.add(topic_queue.subscribers.get_min_message_id());
+            min_message_id.add(topic_queue.delayed.get_min_message_id());
         }
 
+        min_message_id.add(self.scheduled.get_min_message_id());
+
         min_message_id.get()
     }
 
@@ -140,6 +356,15 @@ impl TopicInner {
                         }
                     }
                 }
+
+                for message_id in queue.delayed.iter_message_ids() {
+                    result.add_if_not_exists(SubPageId::from_message_id(message_id.into()));
+                }
+            }
+
+            // Scheduled messages sit in no queue yet, and there is no other copy of them.
+            for message_id in self.scheduled.iter_message_ids() {
+                result.add_if_not_exists(SubPageId::from_message_id(message_id.into()));
             }
         }
 
@@ -243,6 +468,16 @@ impl TopicInner {
         result
     }
 
+    pub fn get_quota_usage(&self) -> QuotaUsage {
+        let metrics = self.get_topic_size_metrics();
+
+        QuotaUsage {
+            memory_size: metrics.data_size,
+            persist_queue_size: metrics.persist_size,
+            queue_size: self.queues.get_all().map(|itm| itm.get_queue_size()).sum(),
+        }
+    }
+
     pub fn get_messages_to_persist<TResult>(
         &self,
         transform: impl Fn(&MySbMessageContent) -> TResult,
@@ -254,6 +489,28 @@ impl TopicInner {
 
     pub fn mark_messages_as_persisted(&mut self, sub_page_id: SubPageId, ids: &QueueWithIntervals) {
         self.pages.mark_messages_as_persisted(sub_page_id, ids);
+
+        let sub_page_is_persisted = match self.pages.get(sub_page_id) {
+            Some(sub_page) => !sub_page.has_messages_to_persist(),
+            None => true,
+        };
+
+        if sub_page_is_persisted {
+            if let Some(wal) = self.wal.as_ref() {
+                if let Err(err) = wal.remove_segment(sub_page_id) {
+                    my_logger::LOGGER.write_error(
+                        "mark_messages_as_persisted",
+                        format!(
+                            "Can not delete write-ahead log segment {}: {:?}",
+                            sub_page_id.get_value(),
+                            err
+                        ),
+                        my_logger::LogEventCtx::new().add("topicId", self.topic_id.as_str()),
+                    );
+                }
+            }
+        }
+
         self.gc();
     }
 
@@ -320,6 +577,41 @@ mod tests {
         assert!(message_result.unwrap().is_not_loaded());
     }
 
+    #[test]
+    fn test_scheduled_message_is_held_out_of_queues_until_due() {
+        use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+        let mut topic_inner = super::TopicInner::new("test".into(), 0, true, 0);
+
+        topic_inner.queues.add_queue_if_not_exists(
+            "test".into(),
+            "test".to_string(),
+            TopicQueueType::Permanent,
+        );
+
+        topic_inner.publish_messages(
+            10.into(),
+            vec![MessageToPublish {
+                headers: SbMessageHeaders::new().add(
+                    super::super::SCHEDULED_DELAY_MS_HEADER.to_string(),
+                    "60000".to_string(),
+                ),
+                content: vec![1, 2, 3],
+            }],
+        );
+
+        assert_eq!(topic_inner.queues.get("test").unwrap().get_queue_size(), 0);
+        assert_eq!(topic_inner.scheduled.len(), 1);
+
+        assert!(!topic_inner.enqueue_due_scheduled_messages(DateTimeAsMicroseconds::now()));
+
+        let due = DateTimeAsMicroseconds::now().add(std::time::Duration::from_secs(61));
+        assert!(topic_inner.enqueue_due_scheduled_messages(due));
+
+        assert_eq!(topic_inner.queues.get("test").unwrap().get_queue_size(), 1);
+        assert_eq!(topic_inner.scheduled.len(), 0);
+    }
+
     #[test]
     fn persist_false_protects_all_sub_pages_in_queue_intervals() {
         let mut topic_inner = super::TopicInner::new("test".into(), 6_000, false, 0);
@@ -2,12 +2,25 @@ use my_service_bus::abstractions::{
     queue_with_intervals::QueueIndexRange, subscriber::TopicQueueType,
 };
 use rust_extensions::ShortString;
+use std::time::Duration;
+
+use crate::{
+    persistence::PersistenceCompression,
+    queues::{DeadLetterSettings, HeaderFilter, RedeliveryPolicy},
+    quotas::Quotas,
+};
 
 #[derive(Clone)]
 pub struct TopicQueueSnapshot {
     pub queue_id: String,
     pub queue_type: TopicQueueType,
     pub ranges: Vec<QueueIndexRange<i64>>,
+    pub dead_letter: Option<DeadLetterSettings>,
+    pub redelivery: Option<RedeliveryPolicy>,
+    pub ordering_key_header: Option<String>,
+    pub header_filter: Option<HeaderFilter>,
+    pub paused: bool,
+    pub delivery_timeout: Option<Duration>,
 }
 #[derive(Clone)]
 pub struct TopicSnapshot {
@@ -19,4 +32,29 @@ pub struct TopicSnapshot {
     pub queues: Vec<TopicQueueSnapshot>,
     pub persist: bool,
     pub deleted: i64,
+    /// `(message_id, deliver_at)` of the messages which are not due yet.
+    pub scheduled: Vec<(i64, i64)>,
+    pub ttl: Option<Duration>,
+    /// Dedup is on when either of these is set.
+    pub dedup_window: Option<Duration>,
+    pub dedup_max_keys: Option<usize>,
+    pub forward_rules: Vec<ForwardRuleSnapshot>,
+    pub router: Option<ContentRouterSnapshot>,
+    pub quotas: Quotas,
+    pub persist_compression: Option<PersistenceCompression>,
+}
+
+#[derive(Clone)]
+pub struct ForwardRuleSnapshot {
+    pub namespace: String,
+    pub topic_id: String,
+    pub filter: Option<HeaderFilter>,
+}
+
+#[derive(Clone)]
+pub struct ContentRouterSnapshot {
+    pub header: String,
+    /// `(header value, destination topic id)`
+    pub routes: Vec<(String, String)>,
+    pub fallback_topic_id: Option<String>,
 }
@@ -5,6 +5,8 @@ use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::validators::InvalidTopicName;
 use rust_extensions::sorted_vec::SortedVecOfArcWithStrKey;
 
+use crate::wal::{MessagesWal, TopicWal};
+
 use super::topic::Topic;
 
 #[derive(Clone)]
@@ -43,14 +45,17 @@ pub struct TopicsList {
     namespace: String,
     inner: ArcSwap<TopicsInner>,
     write_lock: Mutex<()>,
+    /// Every topic created here gets its own part of the log.
+    wal: Option<Arc<MessagesWal>>,
 }
 
 impl TopicsList {
-    pub fn new(namespace: String) -> Self {
+    pub fn new(namespace: String, wal: Option<Arc<MessagesWal>>) -> Self {
         Self {
             namespace,
             inner: ArcSwap::from_pointee(TopicsInner::empty()),
             write_lock: Mutex::new(()),
+            wal,
         }
     }
 
@@ -86,6 +91,7 @@ impl TopicsList {
             0,
             true,
             0,
+            self.get_topic_wal(topic_id),
         ));
         let mut new_sorted = current.sorted.clone();
         new_sorted.insert_or_replace(topic.clone());
@@ -114,6 +120,7 @@ impl TopicsList {
             message_id.get_value(),
             persist,
             deleted,
+            self.get_topic_wal(topic_id),
         ));
         let mut new_sorted = current.sorted.clone();
         new_sorted.insert_or_replace(topic.clone());
@@ -126,6 +133,12 @@ impl TopicsList {
         topic
     }
 
+    fn get_topic_wal(&self, topic_id: &str) -> Option<Arc<TopicWal>> {
+        self.wal
+            .as_ref()
+            .map(|wal| wal.get_topic_wal(self.namespace.as_str(), topic_id))
+    }
+
     pub fn delete_topic(&self, topic_id: &str) -> Option<Arc<Topic>> {
         let _guard = self.write_lock.lock().unwrap();
         let current = self.inner.load_full();
@@ -138,6 +151,18 @@ impl TopicsList {
             current.snapshot_id + 1,
         )));
 
+        if let Some(wal) = self.wal.as_ref() {
+            if let Err(err) = wal.delete_topic(self.namespace.as_str(), topic_id) {
+                my_logger::LOGGER.write_error(
+                    "delete_topic",
+                    format!("Can not delete write-ahead log of the topic: {:?}", err),
+                    my_logger::LogEventCtx::new()
+                        .add("namespace", self.namespace.as_str())
+                        .add("topicId", topic_id),
+                );
+            }
+        }
+
         Some(removed)
     }
 }
@@ -0,0 +1,28 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+/// Moment given by a human or a client: unix milliseconds or an RFC3339 date.
+pub fn parse_date_time(value: &str) -> Option<DateTimeAsMicroseconds> {
+    let value = value.trim();
+
+    if let Ok(unix_ms) = value.parse::<i64>() {
+        return Some(DateTimeAsMicroseconds::new(unix_ms.checked_mul(1000)?));
+    }
+
+    let date_time = chrono::DateTime::parse_from_rfc3339(value).ok()?;
+    Some(DateTimeAsMicroseconds::new(date_time.timestamp_micros()))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_unix_ms_which_overflows_is_not_a_date() {
+        assert_eq!(
+            super::parse_date_time("1700000000000")
+                .unwrap()
+                .unix_microseconds,
+            1_700_000_000_000_000
+        );
+
+        assert!(super::parse_date_time(i64::MAX.to_string().as_str()).is_none());
+    }
+}
@@ -0,0 +1,8 @@
+use my_service_bus::abstractions::SbMessageHeaders;
+
+pub fn get_header<'s>(headers: &'s SbMessageHeaders, key: &str) -> Option<&'s str> {
+    headers
+        .iter()
+        .find(|itm| itm.0 == key)
+        .map(|itm| itm.1.as_str())
+}
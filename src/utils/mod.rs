@@ -1,4 +1,12 @@
+mod date_time_parser;
 mod min_message_id_calculator;
 mod string_multi_threaded;
+pub use date_time_parser::*;
 pub use min_message_id_calculator::*;
 pub use string_multi_threaded::*;
+mod token_bucket;
+pub use token_bucket::*;
+mod protobuf_records;
+pub use protobuf_records::*;
+mod headers;
+pub use headers::*;
@@ -0,0 +1,31 @@
+/// Records of a file which is only ever appended to: a little-endian `u32`
+/// length followed by the protobuf message.
+pub fn write_protobuf_record(buffer: &mut Vec<u8>, record: &impl prost::Message) {
+    buffer.extend_from_slice(&(record.encoded_len() as u32).to_le_bytes());
+    record.encode(buffer).unwrap();
+}
+
+/// A record torn by a crash in the middle of a write can only be the last one,
+/// and it is dropped.
+pub fn read_protobuf_records<T: prost::Message + Default>(content: &[u8]) -> Vec<T> {
+    let mut result = Vec::new();
+    let mut position = 0;
+
+    while position + 4 <= content.len() {
+        let len = u32::from_le_bytes(content[position..position + 4].try_into().unwrap()) as usize;
+        position += 4;
+
+        if position + len > content.len() {
+            break;
+        }
+
+        match T::decode(&content[position..position + len]) {
+            Ok(record) => result.push(record),
+            Err(_) => break,
+        }
+
+        position += len;
+    }
+
+    result
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+/// Holds up to a second worth of tokens. Taking more than there is leaves the
+/// bucket in debt, which the ones coming next wait out.
+pub struct TokenBucket {
+    per_second: f64,
+    tokens: f64,
+    updated: DateTimeAsMicroseconds,
+}
+
+impl TokenBucket {
+    pub fn new(per_second: usize, now: DateTimeAsMicroseconds) -> Self {
+        Self {
+            per_second: per_second as f64,
+            tokens: per_second as f64,
+            updated: now,
+        }
+    }
+
+    pub fn refill(&mut self, now: DateTimeAsMicroseconds) {
+        let elapsed = now.unix_microseconds - self.updated.unix_microseconds;
+
+        if elapsed <= 0 {
+            return;
+        }
+
+        self.tokens += self.per_second * elapsed as f64 / 1_000_000.0;
+
+        if self.tokens > self.per_second {
+            self.tokens = self.per_second;
+        }
+
+        self.updated = now;
+    }
+
+    /// How long `amount` has to wait for its tokens. More than the bucket holds
+    /// only waits for a full bucket, otherwise it would never pass.
+    pub fn get_wait(&self, amount: usize) -> Duration {
+        let needed = (amount as f64).min(self.per_second);
+
+        if self.tokens >= needed {
+            return Duration::ZERO;
+        }
+
+        Duration::from_secs_f64((needed - self.tokens) / self.per_second)
+    }
+
+    pub fn take(&mut self, amount: usize) {
+        self.tokens -= amount as f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::TokenBucket;
+
+    #[test]
+    fn test_bucket_refills_with_time() {
+        let now = DateTimeAsMicroseconds::new(0);
+        let mut bucket = TokenBucket::new(100, now);
+
+        assert_eq!(Duration::ZERO, bucket.get_wait(100));
+        bucket.take(100);
+
+        assert_eq!(Duration::from_millis(500), bucket.get_wait(50));
+
+        bucket.refill(DateTimeAsMicroseconds::new(500_000));
+        assert_eq!(Duration::ZERO, bucket.get_wait(50));
+
+        bucket.refill(DateTimeAsMicroseconds::new(10_000_000));
+        assert_eq!(Duration::ZERO, bucket.get_wait(1000));
+    }
+}
@@ -0,0 +1,97 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::messages_page::MySbMessageContent;
+
+use super::{TopicWal, WAL_SEGMENT_EXTENSION};
+
+pub struct WalTopicMessages {
+    pub namespace: String,
+    pub topic_id: String,
+    pub messages: Vec<MySbMessageContent>,
+}
+
+/// Keeps published messages on disk until persistence has them, so a crash of
+/// the node does not lose what was still waiting to be persisted. Laid out as
+/// `{path}/{namespace}/{topic_id}/{sub_page_id}.wal`.
+pub struct MessagesWal {
+    path: PathBuf,
+}
+
+impl MessagesWal {
+    pub fn new(path: String) -> Self {
+        Self {
+            path: PathBuf::from(path),
+        }
+    }
+
+    pub fn get_topic_wal(&self, namespace: &str, topic_id: &str) -> Arc<TopicWal> {
+        Arc::new(TopicWal::new(self.get_topic_path(namespace, topic_id)))
+    }
+
+    pub fn delete_topic(&self, namespace: &str, topic_id: &str) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(self.get_topic_path(namespace, topic_id)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Everything the previous run left in the log, by topic and sorted by id.
+    pub fn read_all(&self) -> std::io::Result<Vec<WalTopicMessages>> {
+        let mut result = Vec::new();
+
+        if !self.path.exists() {
+            return Ok(result);
+        }
+
+        for namespace in std::fs::read_dir(&self.path)? {
+            let namespace = namespace?;
+
+            if !namespace.file_type()?.is_dir() {
+                continue;
+            }
+
+            for topic in std::fs::read_dir(namespace.path())? {
+                let topic = topic?;
+
+                if !topic.file_type()?.is_dir() {
+                    continue;
+                }
+
+                let mut messages = Vec::new();
+
+                for segment in std::fs::read_dir(topic.path())? {
+                    let segment_path = segment?.path();
+
+                    let is_segment = segment_path
+                        .extension()
+                        .map(|itm| itm == WAL_SEGMENT_EXTENSION)
+                        .unwrap_or(false);
+
+                    if is_segment {
+                        let content = std::fs::read(&segment_path)?;
+                        messages.extend(super::read_segment(&content));
+                    }
+                }
+
+                if messages.is_empty() {
+                    continue;
+                }
+
+                messages.sort_by_key(|itm| itm.id.get_value());
+                messages.dedup_by_key(|itm| itm.id.get_value());
+
+                result.push(WalTopicMessages {
+                    namespace: namespace.file_name().to_string_lossy().to_string(),
+                    topic_id: topic.file_name().to_string_lossy().to_string(),
+                    messages,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn get_topic_path(&self, namespace: &str, topic_id: &str) -> PathBuf {
+        self.path.join(namespace).join(topic_id)
+    }
+}
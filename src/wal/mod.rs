@@ -0,0 +1,5 @@
+mod messages_wal;
+mod topic_wal;
+
+pub use messages_wal::*;
+pub use topic_wal::*;
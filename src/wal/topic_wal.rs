@@ -0,0 +1,316 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use my_service_bus::shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId};
+use parking_lot::Mutex;
+
+use crate::{
+    messages_page::MySbMessageContent,
+    persistence_grpc::MessageContentGrpcModel,
+    utils::{read_protobuf_records, write_protobuf_record},
+};
+
+pub const WAL_SEGMENT_EXTENSION: &str = "wal";
+
+struct PendingRecords {
+    by_sub_page: BTreeMap<i64, Vec<u8>>,
+    /// Number of the last record appended.
+    last_no: u64,
+}
+
+struct WalSegments {
+    files: HashMap<i64, File>,
+    /// Number of the last record which is on the disk.
+    flushed_no: u64,
+}
+
+/// Write-ahead log of one topic: a segment per sub-page, a protobuf record per
+/// message. A segment is deleted once every message of its sub-page is persisted.
+///
+/// Records are appended under the lock of the topic, but only buffered there;
+/// the disk is written and synced by [`WalFlush`] once the topic is let go. One
+/// flush takes everything buffered by then, so publishers which wait together
+/// share a single sync.
+pub struct TopicWal {
+    path: PathBuf,
+    pending: Mutex<PendingRecords>,
+    segments: Mutex<WalSegments>,
+}
+
+impl TopicWal {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            pending: Mutex::new(PendingRecords {
+                by_sub_page: BTreeMap::new(),
+                last_no: 0,
+            }),
+            segments: Mutex::new(WalSegments {
+                files: HashMap::new(),
+                flushed_no: 0,
+            }),
+        }
+    }
+
+    /// Buffers the message; nothing reaches the disk before [`Self::flush`].
+    pub fn append(&self, sub_page_id: SubPageId, message: &MySbMessageContent) {
+        let model: MessageProtobufModel = message.into();
+        let model: MessageContentGrpcModel = model.into();
+
+        let mut pending = self.pending.lock();
+        pending.last_no += 1;
+
+        let buffer = pending
+            .by_sub_page
+            .entry(sub_page_id.get_value())
+            .or_default();
+        write_protobuf_record(buffer, &model);
+    }
+
+    /// What to wait for to have every record appended so far on the disk.
+    pub fn get_flush(self: &Arc<Self>) -> WalFlush {
+        WalFlush {
+            wal: self.clone(),
+            no: self.pending.lock().last_no,
+        }
+    }
+
+    /// Returns once the records up to `no` are on the disk. Blocks on the disk,
+    /// so it never runs under the lock of the topic.
+    ///
+    /// A segment which fails keeps its records buffered for the next flush, and
+    /// is cut back to where it was so the retry does not leave a torn record in
+    /// the middle of it.
+    pub fn flush(&self, no: u64) -> std::io::Result<()> {
+        let mut segments = self.segments.lock();
+
+        if segments.flushed_no >= no {
+            return Ok(());
+        }
+
+        let (mut to_write, last_no) = {
+            let mut pending = self.pending.lock();
+            (std::mem::take(&mut pending.by_sub_page), pending.last_no)
+        };
+
+        let sub_page_ids: Vec<i64> = to_write.keys().copied().collect();
+
+        for sub_page_id in sub_page_ids {
+            let buffer = to_write.get(&sub_page_id).unwrap();
+
+            if let Err(err) = self.flush_segment(&mut segments, sub_page_id, buffer) {
+                // Whatever came in meanwhile goes after what is given back.
+                let mut pending = self.pending.lock();
+
+                for (sub_page_id, mut buffer) in std::mem::take(&mut pending.by_sub_page) {
+                    to_write.entry(sub_page_id).or_default().append(&mut buffer);
+                }
+
+                pending.by_sub_page = to_write;
+
+                return Err(err);
+            }
+
+            to_write.remove(&sub_page_id);
+        }
+
+        segments.flushed_no = last_no;
+
+        Ok(())
+    }
+
+    fn flush_segment(
+        &self,
+        segments: &mut WalSegments,
+        sub_page_id: i64,
+        buffer: &[u8],
+    ) -> std::io::Result<()> {
+        if !segments.files.contains_key(&sub_page_id) {
+            std::fs::create_dir_all(&self.path)?;
+
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.get_segment_path(sub_page_id))?;
+
+            segments.files.insert(sub_page_id, file);
+        }
+
+        let file = segments.files.get_mut(&sub_page_id).unwrap();
+
+        let len = file.metadata()?.len();
+
+        let result = file.write_all(buffer).and_then(|_| file.sync_data());
+
+        if result.is_err() {
+            let _ = file.set_len(len);
+        }
+
+        result
+    }
+
+    /// Every message of the sub-page is persisted, so records of it which are
+    /// still buffered are dropped as well.
+    pub fn remove_segment(&self, sub_page_id: SubPageId) -> std::io::Result<()> {
+        let sub_page_id = sub_page_id.get_value();
+
+        let mut segments = self.segments.lock();
+        self.pending.lock().by_sub_page.remove(&sub_page_id);
+
+        segments.files.remove(&sub_page_id);
+
+        match std::fs::remove_file(self.get_segment_path(sub_page_id)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn get_segment_path(&self, sub_page_id: i64) -> PathBuf {
+        self.path
+            .join(format!("{}.{}", sub_page_id, WAL_SEGMENT_EXTENSION))
+    }
+}
+
+/// Records of the write-ahead log which the caller waits to be on the disk.
+pub struct WalFlush {
+    wal: Arc<TopicWal>,
+    no: u64,
+}
+
+impl WalFlush {
+    pub async fn wait(self) -> std::io::Result<()> {
+        let result = tokio::task::spawn_blocking(move || self.wal.flush(self.no)).await;
+
+        match result {
+            Ok(result) => result,
+            Err(err) => Err(std::io::Error::new(std::io::ErrorKind::Other, err)),
+        }
+    }
+
+    /// For the copies the broker makes itself: nobody waits for an ack, so a
+    /// failed write is only reported.
+    pub fn spawn(self, process: &'static str, topic_id: String) {
+        tokio::spawn(async move {
+            if let Err(err) = self.wait().await {
+                my_logger::LOGGER.write_error(
+                    process,
+                    format!("Can not write messages to the write-ahead log: {:?}", err),
+                    my_logger::LogEventCtx::new().add("topicId", topic_id.as_str()),
+                );
+            }
+        });
+    }
+}
+
+pub fn read_segment(content: &[u8]) -> Vec<MySbMessageContent> {
+    read_protobuf_records::<MessageContentGrpcModel>(content)
+        .into_iter()
+        .map(|itm| itm.into())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::{abstractions::SbMessageHeaders, shared::sub_page::SubPageId};
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::messages_page::MySbMessageContent;
+
+    use super::*;
+
+    fn create_message(id: i64) -> MySbMessageContent {
+        MySbMessageContent::new(
+            id.into(),
+            vec![1, 2, 3],
+            SbMessageHeaders::new().add("key".to_string(), "value".to_string()),
+            DateTimeAsMicroseconds::now(),
+        )
+    }
+
+    #[test]
+    fn test_segment_is_read_back_without_its_torn_tail() {
+        let path = std::env::temp_dir().join(format!(
+            "my-sb-wal-test-{}",
+            DateTimeAsMicroseconds::now().unix_microseconds
+        ));
+
+        let wal = TopicWal::new(path.clone());
+
+        wal.append(SubPageId::new(0), &create_message(1));
+        wal.append(SubPageId::new(0), &create_message(2));
+        wal.flush(2).unwrap();
+
+        let segment_path = path.join("0.wal");
+        let mut content = std::fs::read(&segment_path).unwrap();
+        content.extend_from_slice(&100u32.to_le_bytes());
+        content.push(1);
+
+        let messages = read_segment(&content);
+
+        assert_eq!(2, messages.len());
+        assert_eq!(2, messages[1].id.get_value());
+        assert_eq!(vec![1, 2, 3], messages[1].content);
+
+        wal.remove_segment(SubPageId::new(0)).unwrap();
+        assert!(!segment_path.exists());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_messages_which_failed_to_flush_are_flushed_next_time() {
+        let path = std::env::temp_dir().join(format!(
+            "my-sb-wal-fail-test-{}",
+            DateTimeAsMicroseconds::now().unix_microseconds
+        ));
+
+        // A file where the folder of the log should be makes the flush fail.
+        std::fs::write(&path, b"").unwrap();
+
+        let wal = TopicWal::new(path.clone());
+
+        wal.append(SubPageId::new(0), &create_message(1));
+        assert!(wal.flush(1).is_err());
+
+        wal.append(SubPageId::new(0), &create_message(2));
+
+        std::fs::remove_file(&path).unwrap();
+
+        wal.flush(2).unwrap();
+
+        let content = std::fs::read(path.join("0.wal")).unwrap();
+        let messages = read_segment(&content);
+        assert_eq!(2, messages.len());
+        assert_eq!(1, messages[0].id.get_value());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_one_flush_writes_every_record_appended_before_it() {
+        let path = std::env::temp_dir().join(format!(
+            "my-sb-wal-group-test-{}",
+            DateTimeAsMicroseconds::now().unix_microseconds
+        ));
+
+        let wal = Arc::new(TopicWal::new(path.clone()));
+
+        wal.append(SubPageId::new(0), &create_message(1));
+        let first = wal.get_flush();
+
+        wal.append(SubPageId::new(1), &create_message(2));
+        let second = wal.get_flush();
+
+        wal.flush(second.no).unwrap();
+
+        // Already on the disk: nothing is left to write.
+        std::fs::remove_dir_all(&path).unwrap();
+        wal.flush(first.no).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -2,6 +2,9 @@ use crate::models::{MySbHttpContract, NamespaceApiModel};
 
 const STATUS_PATH: &str = "/api/Status";
 const QUEUES_PATH: &str = "/api/Queues";
+const SEEK_QUEUE_BY_TIME_PATH: &str = "/api/Queues/SeekByTime";
+const SET_QUEUE_PAUSED_PATH: &str = "/api/Queues/SetPaused";
+const PURGE_QUEUE_PATH: &str = "/api/Queues/Purge";
 const DELETE_TOPIC_PATH: &str = "/api/Topics/Delete";
 const RESTORE_TOPIC_PATH: &str = "/api/Topics/Restore";
 const NAMESPACES_PATH: &str = "/api/Namespaces/List";
@@ -95,6 +98,71 @@ pub async fn delete_queue(topic_id: &str, queue_id: &str) -> Result<(), String>
     Ok(())
 }
 
+pub async fn seek_queue_by_time(
+    topic_id: &str,
+    queue_id: &str,
+    from_date_iso: &str,
+) -> Result<(), String> {
+    let origin = get_origin()?;
+    let topic_enc: String = js_sys::encode_uri_component(topic_id).into();
+    let queue_enc: String = js_sys::encode_uri_component(queue_id).into();
+    let from_enc: String = js_sys::encode_uri_component(from_date_iso).into();
+    let url = format!(
+        "{origin}{SEEK_QUEUE_BY_TIME_PATH}?topicId={topic_enc}&queueId={queue_enc}&fromDate={from_enc}"
+    );
+
+    let resp = request(reqwest::Method::POST, &url)
+        .send()
+        .await
+        .map_err(|e| format!("POST {url} failed: {e}"))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("POST {url} returned {}", resp.status()));
+    }
+
+    Ok(())
+}
+
+pub async fn set_queue_paused(topic_id: &str, queue_id: &str, paused: bool) -> Result<(), String> {
+    let origin = get_origin()?;
+    let topic_enc: String = js_sys::encode_uri_component(topic_id).into();
+    let queue_enc: String = js_sys::encode_uri_component(queue_id).into();
+    let url = format!(
+        "{origin}{SET_QUEUE_PAUSED_PATH}?topicId={topic_enc}&queueId={queue_enc}&paused={paused}"
+    );
+
+    let resp = request(reqwest::Method::POST, &url)
+        .send()
+        .await
+        .map_err(|e| format!("POST {url} failed: {e}"))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("POST {url} returned {}", resp.status()));
+    }
+
+    Ok(())
+}
+
+pub async fn purge_queue(topic_id: &str, queue_id: &str, keep_last: usize) -> Result<(), String> {
+    let origin = get_origin()?;
+    let topic_enc: String = js_sys::encode_uri_component(topic_id).into();
+    let queue_enc: String = js_sys::encode_uri_component(queue_id).into();
+    let url = format!(
+        "{origin}{PURGE_QUEUE_PATH}?topicId={topic_enc}&queueId={queue_enc}&keepLast={keep_last}"
+    );
+
+    let resp = request(reqwest::Method::POST, &url)
+        .send()
+        .await
+        .map_err(|e| format!("POST {url} failed: {e}"))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("POST {url} returned {}", resp.status()));
+    }
+
+    Ok(())
+}
+
 pub async fn delete_topic(topic_id: &str, hard_delete_moment_iso: &str) -> Result<(), String> {
     let origin = get_origin()?;
     let topic_enc: String = js_sys::encode_uri_component(topic_id).into();
@@ -3,10 +3,14 @@ use dioxus::prelude::*;
 mod delete_queue;
 mod delete_topic;
 mod dialog_template;
+mod purge_queue;
+mod seek_queue;
 
 pub use delete_queue::*;
 pub use delete_topic::*;
 pub use dialog_template::*;
+pub use purge_queue::*;
+pub use seek_queue::*;
 
 #[derive(Clone)]
 pub enum DialogState {
@@ -20,6 +24,17 @@ pub enum DialogState {
         queue_id: String,
         on_ok: EventHandler<()>,
     },
+    SeekQueue {
+        topic_id: String,
+        queue_id: String,
+        on_ok: EventHandler<String>,
+    },
+    PurgeQueue {
+        topic_id: String,
+        queue_id: String,
+        size: i64,
+        on_ok: EventHandler<usize>,
+    },
 }
 
 #[component]
@@ -37,5 +52,20 @@ pub fn RenderDialog() -> Element {
         } => rsx! {
             DeleteQueueDialog { topic_id, queue_id, on_ok }
         },
+        DialogState::SeekQueue {
+            topic_id,
+            queue_id,
+            on_ok,
+        } => rsx! {
+            SeekQueueDialog { topic_id, queue_id, on_ok }
+        },
+        DialogState::PurgeQueue {
+            topic_id,
+            queue_id,
+            size,
+            on_ok,
+        } => rsx! {
+            PurgeQueueDialog { topic_id, queue_id, size, on_ok }
+        },
     }
 }
@@ -0,0 +1,60 @@
+use dioxus::prelude::*;
+
+use crate::components::ui::{BtnVariant, Button};
+
+const DIALOG_TITLE: &str = "Purge queue";
+
+#[component]
+pub fn PurgeQueueDialog(
+    topic_id: String,
+    queue_id: String,
+    size: i64,
+    on_ok: EventHandler<usize>,
+) -> Element {
+    let mut keep_last = use_signal(String::new);
+    let label_topic = topic_id.clone();
+    let label_queue = queue_id.clone();
+
+    let content = rsx! {
+        p {
+            "Confirm to drop pending messages of queue "
+            b { "{label_topic}/{label_queue}" }
+            " ({size} messages)?"
+        }
+        div { style: "display:flex; align-items:center; gap:8px; margin: 12px 0;",
+            "Keep newest"
+            input {
+                class: "msb-input",
+                r#type: "number",
+                min: "0",
+                placeholder: "0",
+                value: "{keep_last}",
+                oninput: move |e| keep_last.set(e.value()),
+            }
+            "messages"
+        }
+    };
+
+    let ok_button = rsx! {
+        Button {
+            variant: BtnVariant::Danger,
+            onclick: move |_| {
+                // Only an empty field means keep nothing; a typo must not purge everything.
+                let value = keep_last.read().trim().to_string();
+                let keep_last = if value.is_empty() {
+                    0
+                } else {
+                    match value.parse::<usize>() {
+                        Ok(keep_last) => keep_last,
+                        Err(_) => return,
+                    }
+                };
+                consume_context::<Signal<super::DialogState>>().set(super::DialogState::None);
+                on_ok.call(keep_last);
+            },
+            "Purge"
+        }
+    };
+
+    super::dialog_template(DIALOG_TITLE, content, ok_button)
+}
@@ -0,0 +1,53 @@
+use dioxus::prelude::*;
+
+use crate::components::ui::{BtnVariant, Button};
+
+const DIALOG_TITLE: &str = "Seek queue";
+
+#[component]
+pub fn SeekQueueDialog(topic_id: String, queue_id: String, on_ok: EventHandler<String>) -> Element {
+    // `datetime-local` value in the browser's time zone, e.g. 2024-05-01T14:05.
+    let mut from_date = use_signal(String::new);
+    let label_topic = topic_id.clone();
+    let label_queue = queue_id.clone();
+
+    let content = rsx! {
+        p {
+            "Move queue "
+            b { "{label_topic}/{label_queue}" }
+            " to the first message created at or after:"
+        }
+        div { style: "margin: 12px 0;",
+            input {
+                class: "msb-input",
+                r#type: "datetime-local",
+                step: "1",
+                value: "{from_date}",
+                oninput: move |e| from_date.set(e.value()),
+            }
+        }
+        p { "Messages before it are dropped from the queue, messages after it are delivered again." }
+    };
+
+    let ok_button = rsx! {
+        Button {
+            variant: BtnVariant::Primary,
+            onclick: move |_| {
+                let value = from_date.read().clone();
+                if value.is_empty() {
+                    return;
+                }
+                let date = js_sys::Date::new(&value.into());
+                if date.get_time().is_nan() {
+                    return;
+                }
+                let iso: String = date.to_iso_string().into();
+                consume_context::<Signal<super::DialogState>>().set(super::DialogState::None);
+                on_ok.call(iso);
+            },
+            "Seek"
+        }
+    };
+
+    super::dialog_template(DIALOG_TITLE, content, ok_button)
+}
@@ -22,6 +22,37 @@ pub struct MySbHttpContract {
     /// window, reports.
     #[serde(rename = "mcpWritesRemainingSecs", default)]
     pub mcp_writes_remaining_secs: Option<u64>,
+
+    /// Publishers the rate limits held back during the last minute.
+    #[serde(default)]
+    pub throttled: Vec<ThrottledPublisherHttpModel>,
+
+    /// Present while writes to persistence fail.
+    #[serde(rename = "persistenceDegraded", default)]
+    pub persistence_degraded: Option<PersistenceDegradedHttpModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct PersistenceDegradedHttpModel {
+    #[serde(rename = "degradedSince")]
+    pub degraded_since: String,
+    pub failures: u32,
+    #[serde(rename = "circuitOpen", default)]
+    pub circuit_open: bool,
+    #[serde(rename = "nextAttempt", default)]
+    pub next_attempt: String,
+    #[serde(rename = "lastError", default)]
+    pub last_error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ThrottledPublisherHttpModel {
+    #[serde(rename = "sessionName", default)]
+    pub session_name: Option<String>,
+    #[serde(rename = "topicId", default)]
+    pub topic_id: Option<String>,
+    pub delayed: usize,
+    pub rejected: usize,
 }
 
 impl MySbHttpContract {
@@ -123,6 +154,9 @@ pub struct TopicHttpModel {
 
     #[serde(default)]
     pub deleted: i64,
+
+    #[serde(default)]
+    pub scheduled: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -171,6 +205,8 @@ pub struct TopicQueueModel {
     #[serde(rename = "onDelivery")]
     pub on_delivery: i64,
     pub data: Vec<QueueIntervalModel>,
+    #[serde(default)]
+    pub paused: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
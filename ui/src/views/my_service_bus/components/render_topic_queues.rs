@@ -87,6 +87,93 @@ pub fn render_topic_queues(
             rsx! {}
         };
 
+        let topic_id_owned = topic.id.clone();
+        let queue_id_owned = topic_queue.id.clone();
+
+        let seek_button = rsx! {
+            Button {
+                variant: BtnVariant::Ghost,
+                size: BtnSize::Xs,
+                onclick: move |_| {
+                    let t = topic_id_owned.clone();
+                    let q = queue_id_owned.clone();
+                    consume_context::<Signal<DialogState>>().set(DialogState::SeekQueue {
+                        topic_id: t.clone(),
+                        queue_id: q.clone(),
+                        on_ok: EventHandler::new(move |from_date: String| {
+                            let t = t.clone();
+                            let q = q.clone();
+                            spawn(async move {
+                                if let Err(err) =
+                                    crate::api::my_sb::seek_queue_by_time(&t, &q, &from_date).await
+                                {
+                                    dioxus_logger::tracing::error!("seek_queue_by_time failed: {err}");
+                                }
+                            });
+                        }),
+                    });
+                },
+                "Seek"
+            }
+        };
+
+        let topic_id_owned = topic.id.clone();
+        let queue_id_owned = topic_queue.id.clone();
+        let paused = topic_queue.paused;
+
+        let pause_button = rsx! {
+            Button {
+                variant: if paused { BtnVariant::OutlineSuccess } else { BtnVariant::Ghost },
+                size: BtnSize::Xs,
+                onclick: move |_| {
+                    let t = topic_id_owned.clone();
+                    let q = queue_id_owned.clone();
+                    spawn(async move {
+                        if let Err(err) = crate::api::my_sb::set_queue_paused(&t, &q, !paused).await {
+                            dioxus_logger::tracing::error!("set_queue_paused failed: {err}");
+                        }
+                    });
+                },
+                if paused { "Resume" } else { "Pause" }
+            }
+        };
+
+        let topic_id_owned = topic.id.clone();
+        let queue_id_owned = topic_queue.id.clone();
+        let queue_size = topic_queue.size;
+
+        let purge_button = if queue_size > 0 {
+            rsx! {
+                Button {
+                    variant: BtnVariant::OutlineDanger,
+                    size: BtnSize::Xs,
+                    onclick: move |_| {
+                        let t = topic_id_owned.clone();
+                        let q = queue_id_owned.clone();
+                        consume_context::<Signal<DialogState>>().set(DialogState::PurgeQueue {
+                            topic_id: t.clone(),
+                            queue_id: q.clone(),
+                            size: queue_size,
+                            on_ok: EventHandler::new(move |keep_last: usize| {
+                                let t = t.clone();
+                                let q = q.clone();
+                                spawn(async move {
+                                    if let Err(err) =
+                                        crate::api::my_sb::purge_queue(&t, &q, keep_last).await
+                                    {
+                                        dioxus_logger::tracing::error!("purge_queue failed: {err}");
+                                    }
+                                });
+                            }),
+                        });
+                    },
+                    "Purge"
+                }
+            }
+        } else {
+            rsx! {}
+        };
+
         let render_subs = topic
             .subscribers
             .iter()
@@ -100,6 +187,9 @@ pub fn render_topic_queues(
                 div { class: "msb-queue__head",
                     div { class: "msb-queue__title-row",
                         div { class: "msb-queue__title selectable", "{topic_queue.id}" }
+                        {pause_button}
+                        {seek_button}
+                        {purge_button}
                         {delete_button}
                     }
                     div { class: "msb-queue__badges",
@@ -112,6 +202,9 @@ pub fn render_topic_queues(
                         }
                         Badge { tone: delete_mode_tone, "{delete_mode_label}" }
                         Badge { tone: connect_mode_tone, "{connect_mode_label}" }
+                        if paused {
+                            Badge { tone: Tone::Warning, "paused" }
+                        }
                         Badge { tone: size_tone, mono: true,
                             "Sz {topic_queue.size}/{topic_queue.on_delivery}"
                         }
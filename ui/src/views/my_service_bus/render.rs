@@ -9,7 +9,9 @@ use crate::components::ui::{
     Topbar,
 };
 use crate::dialogs::{DialogState, RenderDialog};
-use crate::models::{MySbHttpContract, TopicHttpModel};
+use crate::models::{
+    MySbHttpContract, PersistenceDegradedHttpModel, ThrottledPublisherHttpModel, TopicHttpModel,
+};
 use crate::utils::{format_bytes, format_bytes_per_sec, format_unix_micros};
 
 use super::state::{MySbState, SidebarSection};
@@ -169,6 +171,8 @@ fn render_status_bar(data: &MySbHttpContract, cs_ra: &MySbState, is_live: bool)
             {status_live(is_live)}
             {render_debug_status(data.debug.as_deref())}
             {render_mcp_writes_status(data.mcp_writes_remaining_secs)}
+            {render_throttled_status(&data.throttled)}
+            {render_persistence_degraded_status(data.persistence_degraded.as_ref())}
             {render_problematic_status(count_problematic_queues(data))}
             {status_item("Sessions", &data.sessions.items.len().to_string(), StatusValueTone::Default)}
             {status_item("Persist", &bar.persist_queue.to_string(), persist_tone)}
@@ -229,6 +233,72 @@ fn render_mcp_writes_status(remaining_secs: Option<u64>) -> Element {
     }
 }
 
+/// Shown only while some publisher is being held back by the rate limits; the
+/// tooltip says who it is and how often it was delayed or rejected.
+fn render_throttled_status(throttled: &[ThrottledPublisherHttpModel]) -> Element {
+    if throttled.is_empty() {
+        return rsx! {};
+    }
+
+    let details = throttled
+        .iter()
+        .map(|itm| {
+            format!(
+                "{} -> {}: delayed {}, rejected {}",
+                itm.session_name.as_deref().unwrap_or("*"),
+                itm.topic_id.as_deref().unwrap_or("*"),
+                itm.delayed,
+                itm.rejected
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let count = throttled.len();
+
+    rsx! {
+        div { class: "msb-statusbar__item is-alert", title: "{details}",
+            span { class: "msb-statusbar__label", "Throttled" }
+            span { class: "msb-statusbar__value", "{count}" }
+        }
+    }
+}
+
+/// Messages keep piling up in the persist queue while this is shown; they are
+/// not lost, the node keeps them in memory and retries.
+fn render_persistence_degraded_status(degraded: Option<&PersistenceDegradedHttpModel>) -> Element {
+    let Some(degraded) = degraded else {
+        return rsx! {};
+    };
+
+    let since = degraded
+        .degraded_since
+        .get(11..19)
+        .unwrap_or(degraded.degraded_since.as_str());
+
+    let state = if degraded.circuit_open {
+        "circuit open"
+    } else {
+        "retrying"
+    };
+
+    let details = format!(
+        "Persistence degraded since {}\nFailed writes in a row: {} ({})\nNext attempt: {}\n{}",
+        degraded.degraded_since,
+        degraded.failures,
+        state,
+        degraded.next_attempt,
+        degraded.last_error
+    );
+
+    rsx! {
+        div { class: "msb-statusbar__item is-alert", title: "{details}",
+            span { class: "msb-statusbar__label", "Persistence degraded since" }
+            span { class: "msb-statusbar__value", "{since}" }
+        }
+    }
+}
+
 fn render_debug_status(debug: Option<&str>) -> Element {
     // Shows a red DEBUG badge while the broker's debug console is tracing a target,
     // so it is obvious the node is in debug mode.
@@ -456,6 +526,10 @@ fn render_topic_row(
                     } else {
                         span { class: "value is-muted", "NotPersisted" }
                     }
+                    if topic.scheduled > 0 {
+                        span { class: "label", "Scheduled" }
+                        span { class: "value", "{topic.scheduled}" }
+                    }
                 }
                 div { class: "msb-spark-card",
                     div { class: "msb-spark-row",